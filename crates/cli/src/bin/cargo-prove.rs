@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use sp1_cli::{
     commands::{
-        build::BuildCmd, build_toolchain::BuildToolchainCmd,
+        build::BuildCmd, build_toolchain::BuildToolchainCmd, circuits::CircuitsCmd,
         install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd, trace::TraceCmd,
         vkey::VkeyCmd,
     },
@@ -34,6 +34,7 @@ pub enum ProveCliCommands {
     InstallToolchain(InstallToolchainCmd),
     Trace(TraceCmd),
     Vkey(VkeyCmd),
+    Circuits(CircuitsCmd),
 }
 
 fn main() -> Result<()> {
@@ -47,5 +48,6 @@ fn main() -> Result<()> {
         ProveCliCommands::InstallToolchain(cmd) => cmd.run(),
         ProveCliCommands::Trace(cmd) => cmd.run(),
         ProveCliCommands::Vkey(cmd) => cmd.run(),
+        ProveCliCommands::Circuits(cmd) => cmd.run(),
     }
 }
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use sp1_sdk::install::{
+    circuit_artifacts_checksums, default_circuits_dir, import_circuit_artifacts,
+    verify_circuit_artifacts,
+};
+
+#[derive(Parser)]
+#[command(name = "circuits", about = "Manage the Groth16 and Plonk circuit artifacts.")]
+pub struct CircuitsCmd {
+    #[clap(subcommand)]
+    command: CircuitsCommands,
+}
+
+#[derive(Subcommand)]
+enum CircuitsCommands {
+    /// Import the circuit artifacts from a local tarball, verifying their checksums.
+    Import {
+        /// Path to the `.tar.gz` of the artifacts.
+        #[arg(long, required = true)]
+        tarball: PathBuf,
+
+        /// The artifacts type, `groth16` or `plonk`.
+        #[arg(long = "type", required = true)]
+        artifacts_type: String,
+
+        /// The directory under which the artifacts are stored. Defaults to `~/.sp1/circuits`.
+        #[arg(long)]
+        circuits_dir: Option<PathBuf>,
+    },
+    /// Verify the checksums of installed circuit artifacts.
+    Verify {
+        /// The artifacts type, `groth16` or `plonk`.
+        #[arg(long = "type", required = true)]
+        artifacts_type: String,
+
+        /// The directory of the artifacts. Defaults to the installed artifacts of the current
+        /// circuit version.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Print the checksums of the artifacts in a directory, as a manifest entry.
+    Checksums {
+        /// The directory of the artifacts.
+        #[arg(long, required = true)]
+        dir: PathBuf,
+    },
+}
+
+impl CircuitsCmd {
+    pub fn run(&self) -> Result<()> {
+        match &self.command {
+            CircuitsCommands::Import { tarball, artifacts_type, circuits_dir } => {
+                let circuits_dir = circuits_dir.clone().unwrap_or_else(default_circuits_dir);
+                let build_dir = import_circuit_artifacts(tarball, &circuits_dir, artifacts_type)?;
+                println!(
+                    "Imported {} circuit artifacts to {}",
+                    artifacts_type,
+                    build_dir.display()
+                );
+            }
+            CircuitsCommands::Verify { artifacts_type, dir } => {
+                let dir = dir.clone().unwrap_or_else(|| {
+                    default_circuits_dir().join(artifacts_type).join(sp1_sdk::SP1_CIRCUIT_VERSION)
+                });
+                verify_circuit_artifacts(&dir, artifacts_type)?;
+                println!("Verified {} circuit artifacts at {}", artifacts_type, dir.display());
+            }
+            CircuitsCommands::Checksums { dir } => {
+                for (file, checksum) in circuit_artifacts_checksums(dir)? {
+                    println!("\"{}\" = \"{}\"", file, checksum);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
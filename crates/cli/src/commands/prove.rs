@@ -64,6 +64,10 @@ pub struct ProveCmd {
     #[clap(long, action)]
     verbose: bool,
 
+    /// Recompute the proving key instead of loading it from the key cache.
+    #[clap(long, action)]
+    no_key_cache: bool,
+
     #[clap(flatten)]
     build_args: BuildArgs,
 }
@@ -117,7 +121,8 @@ impl ProveCmd {
 
         let start_time = Instant::now();
         let client = ProverClient::new();
-        let (pk, _) = client.setup(&elf);
+        let (pk, _) =
+            if self.no_key_cache { client.setup(&elf) } else { client.setup_cached(&elf) };
         let proof = client.prove(&pk, stdin).run().unwrap();
 
         if let Some(ref path) = self.output {
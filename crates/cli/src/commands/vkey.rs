@@ -11,6 +11,10 @@ pub struct VkeyCmd {
     /// Path to the ELF.
     #[arg(long, required = true)]
     elf: String,
+
+    /// Recompute the verification key instead of loading it from the key cache.
+    #[arg(long)]
+    no_key_cache: bool,
 }
 
 impl VkeyCmd {
@@ -22,7 +26,8 @@ impl VkeyCmd {
 
         // Get the verification key
         let prover = ProverClient::new();
-        let (_, vk) = prover.setup(&elf);
+        let (_, vk) =
+            if self.no_key_cache { prover.setup(&elf) } else { prover.setup_cached(&elf) };
 
         // Print the verification key hash
         println!("Verification Key Hash:\n{}", vk.vk.bytes32());
@@ -0,0 +1,214 @@
+//! Serialization codecs for the input and public values streams.
+//!
+//! By default, values are read from the input stream and committed to the public values stream
+//! with `bincode`. The codecs in this module can be selected per call (e.g. with
+//! `sp1_zkvm::io::commit_with::<Abi, _>` in the guest and `SP1PublicValues::read_with::<Abi, _>`
+//! on the host) to use an encoding that is stable across versions or readable by non-Rust
+//! consumers.
+//!
+//! The codecs are shared by the guest library `sp1-lib` and the host crates, so this crate has no
+//! dependency on either. For the same reason, it also holds the [`merkle`] commitment used for
+//! large outputs.
+
+use std::fmt;
+
+pub mod merkle;
+
+#[cfg(feature = "abi")]
+pub use alloy_sol_types;
+
+/// An error that occurred while encoding or decoding a value with a [`Codec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodecError(pub String);
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "codec error: {}", self.0)
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// A serialization format for values of type `T`.
+///
+/// Encodings must be self-delimiting, so that several values can be written back to back into the
+/// public values stream and decoded in order.
+pub trait Codec<T> {
+    /// Encode `value` into bytes.
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError>;
+
+    /// Decode a value from the start of `bytes`, returning it with the number of bytes consumed.
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError>;
+}
+
+/// The `bincode` 1 codec, which is the default for `read`, `commit` and `write`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bincode;
+
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Codec<T> for Bincode {
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        bincode::serialize(value).map_err(|e| CodecError(e.to_string()))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        let value: T = bincode::deserialize(bytes).map_err(|e| CodecError(e.to_string()))?;
+        let size = bincode::serialized_size(&value).map_err(|e| CodecError(e.to_string()))?;
+        Ok((value, size as usize))
+    }
+}
+
+/// The `postcard` codec, a compact and stable serde format.
+#[cfg(feature = "postcard")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Codec<T> for Postcard {
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        postcard::to_allocvec(value).map_err(|e| CodecError(e.to_string()))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        let (value, rest) =
+            postcard::take_from_bytes(bytes).map_err(|e| CodecError(e.to_string()))?;
+        Ok((value, bytes.len() - rest.len()))
+    }
+}
+
+/// The `borsh` codec, a deterministic binary format with a formal specification.
+#[cfg(feature = "borsh")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Borsh;
+
+#[cfg(feature = "borsh")]
+impl<T: borsh::BorshSerialize + borsh::BorshDeserialize> Codec<T> for Borsh {
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        borsh::to_vec(value).map_err(|e| CodecError(e.to_string()))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        let mut rest = bytes;
+        let value = T::deserialize(&mut rest).map_err(|e| CodecError(e.to_string()))?;
+        Ok((value, bytes.len() - rest.len()))
+    }
+}
+
+/// The Ethereum ABI codec.
+///
+/// Values are encoded with `abi.encode`, so public values committed with this codec can be decoded
+/// in Solidity with `abi.decode(publicValues, (T))`.
+#[cfg(feature = "abi")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Abi;
+
+#[cfg(feature = "abi")]
+impl<T> Codec<T> for Abi
+where
+    T: alloy_sol_types::SolValue + From<<T::SolType as alloy_sol_types::SolType>::RustType>,
+{
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        Ok(value.abi_encode())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        let value = T::abi_decode(bytes, false).map_err(|e| CodecError(e.to_string()))?;
+        // The ABI encoding is canonical, so the number of bytes consumed is the length of the
+        // re-encoded value.
+        let size = value.abi_encode().len();
+        Ok((value, size))
+    }
+}
+
+/// A struct committed as ABI-encoded public values, with a matching Solidity definition.
+///
+/// This trait is implemented with `#[derive(SP1PublicValues)]` from `sp1-derive`, which also
+/// implements [`Codec`] for [`Abi`] so that the struct can be committed with
+/// `sp1_zkvm::io::commit_with::<Abi, _>` and read with `SP1PublicValues::read_with::<Abi, _>`.
+#[cfg(feature = "abi")]
+pub trait SolidityPublicValues {
+    /// The name of the Solidity struct.
+    const SOLIDITY_NAME: &'static str;
+
+    /// The Solidity type and name of each field, in declaration order.
+    fn solidity_fields() -> Vec<(&'static str, &'static str)>;
+
+    /// The Solidity source of the struct and of a library that decodes it from public values.
+    fn solidity_library() -> String {
+        let name = Self::SOLIDITY_NAME;
+        let fields = Self::solidity_fields()
+            .into_iter()
+            .map(|(ty, field)| format!("    {ty} {field};\n"))
+            .collect::<String>();
+        format!(
+            "// SPDX-License-Identifier: MIT\n\
+             pragma solidity ^0.8.20;\n\n\
+             struct {name} {{\n{fields}}}\n\n\
+             library {name}Lib {{\n    \
+             /// @notice Decodes the public values committed by the SP1 program.\n    \
+             function decode(bytes calldata publicValues) internal pure returns ({name} memory) {{\n        \
+             return abi.decode(publicValues, ({name}));\n    \
+             }}\n\
+             }}\n"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sample = (u32, String, Vec<u8>);
+
+    fn samples() -> Vec<Sample> {
+        vec![(0, String::new(), vec![]), (u32::MAX, "sp1".to_string(), vec![1, 2, 3])]
+    }
+
+    /// Checks that each of `values` round-trips through `C`, and that the values can be decoded in
+    /// order from their concatenated encodings.
+    fn check_codec<C: Codec<T>, T: PartialEq + fmt::Debug>(values: &[T]) {
+        let mut bytes = Vec::new();
+        for value in values {
+            let encoded = C::encode(value).unwrap();
+            let (decoded, size) = C::decode(&encoded).unwrap();
+            assert_eq!(&decoded, value);
+            assert_eq!(size, encoded.len());
+            bytes.extend(encoded);
+        }
+
+        let mut rest = bytes.as_slice();
+        for value in values {
+            let (decoded, size) = C::decode(rest).unwrap();
+            assert_eq!(&decoded, value);
+            rest = &rest[size..];
+        }
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_bincode() {
+        check_codec::<Bincode, _>(&samples());
+        assert!(<Bincode as Codec<Sample>>::decode(&[1, 2]).is_err());
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn test_postcard() {
+        check_codec::<Postcard, _>(&samples());
+        assert!(<Postcard as Codec<Sample>>::decode(&[]).is_err());
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_borsh() {
+        check_codec::<Borsh, _>(&samples());
+        assert!(<Borsh as Codec<Sample>>::decode(&[1, 2]).is_err());
+    }
+
+    #[cfg(feature = "abi")]
+    #[test]
+    fn test_abi() {
+        check_codec::<Abi, _>(&[(0u64, false), (u64::MAX, true)]);
+        check_codec::<Abi, _>(&[String::new(), "sp1".to_string()]);
+        assert!(<Abi as Codec<(u64, bool)>>::decode(&[0; 16]).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Keccak-256 Merkle trees over large program outputs.
+//!
+//! Instead of committing a large output vector to the public values, a program can commit only
+//! the Merkle root of its entries with `sp1_zkvm::io::commit_merkle_leaves`. The entries are
+//! returned to the host out-of-band in `SP1PublicValues::merkle_leaves`, and individual entries
+//! can be proven against the committed root with [`MerkleProof`].
+//!
+//! Leaves are hashed as `keccak256(0x00 || data)` and internal nodes as
+//! `keccak256(0x01 || left || right)`. A node without a sibling is promoted to the next level
+//! unchanged, and the top of an empty tree is zero. The committed root binds the number of leaves
+//! to the top of the tree as `keccak256(0x02 || num_leaves || top)`, with `num_leaves` as a
+//! big-endian `u64`, so that a proof cannot claim a different tree shape.
+//!
+//! The leaves are sent to the host in a single buffer encoded with [`encode_leaves`], so that
+//! empty leaves are not lost.
+
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+/// The domain separator for leaf hashes.
+const LEAF_PREFIX: u8 = 0x00;
+
+/// The domain separator for internal node hashes.
+const NODE_PREFIX: u8 = 0x01;
+
+/// The domain separator for the root hash.
+const ROOT_PREFIX: u8 = 0x02;
+
+/// Hashes a leaf of the tree.
+pub fn hash_leaf(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(data);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Hashes two child nodes into their parent node.
+pub fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Hashes the number of leaves and the top of the tree into the committed root.
+pub fn hash_root(num_leaves: usize, top: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(&[ROOT_PREFIX]);
+    hasher.update(&(num_leaves as u64).to_be_bytes());
+    hasher.update(top);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Computes the next level of the tree from the given level.
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [node] => *node,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Computes the Merkle root of the given leaves.
+pub fn merkle_root<T: AsRef<[u8]>>(leaves: &[T]) -> [u8; 32] {
+    let mut level = leaves.iter().map(|leaf| hash_leaf(leaf.as_ref())).collect::<Vec<_>>();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    let top = level.first().copied().unwrap_or([0u8; 32]);
+    hash_root(leaves.len(), &top)
+}
+
+/// Encodes the leaves into the buffer the guest writes to the Merkle leaves file descriptor.
+///
+/// Each leaf is prefixed with its length, so empty leaves are preserved, and the buffer is never
+/// empty, even without leaves.
+pub fn encode_leaves<T: AsRef<[u8]>>(leaves: &[T]) -> Vec<u8> {
+    let leaves = leaves.iter().map(AsRef::as_ref).collect::<Vec<&[u8]>>();
+    bincode::serialize(&leaves).expect("serialization failed")
+}
+
+/// Decodes leaves encoded with [`encode_leaves`].
+///
+/// Returns `None` if `bytes` is not a valid encoding.
+pub fn decode_leaves(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    bincode::deserialize(bytes).ok()
+}
+
+/// An inclusion proof of a leaf in a Merkle tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// The index of the leaf in the tree.
+    pub index: usize,
+    /// The number of leaves in the tree.
+    pub num_leaves: usize,
+    /// The sibling hashes from the leaf level up to the root, skipping levels where the node on
+    /// the path has no sibling.
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// Generates the inclusion proof of the leaf at `index`.
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    pub fn generate<T: AsRef<[u8]>>(leaves: &[T], index: usize) -> Option<Self> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let mut level = leaves.iter().map(|leaf| hash_leaf(leaf.as_ref())).collect::<Vec<_>>();
+        let mut siblings = Vec::new();
+        let mut position = index;
+        while level.len() > 1 {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            level = next_level(&level);
+            position /= 2;
+        }
+        Some(Self { index, num_leaves: leaves.len(), siblings })
+    }
+
+    /// Verifies that `leaf` is included in the tree with the given root.
+    pub fn verify(&self, root: &[u8; 32], leaf: &[u8]) -> bool {
+        if self.index >= self.num_leaves {
+            return false;
+        }
+        let mut node = hash_leaf(leaf);
+        let mut siblings = self.siblings.iter();
+        let mut position = self.index;
+        let mut level_len = self.num_leaves;
+        while level_len > 1 {
+            if position ^ 1 < level_len {
+                let Some(sibling) = siblings.next() else {
+                    return false;
+                };
+                node = if position % 2 == 0 {
+                    hash_node(&node, sibling)
+                } else {
+                    hash_node(sibling, &node)
+                };
+            }
+            position /= 2;
+            level_len = level_len.div_ceil(2);
+        }
+        siblings.next().is_none() && hash_root(self.num_leaves, &node) == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_leaves() {
+        let leaves: Vec<Vec<u8>> = vec![vec![], vec![1, 2, 3], vec![], vec![4; 5]];
+        assert_eq!(decode_leaves(&encode_leaves(&leaves)), Some(leaves));
+
+        let no_leaves = encode_leaves::<Vec<u8>>(&[]);
+        assert!(!no_leaves.is_empty());
+        assert_eq!(decode_leaves(&no_leaves), Some(vec![]));
+        assert_eq!(decode_leaves(&[1, 2, 3]), None);
+    }
+}
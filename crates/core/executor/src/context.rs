@@ -5,6 +5,7 @@ use hashbrown::HashMap;
 
 use crate::{
     hook::{hookify, BoxedHook, HookEnv, HookRegistry},
+    progress::{CancellationToken, ProgressListener, ProgressMonitor},
     subproof::SubproofVerifier,
 };
 
@@ -21,6 +22,9 @@ pub struct SP1Context<'a> {
 
     /// The maximum number of cpu cycles to use for execution.
     pub max_cycles: Option<u64>,
+
+    /// The progress listener and cancellation token used while proving.
+    pub progress: ProgressMonitor,
 }
 
 /// A builder for [`SP1Context`].
@@ -30,6 +34,7 @@ pub struct SP1ContextBuilder<'a> {
     hook_registry_entries: Vec<(u32, BoxedHook<'a>)>,
     subproof_verifier: Option<Arc<dyn SubproofVerifier + 'a>>,
     max_cycles: Option<u64>,
+    progress: ProgressMonitor,
 }
 
 impl<'a> SP1Context<'a> {
@@ -68,7 +73,8 @@ impl<'a> SP1ContextBuilder<'a> {
             });
         let subproof_verifier = take(&mut self.subproof_verifier);
         let cycle_limit = take(&mut self.max_cycles);
-        SP1Context { hook_registry, subproof_verifier, max_cycles: cycle_limit }
+        let progress = take(&mut self.progress);
+        SP1Context { hook_registry, subproof_verifier, max_cycles: cycle_limit, progress }
     }
 
     /// Add a runtime [Hook](super::Hook) into the context.
@@ -110,21 +116,40 @@ impl<'a> SP1ContextBuilder<'a> {
         self.max_cycles = Some(max_cycles);
         self
     }
+
+    /// Set the token used to cancel proof generation.
+    pub fn cancellation_token(&mut self, token: CancellationToken) -> &mut Self {
+        self.progress.cancellation_token = Some(token);
+        self
+    }
+
+    /// Set the listener notified of the phases of proof generation.
+    pub fn progress_listener(&mut self, listener: Arc<dyn ProgressListener>) -> &mut Self {
+        self.progress.listener = Some(listener);
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use crate::{subproof::DefaultSubproofVerifier, SP1Context};
+    use std::sync::Mutex;
+
+    use crate::{
+        subproof::DefaultSubproofVerifier, CancellationToken, ProofCancelled, ProofPhase,
+        SP1Context,
+    };
 
     #[test]
     fn defaults() {
-        let SP1Context { hook_registry, subproof_verifier, max_cycles: cycle_limit } =
+        let SP1Context { hook_registry, subproof_verifier, max_cycles: cycle_limit, progress } =
             SP1Context::builder().build();
         assert!(hook_registry.is_none());
         assert!(subproof_verifier.is_none());
         assert!(cycle_limit.is_none());
+        assert!(progress.cancellation_token.is_none());
+        assert!(progress.listener.is_none());
     }
 
     #[test]
@@ -155,4 +180,25 @@ mod tests {
             .build();
         assert!(subproof_verifier.is_some());
     }
+
+    #[test]
+    fn progress() {
+        let token = CancellationToken::new();
+        let phases = Arc::new(Mutex::new(Vec::new()));
+        let listener = {
+            let phases = phases.clone();
+            move |phase| phases.lock().unwrap().push(phase)
+        };
+        let SP1Context { progress, .. } = SP1Context::builder()
+            .cancellation_token(token.clone())
+            .progress_listener(Arc::new(listener))
+            .build();
+
+        progress.report(ProofPhase::Execute);
+        assert_eq!(*phases.lock().unwrap(), vec![ProofPhase::Execute]);
+
+        assert_eq!(progress.check_cancelled(), Ok(()));
+        token.cancel();
+        assert_eq!(progress.check_cancelled(), Err(ProofCancelled));
+    }
 }
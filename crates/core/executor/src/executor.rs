@@ -1703,7 +1703,12 @@ mod tests {
         ssz_withdrawals_program,
     };
 
-    use crate::Register;
+    use sp1_primitives::{
+        consts::FD_MERKLE_LEAVES,
+        merkle::{encode_leaves, merkle_root},
+    };
+
+    use crate::{syscalls::SyscallCode, Register};
 
     use super::{Executor, Instruction, Opcode, Program};
 
@@ -2306,4 +2311,29 @@ mod tests {
         assert_eq!(runtime.register(Register::X12), 0x12346525);
         assert_eq!(runtime.register(Register::X11), 0x65256525);
     }
+
+    #[test]
+    fn test_merkle_leaves_write() {
+        // The leaves are written in a single encoded buffer, so empty leaves reach the host.
+        let leaves: Vec<Vec<u8>> = vec![vec![1, 2, 3], vec![], vec![4; 5], vec![]];
+        let bytes = encode_leaves(&leaves);
+        let buf_addr = 0x10000;
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE.syscall_id(), false, true),
+            Instruction::new(Opcode::ADD, 10, 0, FD_MERKLE_LEAVES, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, buf_addr, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, bytes.len() as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        for (i, chunk) in bytes.chunks(4).enumerate() {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            program.memory_image.insert(buf_addr + 4 * i as u32, u32::from_le_bytes(word));
+        }
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.state.merkle_leaves_stream, leaves);
+        assert_eq!(merkle_root(&runtime.state.merkle_leaves_stream), merkle_root(&leaves));
+    }
 }
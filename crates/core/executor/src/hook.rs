@@ -232,6 +232,7 @@ pub mod tests {
     pub fn hook_fds_match() {
         use sp1_zkvm::lib::io;
         assert_eq!(FD_ECRECOVER_HOOK, io::FD_ECRECOVER_HOOK);
+        assert_eq!(sp1_primitives::consts::FD_MERKLE_LEAVES, io::FD_MERKLE_LEAVES);
     }
 
     #[test]
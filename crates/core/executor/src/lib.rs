@@ -32,6 +32,7 @@ mod opcode;
 mod program;
 #[cfg(any(test, feature = "programs"))]
 pub mod programs;
+mod progress;
 mod record;
 mod reduce;
 mod register;
@@ -48,6 +49,7 @@ pub use hook::*;
 pub use instruction::*;
 pub use opcode::*;
 pub use program::*;
+pub use progress::*;
 pub use record::*;
 pub use reduce::*;
 pub use register::*;
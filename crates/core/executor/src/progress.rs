@@ -0,0 +1,118 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use thiserror::Error;
+
+/// A token that cooperatively cancels proof generation.
+///
+/// Clones of a token share their state, so a token can be cancelled from any thread while a proof
+/// is being generated. The prover checks the token at shard and recursion layer boundaries.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new token that is not cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the proof generation observing this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A phase of proof generation, reported to a [`ProgressListener`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofPhase {
+    /// The program is being executed.
+    Execute,
+    /// The `index`-th core shard proof, counting from one, out of `num_shards` has been generated.
+    CoreShard {
+        /// The number of core shard proofs generated so far.
+        index: usize,
+        /// The total number of core shards.
+        num_shards: usize,
+    },
+    /// A layer of the recursion tree that compresses the core shard proofs is being proven.
+    CompressLayer {
+        /// The height of the layer, where layer zero proves the core shard proofs.
+        layer: usize,
+    },
+    /// The compressed proof is being shrunk.
+    Shrink,
+    /// The shrunk proof is being wrapped into a proof over the BN254 scalar field.
+    Wrap,
+    /// The wrapped proof is being proven with gnark.
+    Gnark,
+}
+
+/// A listener that is notified as proof generation moves through its phases.
+///
+/// Listeners are called from the prover's worker threads and should return quickly.
+pub trait ProgressListener: Send + Sync {
+    /// Called when proof generation enters `phase`.
+    fn on_phase(&self, phase: ProofPhase);
+}
+
+impl<F: Fn(ProofPhase) + Send + Sync> ProgressListener for F {
+    fn on_phase(&self, phase: ProofPhase) {
+        self(phase);
+    }
+}
+
+/// The error returned when proof generation is cancelled with a [`CancellationToken`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("proof generation was cancelled")]
+pub struct ProofCancelled;
+
+/// The progress listener and cancellation token of a proof.
+#[derive(Clone, Default)]
+pub struct ProgressMonitor {
+    /// The token used to cancel proof generation.
+    pub cancellation_token: Option<CancellationToken>,
+    /// The listener notified of the phases of proof generation.
+    pub listener: Option<Arc<dyn ProgressListener>>,
+}
+
+impl ProgressMonitor {
+    /// Notify the listener, if any, that proof generation entered `phase`.
+    pub fn report(&self, phase: ProofPhase) {
+        if let Some(listener) = &self.listener {
+            listener.on_phase(phase);
+        }
+    }
+
+    /// Whether proof generation has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Return an error if proof generation has been cancelled.
+    pub fn check_cancelled(&self) -> Result<(), ProofCancelled> {
+        if self.is_cancelled() {
+            Err(ProofCancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl std::fmt::Debug for ProgressMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressMonitor")
+            .field("cancellation_token", &self.cancellation_token)
+            .field("listener", &self.listener.is_some())
+            .finish()
+    }
+}
@@ -60,6 +60,10 @@ pub struct ExecutionState {
     /// `public_values_stream`.
     pub public_values_stream_ptr: usize,
 
+    /// A stream of leaves committed through a Merkle root in the public values, returned to the
+    /// host out-of-band.
+    pub merkle_leaves_stream: Vec<Vec<u8>>,
+
     /// Keeps track of how many times a certain syscall has been called.
     pub syscall_counts: HashMap<SyscallCode, u64>,
 }
@@ -80,6 +84,7 @@ impl ExecutionState {
             input_stream_ptr: 0,
             public_values_stream: Vec::new(),
             public_values_stream_ptr: 0,
+            merkle_leaves_stream: Vec::new(),
             proof_stream: Vec::new(),
             proof_stream_ptr: 0,
             syscall_counts: HashMap::new(),
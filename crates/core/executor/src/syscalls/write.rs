@@ -1,4 +1,7 @@
-use sp1_primitives::consts::num_to_comma_separated;
+use sp1_primitives::{
+    consts::{num_to_comma_separated, FD_MERKLE_LEAVES},
+    merkle::decode_leaves,
+};
 
 use crate::{Executor, Register};
 
@@ -25,6 +28,9 @@ impl Syscall for WriteSyscall {
     ///
     /// If the fd matches a hook in the hook registry, invoke the hook.
     ///
+    /// If fd = `FD_MERKLE_LEAVES`:
+    /// - Decode the leaves into the Merkle leaves stream.
+    ///
     /// Else, log a warning.
     #[allow(clippy::pedantic)]
     fn execute(
@@ -69,6 +75,11 @@ impl Syscall for WriteSyscall {
             // Add result vectors to the beginning of the stream.
             let ptr = rt.state.input_stream_ptr;
             rt.state.input_stream.splice(ptr..ptr, res);
+        } else if fd == FD_MERKLE_LEAVES {
+            match decode_leaves(slice) {
+                Some(leaves) => rt.state.merkle_leaves_stream = leaves,
+                None => tracing::warn!("tried to write malformed Merkle leaves"),
+            }
         } else {
             tracing::warn!("tried to write to unknown file descriptor {fd}");
         }
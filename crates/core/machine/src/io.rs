@@ -1,5 +1,6 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sp1_core_executor::SP1ReduceProof;
+use sp1_primitives::codec::Codec;
 use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkVerifyingKey};
 
 /// Standard input for the prover.
@@ -31,6 +32,13 @@ impl SP1Stdin {
         result
     }
 
+    /// Read a value from the buffer, decoding it with the codec `C`.
+    pub fn read_with<C: Codec<T>, T>(&mut self) -> T {
+        let (result, _) = C::decode(&self.buffer[self.ptr]).expect("failed to deserialize");
+        self.ptr += 1;
+        result
+    }
+
     /// Read a slice of bytes from the buffer.
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         slice.copy_from_slice(&self.buffer[self.ptr]);
@@ -44,11 +52,40 @@ impl SP1Stdin {
         self.buffer.push(tmp);
     }
 
+    /// Write a value to the buffer, encoding it with the codec `C`.
+    ///
+    /// The program should read it with `sp1_zkvm::io::read_with` and the same codec.
+    pub fn write_with<C: Codec<T>, T>(&mut self, data: &T) {
+        let tmp = C::encode(data).expect("serialization failed");
+        self.buffer.push(tmp);
+    }
+
     /// Write a slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.buffer.push(slice.to_vec());
     }
 
+    /// Write a seed for the zkVM random number generator to the buffer.
+    ///
+    /// The guest should consume it with `sp1_zkvm::io::read_rng_seed`, after which all randomness
+    /// in the program is a deterministic function of `seed`.
+    pub fn write_rng_seed(&mut self, seed: [u8; 32]) {
+        self.write_slice(&seed);
+    }
+
+    /// Write a value to the buffer in the rkyv archive format.
+    ///
+    /// The guest can access the value in place with `sp1_zkvm::io::read_archived`, without copying
+    /// or deserializing it. The host and guest must enable the same `rkyv` format features.
+    #[cfg(feature = "rkyv")]
+    pub fn write_archived<T>(&mut self, data: &T)
+    where
+        T: rkyv::Serialize<rkyv::ser::serializers::AllocSerializer<256>>,
+    {
+        let bytes = rkyv::to_bytes::<_, 256>(data).expect("serialization failed");
+        self.buffer.push(bytes.into_vec());
+    }
+
     pub fn write_vec(&mut self, vec: Vec<u8>) {
         self.buffer.push(vec);
     }
@@ -98,3 +135,86 @@ pub mod proof_serde {
         }
     }
 }
+
+#[cfg(all(test, feature = "rkyv"))]
+mod tests {
+    use sp1_core_executor::{syscalls::SyscallCode, Executor, Instruction, Opcode, Program};
+    use sp1_stark::SP1CoreOpts;
+
+    use super::*;
+
+    #[derive(rkyv::Archive, rkyv::Serialize)]
+    #[archive(check_bytes)]
+    struct Point {
+        x: u32,
+        y: u32,
+        label: Vec<u8>,
+    }
+
+    #[derive(rkyv::Archive, rkyv::Serialize)]
+    #[archive(check_bytes)]
+    struct Rgb {
+        r: u8,
+        g: u8,
+        b: u8,
+    }
+
+    /// Executes a program that reads the first input into an aligned buffer the way
+    /// `sp1_zkvm::io::read_archived` does, and commits the bytes it sees.
+    fn execute_read_archived(stdin: &SP1Stdin) -> rkyv::AlignedVec {
+        let buf_addr = 0x10000;
+        let len = stdin.buffer[0].len() as u32;
+        let mut instructions = vec![
+            // let len = syscall_hint_len();
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HINT_LEN.syscall_id(), false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 12, 5, 0, false, true),
+            // syscall_hint_read(buf, len);
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HINT_READ.syscall_id(), false, true),
+            Instruction::new(Opcode::ADD, 10, 0, buf_addr, false, true),
+            Instruction::new(Opcode::ADD, 11, 12, 0, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        // Load every word of the buffer, including the partially filled last one.
+        for i in 0..len.div_ceil(4) {
+            instructions.push(Instruction::new(Opcode::LW, 6, 0, buf_addr + 4 * i, false, true));
+        }
+        // sp1_zkvm::io::commit_slice(&buf[..len]);
+        instructions.extend([
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE.syscall_id(), false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, buf_addr, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.write_vecs(&stdin.buffer);
+        runtime.run().unwrap();
+
+        let mut bytes = rkyv::AlignedVec::new();
+        bytes.extend_from_slice(&runtime.state.public_values_stream);
+        bytes
+    }
+
+    #[test]
+    fn test_read_archived() {
+        let mut stdin = SP1Stdin::new();
+        stdin.write_archived(&Point { x: 1, y: 2, label: b"sp1".to_vec() });
+        let bytes = execute_read_archived(&stdin);
+        assert_eq!(bytes.as_slice(), stdin.buffer[0].as_slice());
+        let point = rkyv::check_archived_root::<Point>(&bytes).unwrap();
+        assert_eq!((point.x, point.y), (1, 2));
+        assert_eq!(point.label.as_slice(), b"sp1");
+    }
+
+    #[test]
+    fn test_read_archived_unaligned_len() {
+        let mut stdin = SP1Stdin::new();
+        stdin.write_archived(&Rgb { r: 1, g: 2, b: 3 });
+        assert_eq!(stdin.buffer[0].len(), 3);
+        let bytes = execute_read_archived(&stdin);
+        assert_eq!(bytes.as_slice(), stdin.buffer[0].as_slice());
+        let rgb = rkyv::check_archived_root::<Rgb>(&bytes).unwrap();
+        assert_eq!((rgb.r, rgb.g, rgb.b), (1, 2, 3));
+    }
+}
@@ -0,0 +1,232 @@
+//! Building blocks for proving the core shards of a program across several processes.
+//!
+//! [commit_checkpoints] executes the program, commits to the global traces of every shard and
+//! splits the execution into [CheckpointTask]s. A task carries the prover state at the start of
+//! its checkpoint, so that [prove_checkpoint] can re-execute it and prove its shards on any
+//! machine, independently of the other checkpoints.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use p3_challenger::FieldChallenger;
+use p3_field::PrimeField32;
+use p3_maybe_rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sp1_core_executor::{
+    ExecutionRecord, ExecutionReport, ExecutionState, Executor, Program, ProofPhase, SP1Context,
+};
+use sp1_primitives::io::SP1PublicValues;
+use sp1_stark::{
+    air::{InteractionScope, PublicValues},
+    Com, MachineProver, MachineProvingKey, MachineRecord, OpeningProof, PcsProverData, SP1CoreOpts,
+    ShardProof, StarkGenericConfig, Val,
+};
+
+use super::prove::{reset_seek, trace_checkpoint_state, update_checkpoint_records};
+use crate::{
+    io::SP1Stdin,
+    riscv::{CoreShapeConfig, RiscvAir},
+    utils::SP1CoreProverError,
+};
+
+/// A checkpoint of the execution, together with the prover state needed to prove its shards
+/// independently of the other checkpoints.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CheckpointTask {
+    /// The index of the checkpoint.
+    pub index: usize,
+    /// The execution state at the start of the checkpoint.
+    pub checkpoint: ExecutionState,
+    /// Whether this is the last checkpoint of the execution.
+    pub done: bool,
+    /// The public values of the last shard before the checkpoint.
+    pub state: PublicValues<u32, u32>,
+    /// The events deferred by the previous checkpoints that have not been proven yet.
+    pub deferred: ExecutionRecord,
+}
+
+/// The result of executing a program and committing to the global traces of its shards.
+pub struct CheckpointPlan<SC: StarkGenericConfig> {
+    /// The [CheckpointTask]s of the execution, in order, each serialized to a temporary file.
+    pub tasks: Vec<File>,
+    /// The global main commitment and public values of every shard, in order.
+    pub commitments: Vec<(Com<SC>, Vec<Val<SC>>)>,
+    /// The number of shards of each task, in order.
+    pub num_shards: Vec<usize>,
+    /// The public values of the program.
+    pub public_values: SP1PublicValues,
+    /// The execution report aggregated over all checkpoints.
+    pub report: ExecutionReport,
+}
+
+/// Executes the program, commits to the global traces of its shards and splits the execution
+/// into [CheckpointTask]s that can be proven with [prove_checkpoint].
+pub fn commit_checkpoints<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
+    prover: &P,
+    program: Program,
+    stdin: &SP1Stdin,
+    opts: SP1CoreOpts,
+    context: SP1Context,
+    shape_config: Option<&CoreShapeConfig<SC::Val>>,
+) -> Result<CheckpointPlan<SC>, SP1CoreProverError>
+where
+    SC::Val: PrimeField32,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+{
+    // Setup the runtime.
+    let progress = context.progress.clone();
+    progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
+    progress.report(ProofPhase::Execute);
+    let mut runtime = Executor::with_context(program.clone(), opts, context);
+    let maximal_shapes = match shape_config.as_ref() {
+        Some(shape_config) => shape_config.maximal_core_shapes(),
+        None => vec![],
+    };
+    runtime.maximal_shapes = Some(maximal_shapes.into_iter().map(|s| s.inner).collect());
+    runtime.write_vecs(&stdin.buffer);
+    for proof in stdin.proofs.iter() {
+        let (proof, vk) = proof.clone();
+        runtime.write_proof(proof, vk);
+    }
+
+    let mut state = PublicValues::<u32, u32>::default().reset();
+    let mut deferred = ExecutionRecord::new(program.clone().into());
+    let mut tasks = Vec::new();
+    let mut commitments = Vec::new();
+    let mut num_shards = Vec::new();
+    let mut report = ExecutionReport::default();
+    for index in 0.. {
+        progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
+
+        // Execute the runtime until we reach a checkpoint, and trace it.
+        let (checkpoint, done) =
+            runtime.execute_state().map_err(SP1CoreProverError::ExecutionError)?;
+        let (mut records, checkpoint_report) = tracing::debug_span!("trace checkpoint", index)
+            .in_scope(|| {
+                trace_checkpoint_state::<SC>(
+                    program.clone(),
+                    checkpoint.clone(),
+                    opts,
+                    shape_config,
+                )
+            });
+        report += checkpoint_report;
+
+        // Save the task with the prover state before the checkpoint.
+        let task = CheckpointTask { index, checkpoint, done, state, deferred: deferred.clone() };
+        let mut file = tempfile::tempfile().map_err(SP1CoreProverError::IoError)?;
+        {
+            let mut writer = BufWriter::new(&mut file);
+            bincode::serialize_into(&mut writer, &task)
+                .map_err(SP1CoreProverError::SerializationError)?;
+            writer.flush().map_err(SP1CoreProverError::IoError)?;
+        }
+        reset_seek(&mut file);
+        tasks.push(file);
+
+        // Update the public values & prover state and add the deferred shards.
+        update_checkpoint_records(&mut records, &mut state, &mut deferred, done, opts);
+        if let Some(shape_config) = shape_config {
+            for record in records.iter_mut() {
+                shape_config.fix_shape(record).unwrap();
+            }
+        }
+
+        // Commit to the global traces of each shard.
+        let public_values = records
+            .iter()
+            .map(|record| {
+                record.public_values::<SC::Val>()[0..prover.machine().num_pv_elts()].to_vec()
+            })
+            .collect::<Vec<_>>();
+        let commits = tracing::debug_span!("commit global traces", index).in_scope(|| {
+            records
+                .par_iter()
+                .map(|record| {
+                    let traces = prover.generate_traces(record, InteractionScope::Global);
+                    prover.commit(record, traces).main_commit.clone()
+                })
+                .collect::<Vec<_>>()
+        });
+        num_shards.push(commits.len());
+        commitments.extend(commits.into_iter().zip(public_values));
+
+        if done {
+            break;
+        }
+    }
+
+    let mut public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+    public_values.set_merkle_leaves(runtime.state.merkle_leaves_stream);
+    Ok(CheckpointPlan { tasks, commitments, num_shards, public_values, report })
+}
+
+/// Proves the shards of a checkpoint, given the commitments of all the shards of the execution
+/// computed by [commit_checkpoints].
+///
+/// The shard proofs are returned in order, and the proofs of all checkpoints concatenated in
+/// order form the core proof of the program.
+pub fn prove_checkpoint<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
+    prover: &P,
+    pk: &P::DeviceProvingKey,
+    program: Program,
+    task: CheckpointTask,
+    commitments: &[(Com<SC>, Vec<Val<SC>>)],
+    opts: SP1CoreOpts,
+    shape_config: Option<&CoreShapeConfig<SC::Val>>,
+) -> Vec<ShardProof<SC>>
+where
+    SC::Val: PrimeField32,
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+{
+    // Recompute the challenger and the global permutation challenges from the commitments.
+    let mut challenger = prover.config().challenger();
+    pk.observe_into(&mut challenger);
+    for (commit, public_values) in commitments {
+        prover.observe(&mut challenger, commit.clone(), public_values);
+    }
+    let global_permutation_challenges: [SC::Challenge; 2] =
+        [challenger.sample_ext_element(), challenger.sample_ext_element()];
+
+    // Trace the checkpoint and reconstruct the execution records.
+    let CheckpointTask { index, checkpoint, done, mut state, mut deferred } = task;
+    let (mut records, _) = tracing::debug_span!("trace checkpoint", index)
+        .in_scope(|| trace_checkpoint_state::<SC>(program, checkpoint, opts, shape_config));
+    update_checkpoint_records(&mut records, &mut state, &mut deferred, done, opts);
+    tracing::debug_span!("generate dependencies", index).in_scope(|| {
+        prover.machine().generate_dependencies(&mut records, &opts, None);
+    });
+    if let Some(shape_config) = shape_config {
+        for record in records.iter_mut() {
+            shape_config.fix_shape(record).unwrap();
+        }
+    }
+
+    // Prove the shards.
+    let span = tracing::Span::current().clone();
+    records
+        .into_par_iter()
+        .map(|record| {
+            let _span = span.enter();
+            let global_traces = prover.generate_traces(&record, InteractionScope::Global);
+            let local_traces = prover.generate_traces(&record, InteractionScope::Local);
+            let global_data = prover.commit(&record, global_traces);
+            let local_data = prover.commit(&record, local_traces);
+            prover
+                .open(
+                    pk,
+                    Some(global_data),
+                    local_data,
+                    &mut challenger.clone(),
+                    &global_permutation_challenges,
+                )
+                .unwrap()
+        })
+        .collect()
+}
@@ -1,4 +1,5 @@
 pub mod concurrency;
+mod distributed;
 mod logger;
 #[cfg(any(test, feature = "programs"))]
 mod programs;
@@ -6,6 +7,7 @@ mod prove;
 mod span;
 mod tracer;
 
+pub use distributed::*;
 pub use logger::*;
 use p3_field::Field;
 pub use prove::*;
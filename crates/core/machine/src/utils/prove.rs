@@ -4,7 +4,11 @@ use std::{
     io::{
         Seek, {self},
     },
-    sync::{mpsc::sync_channel, Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::sync_channel,
+        Arc, Mutex,
+    },
 };
 use web_time::Instant;
 
@@ -14,7 +18,7 @@ use p3_maybe_rayon::prelude::*;
 use serde::{de::DeserializeOwned, Serialize};
 use size::Size;
 use sp1_stark::{
-    air::InteractionScope, baby_bear_poseidon2::BabyBearPoseidon2, MachineProvingKey,
+    air::InteractionScope, baby_bear_poseidon2::BabyBearPoseidon2, FriParams, MachineProvingKey,
     MachineVerificationError,
 };
 use std::thread::ScopedJoinHandle;
@@ -34,7 +38,7 @@ use sp1_primitives::io::SP1PublicValues;
 
 use sp1_core_executor::{
     subproof::NoOpSubproofVerifier, ExecutionError, ExecutionRecord, ExecutionReport, Executor,
-    Program, SP1Context,
+    Program, ProofCancelled, ProofPhase, SP1Context,
 };
 use sp1_stark::{
     air::{MachineAir, PublicValues},
@@ -52,6 +56,10 @@ pub enum SP1CoreProverError {
     IoError(io::Error),
     #[error("serialization error: {0}")]
     SerializationError(bincode::Error),
+    #[error("{0}")]
+    Cancelled(ProofCancelled),
+    #[error("FRI params mismatch: requested {0:?}, prover uses {1:?}")]
+    FriParamsMismatch(FriParams, FriParams),
 }
 
 pub fn prove_simple<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
@@ -133,6 +141,29 @@ pub fn prove_with_context<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<
     context: SP1Context,
     shape_config: Option<&CoreShapeConfig<SC::Val>>,
 ) -> Result<(MachineProof<SC>, Vec<u8>, u64), SP1CoreProverError>
+where
+    SC::Val: PrimeField32,
+    SC::Challenger: 'static + Clone + Send,
+    OpeningProof<SC>: Send,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+{
+    let (proof, public_values, cycles) =
+        prove_with_public_values::<SC, P>(prover, pk, program, stdin, opts, context, shape_config)?;
+    Ok((proof, public_values.to_vec(), cycles))
+}
+
+/// Like [`prove_with_context`], but returns the public values together with the leaves the
+/// program committed through a Merkle root.
+pub fn prove_with_public_values<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
+    prover: &P,
+    pk: &P::DeviceProvingKey,
+    program: Program,
+    stdin: &SP1Stdin,
+    opts: SP1CoreOpts,
+    context: SP1Context,
+    shape_config: Option<&CoreShapeConfig<SC::Val>>,
+) -> Result<(MachineProof<SC>, SP1PublicValues, u64), SP1CoreProverError>
 where
     SC::Val: PrimeField32,
     SC::Challenger: 'static + Clone + Send,
@@ -141,6 +172,9 @@ where
     PcsProverData<SC>: Send + Sync,
 {
     // Setup the runtime.
+    let progress = context.progress.clone();
+    progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
+    progress.report(ProofPhase::Execute);
     let mut runtime = Executor::with_context(program.clone(), opts, context);
     let maximal_shapes = match shape_config.as_ref() {
         Some(shape_config) => shape_config.maximal_core_shapes(),
@@ -166,12 +200,17 @@ where
         let checkpoint_generator_span = tracing::Span::current().clone();
         let (checkpoints_tx, checkpoints_rx) =
             sync_channel::<(usize, File, bool)>(opts.checkpoints_channel_capacity);
+        let checkpoint_generator_progress = progress.clone();
         let checkpoint_generator_handle: ScopedJoinHandle<Result<_, SP1CoreProverError>> =
             s.spawn(move || {
                 let _span = checkpoint_generator_span.enter();
+                let progress = checkpoint_generator_progress;
                 tracing::debug_span!("checkpoint generator").in_scope(|| {
                     let mut index = 0;
                     loop {
+                        // Stop executing if the proof has been cancelled.
+                        progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
+
                         // Enter the span.
                         let span = tracing::debug_span!("batch");
                         let _span = span.enter();
@@ -187,12 +226,18 @@ where
                             .save(&mut checkpoint_file)
                             .map_err(SP1CoreProverError::IoError)?;
 
-                        // Send the checkpoint.
-                        checkpoints_tx.send((index, checkpoint_file, done)).unwrap();
+                        // Send the checkpoint. The workers only stop receiving checkpoints if the
+                        // proof has been cancelled.
+                        if checkpoints_tx.send((index, checkpoint_file, done)).is_err() {
+                            break Err(SP1CoreProverError::Cancelled(ProofCancelled));
+                        }
 
                         // If we've reached the final checkpoint, break out of the loop.
                         if done {
-                            break Ok(runtime.state.public_values_stream);
+                            let mut public_values =
+                                SP1PublicValues::from(&runtime.state.public_values_stream);
+                            public_values.set_merkle_leaves(runtime.state.merkle_leaves_stream);
+                            break Ok(public_values);
                         }
 
                         // Update the index.
@@ -225,6 +270,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let progress = progress.clone();
 
             let span = tracing::Span::current().clone();
 
@@ -232,6 +278,11 @@ where
                 let _span = span.enter();
                 tracing::debug_span!("phase 1 trace generation").in_scope(|| {
                     loop {
+                        // Stop tracing checkpoints if the proof has been cancelled.
+                        if progress.is_cancelled() {
+                            break;
+                        }
+
                         // Receive the latest checkpoint.
                         let received = { checkpoints_rx.lock().unwrap().recv() };
 
@@ -253,50 +304,15 @@ where
                             log::info!("waiting for turn {}", index);
                             record_gen_sync.wait_for_turn(index);
 
-                            // Update the public values & prover state for the shards which contain
-                            // "cpu events".
-                            let mut state = state.lock().unwrap();
-                            for record in records.iter_mut() {
-                                state.shard += 1;
-                                state.execution_shard = record.public_values.execution_shard;
-                                state.start_pc = record.public_values.start_pc;
-                                state.next_pc = record.public_values.next_pc;
-                                state.committed_value_digest =
-                                    record.public_values.committed_value_digest;
-                                state.deferred_proofs_digest =
-                                    record.public_values.deferred_proofs_digest;
-                                record.public_values = *state;
-                            }
-
-                            // Defer events that are too expensive to include in every shard.
-                            let mut deferred = deferred.lock().unwrap();
-                            for record in records.iter_mut() {
-                                deferred.append(&mut record.defer());
-                            }
-
-                            // See if any deferred shards are ready to be committed to.
-                            let mut deferred = deferred.split(done, opts.split_opts);
-                            log::info!("deferred {} records", deferred.len());
-
-                            // Update the public values & prover state for the shards which do not
-                            // contain "cpu events" before committing to them.
-                            if !done {
-                                state.execution_shard += 1;
-                            }
-                            for record in deferred.iter_mut() {
-                                state.shard += 1;
-                                state.previous_init_addr_bits =
-                                    record.public_values.previous_init_addr_bits;
-                                state.last_init_addr_bits =
-                                    record.public_values.last_init_addr_bits;
-                                state.previous_finalize_addr_bits =
-                                    record.public_values.previous_finalize_addr_bits;
-                                state.last_finalize_addr_bits =
-                                    record.public_values.last_finalize_addr_bits;
-                                state.start_pc = state.next_pc;
-                                record.public_values = *state;
-                            }
-                            records.append(&mut deferred);
+                            // Update the public values & prover state and add the deferred
+                            // shards.
+                            update_checkpoint_records(
+                                &mut records,
+                                &mut state.lock().unwrap(),
+                                &mut deferred.lock().unwrap(),
+                                done,
+                                opts,
+                            );
 
                             // Collect the checkpoints to be used again in the phase 2 prover.
                             log::info!("collecting checkpoints");
@@ -352,16 +368,26 @@ where
         }
         drop(p1_records_and_traces_tx);
 
+        // Only the workers hold the checkpoint receiver, so that the checkpoint generator stops
+        // once they stop receiving checkpoints.
+        drop(checkpoints_rx);
+
         // Create the challenger and observe the verifying key.
         let mut challenger = prover.config().challenger();
         pk.observe_into(&mut challenger);
 
         // Spawn the phase 1 prover thread.
         let phase_1_prover_span = tracing::Span::current().clone();
+        let phase_1_prover_progress = progress.clone();
         let phase_1_prover_handle = s.spawn(move || {
             let _span = phase_1_prover_span.enter();
             tracing::debug_span!("phase 1 prover").in_scope(|| {
                 for (records, traces) in p1_records_and_traces_rx.iter() {
+                    // Drop the remaining batches without committing to them if the proof has been
+                    // cancelled.
+                    if phase_1_prover_progress.is_cancelled() {
+                        continue;
+                    }
                     tracing::debug_span!("batch").in_scope(|| {
                         let span = tracing::Span::current().clone();
 
@@ -415,13 +441,17 @@ where
         });
 
         // Wait until the checkpoint generator handle has fully finished.
-        let public_values_stream = checkpoint_generator_handle.join().unwrap().unwrap();
+        let public_values = checkpoint_generator_handle.join().unwrap()?;
 
         // Wait until the records and traces have been fully generated.
         p1_record_and_trace_gen_handles.into_iter().for_each(|handle| handle.join().unwrap());
 
         // Wait until the phase 1 prover has completely finished.
         let mut challenger = phase_1_prover_handle.join().unwrap();
+        progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
+
+        // The shard counter of the phase 1 state is the total number of shards.
+        let num_shards = state.lock().unwrap().shard as usize;
 
         // Sample for the global permutation challenges.
         // Obtain the challenges used for the global permutation argument.
@@ -457,6 +487,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let progress = progress.clone();
 
             let span = tracing::Span::current().clone();
 
@@ -467,6 +498,11 @@ where
                 let _span = span.enter();
                 tracing::debug_span!("phase 2 trace generation").in_scope(|| {
                     loop {
+                        // Stop tracing checkpoints if the proof has been cancelled.
+                        if progress.is_cancelled() {
+                            break;
+                        }
+
                         // Receive the latest checkpoint.
                         let received = { checkpoints.lock().unwrap().pop_front() };
                         if let Some((index, mut checkpoint, done)) = received {
@@ -487,50 +523,15 @@ where
                             // Wait for our turn to update the state.
                             record_gen_sync.wait_for_turn(index);
 
-                            // Update the public values & prover state for the shards which contain
-                            // "cpu events".
-                            let mut state = state.lock().unwrap();
-                            for record in records.iter_mut() {
-                                state.shard += 1;
-                                state.execution_shard = record.public_values.execution_shard;
-                                state.start_pc = record.public_values.start_pc;
-                                state.next_pc = record.public_values.next_pc;
-                                state.committed_value_digest =
-                                    record.public_values.committed_value_digest;
-                                state.deferred_proofs_digest =
-                                    record.public_values.deferred_proofs_digest;
-                                record.public_values = *state;
-                            }
-
-                            // Defer events that are too expensive to include in every shard.
-                            let mut deferred = deferred.lock().unwrap();
-                            for record in records.iter_mut() {
-                                deferred.append(&mut record.defer());
-                            }
-
-                            // See if any deferred shards are ready to be committed to.
-                            let mut deferred = deferred.split(done, opts.split_opts);
-                            log::info!("deferred {} records", deferred.len());
-
-                            // Update the public values & prover state for the shards which do not
-                            // contain "cpu events" before committing to them.
-                            if !done {
-                                state.execution_shard += 1;
-                            }
-                            for record in deferred.iter_mut() {
-                                state.shard += 1;
-                                state.previous_init_addr_bits =
-                                    record.public_values.previous_init_addr_bits;
-                                state.last_init_addr_bits =
-                                    record.public_values.last_init_addr_bits;
-                                state.previous_finalize_addr_bits =
-                                    record.public_values.previous_finalize_addr_bits;
-                                state.last_finalize_addr_bits =
-                                    record.public_values.last_finalize_addr_bits;
-                                state.start_pc = state.next_pc;
-                                record.public_values = *state;
-                            }
-                            records.append(&mut deferred);
+                            // Update the public values & prover state and add the deferred
+                            // shards.
+                            update_checkpoint_records(
+                                &mut records,
+                                &mut state.lock().unwrap(),
+                                &mut deferred.lock().unwrap(),
+                                done,
+                                opts,
+                            );
 
                             // Generate the dependencies.
                             tracing::debug_span!("generate dependencies", index).in_scope(|| {
@@ -606,11 +607,19 @@ where
 
         // Spawn the phase 2 prover thread.
         let p2_prover_span = tracing::Span::current().clone();
+        let p2_prover_progress = progress.clone();
+        let p2_proven_shards = AtomicUsize::new(0);
         let p2_prover_handle = s.spawn(move || {
             let _span = p2_prover_span.enter();
+            let progress = p2_prover_progress;
             let mut shard_proofs = Vec::new();
             tracing::debug_span!("phase 2 prover").in_scope(|| {
                 for (records, traces) in p2_records_and_traces_rx.into_iter() {
+                    // Drop the remaining batches without proving them if the proof has been
+                    // cancelled.
+                    if progress.is_cancelled() {
+                        continue;
+                    }
                     tracing::debug_span!("batch").in_scope(|| {
                         let span = tracing::Span::current().clone();
                         shard_proofs.par_extend(
@@ -640,6 +649,12 @@ where
                                             );
                                         }
                                     }
+
+                                    let index = p2_proven_shards.fetch_add(1, Ordering::Relaxed);
+                                    progress.report(ProofPhase::CoreShard {
+                                        index: index + 1,
+                                        num_shards,
+                                    });
                                     proof
                                 },
                             ),
@@ -655,6 +670,7 @@ where
 
         // Wait until the phase 2 prover has finished.
         let shard_proofs = p2_prover_handle.join().unwrap();
+        progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
 
         // Log some of the `ExecutionReport` information.
         let report_aggregate = report_aggregate.lock().unwrap();
@@ -697,7 +713,7 @@ where
             prover.machine().debug_constraints(&pk.to_host(), all_records, &mut challenger);
         }
 
-        Ok((proof, public_values_stream, cycles))
+        Ok((proof, public_values, cycles))
     })
 }
 
@@ -829,12 +845,76 @@ where
     run_test_machine_with_prover::<SC, A, CpuProver<_, _>>(&prover, records, pk, vk)
 }
 
+/// Sets the public values of the records traced from a checkpoint from the running prover
+/// `state`, and appends the shards of `deferred` events that are ready to be proven.
+///
+/// Checkpoints must be passed in order, since `state` and `deferred` carry over between them.
+pub(crate) fn update_checkpoint_records(
+    records: &mut Vec<ExecutionRecord>,
+    state: &mut PublicValues<u32, u32>,
+    deferred: &mut ExecutionRecord,
+    done: bool,
+    opts: SP1CoreOpts,
+) {
+    // Update the public values & prover state for the shards which contain "cpu events".
+    for record in records.iter_mut() {
+        state.shard += 1;
+        state.execution_shard = record.public_values.execution_shard;
+        state.start_pc = record.public_values.start_pc;
+        state.next_pc = record.public_values.next_pc;
+        state.committed_value_digest = record.public_values.committed_value_digest;
+        state.deferred_proofs_digest = record.public_values.deferred_proofs_digest;
+        record.public_values = *state;
+    }
+
+    // Defer events that are too expensive to include in every shard.
+    for record in records.iter_mut() {
+        deferred.append(&mut record.defer());
+    }
+
+    // See if any deferred shards are ready to be committed to.
+    let mut deferred = deferred.split(done, opts.split_opts);
+    log::info!("deferred {} records", deferred.len());
+
+    // Update the public values & prover state for the shards which do not contain "cpu events"
+    // before committing to them.
+    if !done {
+        state.execution_shard += 1;
+    }
+    for record in deferred.iter_mut() {
+        state.shard += 1;
+        state.previous_init_addr_bits = record.public_values.previous_init_addr_bits;
+        state.last_init_addr_bits = record.public_values.last_init_addr_bits;
+        state.previous_finalize_addr_bits = record.public_values.previous_finalize_addr_bits;
+        state.last_finalize_addr_bits = record.public_values.last_finalize_addr_bits;
+        state.start_pc = state.next_pc;
+        record.public_values = *state;
+    }
+    records.append(&mut deferred);
+}
+
 fn trace_checkpoint<SC: StarkGenericConfig>(
     program: Program,
     file: &File,
     opts: SP1CoreOpts,
     shape_config: Option<&CoreShapeConfig<SC::Val>>,
 ) -> (Vec<ExecutionRecord>, ExecutionReport)
+where
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    let mut reader = std::io::BufReader::new(file);
+    let state: ExecutionState =
+        bincode::deserialize_from(&mut reader).expect("failed to deserialize state");
+    trace_checkpoint_state::<SC>(program, state, opts, shape_config)
+}
+
+/// Re-executes the program from the checkpoint `state`, generating its execution records.
+pub(crate) fn trace_checkpoint_state<SC: StarkGenericConfig>(
+    program: Program,
+    state: ExecutionState,
+    opts: SP1CoreOpts,
+    shape_config: Option<&CoreShapeConfig<SC::Val>>,
+) -> (Vec<ExecutionRecord>, ExecutionReport)
 where
     <SC as StarkGenericConfig>::Val: PrimeField32,
 {
@@ -842,10 +922,7 @@ where
         Some(shape_config) => shape_config.maximal_core_shapes(),
         None => vec![],
     };
-    let mut reader = std::io::BufReader::new(file);
-    let state: ExecutionState =
-        bincode::deserialize_from(&mut reader).expect("failed to deserialize state");
-    let mut runtime = Executor::recover(program.clone(), state.clone(), opts);
+    let mut runtime = Executor::recover(program, state, opts);
     runtime.maximal_shapes = Some(maximal_shapes.into_iter().map(|s| s.inner).collect());
 
     // We already passed the deferred proof verifier when creating checkpoints, so the proofs were
@@ -858,7 +935,7 @@ where
     (records, runtime.report)
 }
 
-fn reset_seek(file: &mut File) {
+pub(crate) fn reset_seek(file: &mut File) {
     file.seek(std::io::SeekFrom::Start(0)).expect("failed to seek to start of tempfile");
 }
 
@@ -0,0 +1,38 @@
+//! Serves the `ProverService` protocol on the CPU, see `sp1_cuda::server`.
+
+use clap::Parser;
+use sp1_core_machine::utils::setup_logger;
+use sp1_cuda::server::CpuProverService;
+use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+use sp1_stark::SP1ProverOpts;
+use tokio::net::TcpListener;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The address to listen on. Use e.g. `0.0.0.0:3000` to accept connections from other hosts.
+    #[clap(long, env = "SP1_SERVER_LISTEN", default_value = "127.0.0.1:3000")]
+    listen: String,
+    /// The maximum number of requests proven at the same time.
+    #[clap(long, default_value_t = 1)]
+    max_concurrent_proofs: usize,
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    setup_logger();
+    let args = Args::parse();
+
+    let prover = SP1Prover::<DefaultProverComponents>::new();
+    let service =
+        CpuProverService::new(prover, SP1ProverOpts::default(), args.max_concurrent_proofs);
+    let listener = TcpListener::bind(&args.listen).await?;
+    tracing::info!("serving the prover service on {}", listener.local_addr()?);
+
+    service
+        .serve(listener, async {
+            let _ = tokio::signal::ctrl_c().await;
+            tracing::info!("shutting down after the pending requests");
+        })
+        .await
+}
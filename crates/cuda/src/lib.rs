@@ -1,7 +1,7 @@
 use std::{
     error::Error as StdError,
     future::Future,
-    io::{BufReader, Read, Write},
+    io::{self, BufReader, Read, Write},
     process::{Command, Stdio},
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -19,6 +19,7 @@ use sp1_core_machine::{io::SP1Stdin, reduce::SP1ReduceProof, utils::SP1CoreProve
 use sp1_prover::{
     types::SP1ProvingKey, InnerSC, OuterSC, SP1CoreProof, SP1RecursionProverError, SP1VerifyingKey,
 };
+use sp1_stark::SP1ProverOpts;
 use tokio::task::block_in_place;
 use twirp::{
     async_trait,
@@ -31,17 +32,20 @@ use twirp::{
 pub mod proto {
     pub mod api;
 }
+pub mod server;
 
-/// A remote client to [sp1_prover::SP1Prover] that runs inside a container.
+/// A remote client to [sp1_prover::SP1Prover] that runs inside a container or behind any
+/// endpoint serving the [proto::api::ProverService] protocol.
 ///
-/// This is currently used to provide experimental support for GPU hardware acceleration.
+/// This is currently used to provide experimental support for GPU hardware acceleration, and to
+/// access shared proving services such as [server::CpuProverService].
 ///
 /// **WARNING**: This is an experimental feature and may not work as expected.
 pub struct SP1CudaProver {
     /// The gRPC client to communicate with the container.
     client: Client,
-    /// The name of the container.
-    container_name: String,
+    /// The name of the container, if it was started by this client.
+    container_name: Option<String>,
     /// A flag to indicate whether the container has already been cleaned up.
     cleaned_up: Arc<AtomicBool>,
 }
@@ -55,6 +59,8 @@ pub struct ProveCoreRequestPayload {
     pub pk: SP1ProvingKey,
     /// The input stream.
     pub stdin: SP1Stdin,
+    /// The options to prove with, or `None` for the default options of the service.
+    pub opts: Option<SP1ProverOpts>,
 }
 
 /// The payload for the [sp1_prover::SP1Prover::compress] method.
@@ -68,6 +74,8 @@ pub struct CompressRequestPayload {
     pub proof: SP1CoreProof,
     /// The deferred proofs.
     pub deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+    /// The options to prove with, or `None` for the default options of the service.
+    pub opts: Option<SP1ProverOpts>,
 }
 
 /// The payload for the [sp1_prover::SP1Prover::shrink] method.
@@ -76,6 +84,8 @@ pub struct CompressRequestPayload {
 #[derive(Serialize, Deserialize)]
 pub struct ShrinkRequestPayload {
     pub reduced_proof: SP1ReduceProof<InnerSC>,
+    /// The options to prove with, or `None` for the default options of the service.
+    pub opts: Option<SP1ProverOpts>,
 }
 
 /// The payload for the [sp1_prover::SP1Prover::wrap_bn254] method.
@@ -84,6 +94,8 @@ pub struct ShrinkRequestPayload {
 #[derive(Serialize, Deserialize)]
 pub struct WrapRequestPayload {
     pub reduced_proof: SP1ReduceProof<InnerSC>,
+    /// The options to prove with, or `None` for the default options of the service.
+    pub opts: Option<SP1ProverOpts>,
 }
 
 impl SP1CudaProver {
@@ -175,19 +187,45 @@ impl SP1CudaProver {
         std::thread::sleep(Duration::from_secs(2));
 
         // Check if the container is ready
-        let client = Client::from_base_url(
-            Url::parse("http://localhost:3000/twirp/").expect("failed to parse url"),
-        )
-        .expect("failed to create client");
+        let url = Url::parse("http://localhost:3000/twirp/").expect("failed to parse url");
+        let client = Self::wait_until_ready(url, Duration::from_secs(300))?;
+
+        Ok(SP1CudaProver {
+            client,
+            container_name: Some(container_name.to_string()),
+            cleaned_up: cleaned_up.clone(),
+        })
+    }
+
+    /// Connects to a proving service that is already running at `endpoint`, such as the
+    /// `sp1-cpu-server` binary, e.g. `http://localhost:3000`.
+    ///
+    /// The requests are sent to the `/twirp/` routes of the endpoint.
+    pub fn connect(endpoint: &str) -> Result<Self, Box<dyn StdError>> {
+        let mut url = Url::parse(endpoint)?;
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        let url = url.join("twirp/")?;
+        let client = Self::wait_until_ready(url, Duration::from_secs(60))?;
 
-        let timeout = Duration::from_secs(300);
+        Ok(SP1CudaProver {
+            client,
+            container_name: None,
+            cleaned_up: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Waits until the proving server at `url` is ready and returns a client to it.
+    fn wait_until_ready(url: Url, timeout: Duration) -> Result<Client, Box<dyn StdError>> {
+        let client = Client::from_base_url(url.clone()).expect("failed to create client");
         let start_time = Instant::now();
 
         block_on(async {
             tracing::info!("waiting for proving server to be ready");
             loop {
                 if start_time.elapsed() > timeout {
-                    return Err("Timeout: proving server did not become ready within 60 seconds. Please check your Docker container and network settings.".to_string());
+                    return Err(format!("Timeout: proving server did not become ready within {} seconds. Please check your Docker container and network settings.", timeout.as_secs()));
                 }
 
                 let request = ReadyRequest {};
@@ -209,17 +247,12 @@ impl SP1CudaProver {
         })?;
 
         let client = Client::new(
-            Url::parse("http://localhost:3000/twirp/").expect("failed to parse url"),
+            url,
             reqwest::Client::new(),
             vec![Box::new(LoggingMiddleware) as Box<dyn Middleware>],
         )
         .expect("failed to create client");
-
-        Ok(SP1CudaProver {
-            client,
-            container_name: container_name.to_string(),
-            cleaned_up: cleaned_up.clone(),
-        })
+        Ok(client)
     }
 
     fn check_docker_availability() -> Result<bool, Box<dyn std::error::Error>> {
@@ -238,12 +271,16 @@ impl SP1CudaProver {
         &self,
         pk: &SP1ProvingKey,
         stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
-        let payload = ProveCoreRequestPayload { pk: pk.clone(), stdin: stdin.clone() };
+        let payload =
+            ProveCoreRequestPayload { pk: pk.clone(), stdin: stdin.clone(), opts: Some(opts) };
         let request =
             crate::proto::api::ProveCoreRequest { data: bincode::serialize(&payload).unwrap() };
-        let response = block_on(async { self.client.prove_core(request).await }).unwrap();
-        let proof: SP1CoreProof = bincode::deserialize(&response.result).unwrap();
+        let response = block_on(async { self.client.prove_core(request).await })
+            .map_err(|e| SP1CoreProverError::IoError(io::Error::other(e.to_string())))?;
+        let proof: SP1CoreProof = bincode::deserialize(&response.result)
+            .map_err(SP1CoreProverError::SerializationError)?;
         Ok(proof)
     }
 
@@ -257,13 +294,17 @@ impl SP1CudaProver {
         vk: &SP1VerifyingKey,
         proof: SP1CoreProof,
         deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+        opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
-        let payload = CompressRequestPayload { vk: vk.clone(), proof, deferred_proofs };
+        let payload =
+            CompressRequestPayload { vk: vk.clone(), proof, deferred_proofs, opts: Some(opts) };
         let request =
             crate::proto::api::CompressRequest { data: bincode::serialize(&payload).unwrap() };
 
-        let response = block_on(async { self.client.compress(request).await }).unwrap();
-        let proof: SP1ReduceProof<InnerSC> = bincode::deserialize(&response.result).unwrap();
+        let response = block_on(async { self.client.compress(request).await })
+            .map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
+        let proof: SP1ReduceProof<InnerSC> = bincode::deserialize(&response.result)
+            .map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
         Ok(proof)
     }
 
@@ -275,13 +316,16 @@ impl SP1CudaProver {
     pub fn shrink(
         &self,
         reduced_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
-        let payload = ShrinkRequestPayload { reduced_proof: reduced_proof.clone() };
+        let payload = ShrinkRequestPayload { reduced_proof, opts: Some(opts) };
         let request =
             crate::proto::api::ShrinkRequest { data: bincode::serialize(&payload).unwrap() };
 
-        let response = block_on(async { self.client.shrink(request).await }).unwrap();
-        let proof: SP1ReduceProof<InnerSC> = bincode::deserialize(&response.result).unwrap();
+        let response = block_on(async { self.client.shrink(request).await })
+            .map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
+        let proof: SP1ReduceProof<InnerSC> = bincode::deserialize(&response.result)
+            .map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
         Ok(proof)
     }
 
@@ -293,13 +337,16 @@ impl SP1CudaProver {
     pub fn wrap_bn254(
         &self,
         reduced_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
-        let payload = WrapRequestPayload { reduced_proof: reduced_proof.clone() };
+        let payload = WrapRequestPayload { reduced_proof, opts: Some(opts) };
         let request =
             crate::proto::api::WrapRequest { data: bincode::serialize(&payload).unwrap() };
 
-        let response = block_on(async { self.client.wrap(request).await }).unwrap();
-        let proof: SP1ReduceProof<OuterSC> = bincode::deserialize(&response.result).unwrap();
+        let response = block_on(async { self.client.wrap(request).await })
+            .map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
+        let proof: SP1ReduceProof<OuterSC> = bincode::deserialize(&response.result)
+            .map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
         Ok(proof)
     }
 }
@@ -312,9 +359,10 @@ impl Default for SP1CudaProver {
 
 impl Drop for SP1CudaProver {
     fn drop(&mut self) {
+        let Some(container_name) = &self.container_name else { return };
         if !self.cleaned_up.load(Ordering::SeqCst) {
             tracing::debug!("dropping SP1ProverClient, cleaning up...");
-            cleanup_container(&self.container_name);
+            cleanup_container(container_name);
             self.cleaned_up.store(true, Ordering::SeqCst);
         }
     }
@@ -371,6 +419,7 @@ mod tests {
         utils::{setup_logger, tests::FIBONACCI_ELF},
     };
     use sp1_prover::{components::DefaultProverComponents, InnerSC, SP1CoreProof, SP1Prover};
+    use sp1_stark::SP1ProverOpts;
     use twirp::{url::Url, Client};
 
     use crate::{
@@ -387,25 +436,26 @@ mod tests {
         let (pk, vk) = prover.setup(FIBONACCI_ELF);
 
         println!("proving core");
-        let proof = client.prove_core(&pk, &SP1Stdin::new()).unwrap();
+        let opts = SP1ProverOpts::default();
+        let proof = client.prove_core(&pk, &SP1Stdin::new(), opts).unwrap();
 
         println!("verifying core");
         prover.verify(&proof.proof, &vk).unwrap();
 
         println!("proving compress");
-        let proof = client.compress(&vk, proof, vec![]).unwrap();
+        let proof = client.compress(&vk, proof, vec![], opts).unwrap();
 
         println!("verifying compress");
         prover.verify_compressed(&proof, &vk).unwrap();
 
         println!("proving shrink");
-        let proof = client.shrink(proof).unwrap();
+        let proof = client.shrink(proof, opts).unwrap();
 
         println!("verifying shrink");
         prover.verify_shrink(&proof, &vk).unwrap();
 
         println!("proving wrap_bn254");
-        let proof = client.wrap_bn254(proof).unwrap();
+        let proof = client.wrap_bn254(proof, opts).unwrap();
 
         println!("verifying wrap_bn254");
         prover.verify_wrap_bn254(&proof, &vk).unwrap();
@@ -418,7 +468,7 @@ mod tests {
 
         let prover = SP1Prover::<DefaultProverComponents>::new();
         let (pk, vk) = prover.setup(FIBONACCI_ELF);
-        let payload = ProveCoreRequestPayload { pk, stdin: SP1Stdin::new() };
+        let payload = ProveCoreRequestPayload { pk, stdin: SP1Stdin::new(), opts: None };
         let request =
             crate::proto::api::ProveCoreRequest { data: bincode::serialize(&payload).unwrap() };
         let proof = client.prove_core(request).await.unwrap();
@@ -426,7 +476,8 @@ mod tests {
         prover.verify(&proof.proof, &vk).unwrap();
 
         tracing::info!("compress");
-        let payload = CompressRequestPayload { vk: vk.clone(), proof, deferred_proofs: vec![] };
+        let payload =
+            CompressRequestPayload { vk: vk.clone(), proof, deferred_proofs: vec![], opts: None };
         let request =
             crate::proto::api::CompressRequest { data: bincode::serialize(&payload).unwrap() };
         let compressed_proof = client.compress(request).await.unwrap();
@@ -0,0 +1,221 @@
+//! A CPU implementation of the [ProverService], so that a shared proving service can be run on
+//! machines without a GPU and accessed with [crate::SP1CudaProver::connect].
+
+use std::{future::Future, io, sync::Arc};
+
+use serde::{de::DeserializeOwned, Serialize};
+use sp1_core_executor::SP1Context;
+use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+use sp1_stark::SP1ProverOpts;
+use tokio::{net::TcpListener, sync::Semaphore};
+use twirp::{async_trait::async_trait, axum, Context, TwirpErrorResponse};
+
+use crate::{
+    proto::api::{
+        router, CompressRequest, CompressResponse, ProveCoreRequest, ProveCoreResponse,
+        ProverService, ReadyRequest, ReadyResponse, ShrinkRequest, ShrinkResponse, WrapRequest,
+        WrapResponse, SERVICE_FQN,
+    },
+    CompressRequestPayload, ProveCoreRequestPayload, ShrinkRequestPayload, WrapRequestPayload,
+};
+
+/// A [ProverService] that generates proofs with [SP1Prover] on the CPU.
+///
+/// Requests are proven on blocking threads, and at most `max_concurrent_proofs` of them are
+/// proven at the same time. Other requests wait for a slot. Requests that do not set their own
+/// options are proven with `opts`.
+#[derive(Clone)]
+pub struct CpuProverService {
+    prover: Arc<SP1Prover<DefaultProverComponents>>,
+    opts: SP1ProverOpts,
+    permits: Arc<Semaphore>,
+}
+
+impl CpuProverService {
+    /// Creates a new [CpuProverService].
+    pub fn new(
+        prover: SP1Prover<DefaultProverComponents>,
+        opts: SP1ProverOpts,
+        max_concurrent_proofs: usize,
+    ) -> Self {
+        assert!(max_concurrent_proofs > 0, "max_concurrent_proofs must be positive");
+        Self {
+            prover: Arc::new(prover),
+            opts,
+            permits: Arc::new(Semaphore::new(max_concurrent_proofs)),
+        }
+    }
+
+    /// The routes of the service, mounted under `/twirp` like the GPU container.
+    pub fn into_router(self) -> axum::Router {
+        axum::Router::new()
+            .nest(&format!("/twirp{SERVICE_FQN}"), router(self))
+            .fallback(twirp::server::not_found_handler)
+    }
+
+    /// Serves requests on `listener` until `shutdown` completes.
+    ///
+    /// Requests that are being proven when `shutdown` completes are finished before returning.
+    pub async fn serve(
+        self,
+        listener: TcpListener,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> io::Result<()> {
+        axum::serve(listener, self.into_router()).with_graceful_shutdown(shutdown).await
+    }
+
+    /// Deserializes a request payload, runs `f` on a blocking thread once a slot is available,
+    /// and serializes its result.
+    async fn run<T, R, F>(&self, data: Vec<u8>, f: F) -> Result<Vec<u8>, TwirpErrorResponse>
+    where
+        T: DeserializeOwned + Send + 'static,
+        R: Serialize + Send + 'static,
+        F: FnOnce(&SP1Prover<DefaultProverComponents>, T, SP1ProverOpts) -> Result<R, String>
+            + Send
+            + 'static,
+    {
+        let payload: T = bincode::deserialize(&data)
+            .map_err(|e| twirp::invalid_argument(format!("failed to decode payload: {e}")))?;
+        let _permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| twirp::unavailable("the service is shutting down"))?;
+
+        let prover = self.prover.clone();
+        let opts = self.opts;
+        let result = tokio::task::spawn_blocking(move || f(&prover, payload, opts))
+            .await
+            .map_err(|e| twirp::internal(format!("prover task failed: {e}")))?
+            .map_err(twirp::internal)?;
+        bincode::serialize(&result)
+            .map_err(|e| twirp::internal(format!("failed to encode result: {e}")))
+    }
+}
+
+#[async_trait]
+impl ProverService for CpuProverService {
+    async fn ready(
+        &self,
+        _ctx: Context,
+        _req: ReadyRequest,
+    ) -> Result<ReadyResponse, TwirpErrorResponse> {
+        Ok(ReadyResponse { ready: true })
+    }
+
+    async fn prove_core(
+        &self,
+        _ctx: Context,
+        req: ProveCoreRequest,
+    ) -> Result<ProveCoreResponse, TwirpErrorResponse> {
+        let result = self
+            .run(req.data, |prover, payload: ProveCoreRequestPayload, opts| {
+                tracing::info!("proving core");
+                prover
+                    .prove_core(
+                        &payload.pk,
+                        &payload.stdin,
+                        payload.opts.unwrap_or(opts),
+                        SP1Context::default(),
+                    )
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+        Ok(ProveCoreResponse { result })
+    }
+
+    async fn compress(
+        &self,
+        _ctx: Context,
+        req: CompressRequest,
+    ) -> Result<CompressResponse, TwirpErrorResponse> {
+        let result = self
+            .run(req.data, |prover, payload: CompressRequestPayload, opts| {
+                tracing::info!("compressing");
+                prover
+                    .compress(
+                        &payload.vk,
+                        payload.proof,
+                        payload.deferred_proofs,
+                        payload.opts.unwrap_or(opts),
+                    )
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+        Ok(CompressResponse { result })
+    }
+
+    async fn shrink(
+        &self,
+        _ctx: Context,
+        req: ShrinkRequest,
+    ) -> Result<ShrinkResponse, TwirpErrorResponse> {
+        let result = self
+            .run(req.data, |prover, payload: ShrinkRequestPayload, opts| {
+                tracing::info!("shrinking");
+                prover
+                    .shrink(payload.reduced_proof, payload.opts.unwrap_or(opts))
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+        Ok(ShrinkResponse { result })
+    }
+
+    async fn wrap(
+        &self,
+        _ctx: Context,
+        req: WrapRequest,
+    ) -> Result<WrapResponse, TwirpErrorResponse> {
+        let result = self
+            .run(req.data, |prover, payload: WrapRequestPayload, opts| {
+                tracing::info!("wrapping");
+                prover
+                    .wrap_bn254(payload.reduced_proof, payload.opts.unwrap_or(opts))
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+        Ok(WrapResponse { result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_core_machine::{
+        io::SP1Stdin,
+        utils::{setup_logger, tests::FIBONACCI_ELF},
+    };
+    use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+    use sp1_stark::SP1ProverOpts;
+
+    use super::CpuProverService;
+    use crate::SP1CudaProver;
+
+    #[test]
+    fn test_cpu_prover_service() {
+        setup_logger();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let service = CpuProverService::new(SP1Prover::new(), SP1ProverOpts::default(), 1);
+        let (shutdown, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = runtime.spawn(service.serve(listener, async {
+            let _ = shutdown_rx.await;
+        }));
+
+        let prover = SP1Prover::<DefaultProverComponents>::new();
+        let (pk, vk) = prover.setup(FIBONACCI_ELF);
+        let client = SP1CudaProver::connect(&endpoint).unwrap();
+        let proof = client.prove_core(&pk, &SP1Stdin::new(), SP1ProverOpts::default()).unwrap();
+        prover.verify(&proof.proof, &vk).unwrap();
+
+        // A request that fails on the server is returned to the client as an error.
+        let mut bad_pk = pk.clone();
+        bad_pk.elf = vec![0; 16];
+        assert!(client.prove_core(&bad_pk, &SP1Stdin::new(), SP1ProverOpts::default()).is_err());
+
+        shutdown.send(()).unwrap();
+        runtime.block_on(server).unwrap().unwrap();
+    }
+}
@@ -319,6 +319,78 @@ pub fn cycle_tracker(_attr: TokenStream, item: TokenStream) -> TokenStream {
     result.into()
 }
 
+/// Derives ABI encoding of a struct as SP1 public values, along with its Solidity definition.
+///
+/// The generated code implements `Codec` for the `Abi` codec and `SolidityPublicValues` from the
+/// codec module at `#[sp1_codec_path = "..."]`, which defaults to `sp1_zkvm::lib::codec`. On the
+/// host, use `#[sp1_codec_path = "sp1_sdk::codec"]`.
+///
+/// Every field must implement `alloy_sol_types::SolValue` and `Clone`.
+#[proc_macro_derive(SP1PublicValues, attributes(sp1_codec_path))]
+pub fn sp1_public_values_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let name = &ast.ident;
+    let codec_path = find_codec_path(&ast.attrs);
+    assert!(ast.generics.params.is_empty(), "Generic structs are not supported");
+
+    let fields = match &ast.data {
+        Data::Struct(s) => match &s.fields {
+            syn::Fields::Named(fields) => &fields.named,
+            _ => unimplemented!("Only structs with named fields are supported"),
+        },
+        _ => unimplemented!("Only structs are supported"),
+    };
+    let idents = fields.iter().map(|field| field.ident.as_ref().unwrap()).collect::<Vec<_>>();
+    let tys = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let field_names = idents.iter().map(|ident| ident.to_string()).collect::<Vec<_>>();
+
+    let result = quote! {
+        impl #codec_path::Codec<#name> for #codec_path::Abi {
+            fn encode(value: &#name) -> core::result::Result<Vec<u8>, #codec_path::CodecError> {
+                use #codec_path::alloy_sol_types::SolValue;
+                Ok((#(value.#idents.clone(),)*).abi_encode())
+            }
+
+            fn decode(
+                bytes: &[u8],
+            ) -> core::result::Result<(#name, usize), #codec_path::CodecError> {
+                use #codec_path::alloy_sol_types::SolValue;
+                let tuple = <(#(#tys,)*) as SolValue>::abi_decode(bytes, false)
+                    .map_err(|e| #codec_path::CodecError(e.to_string()))?;
+                let size = tuple.abi_encode().len();
+                let (#(#idents,)*) = tuple;
+                Ok((#name { #(#idents),* }, size))
+            }
+        }
+
+        impl #codec_path::SolidityPublicValues for #name {
+            const SOLIDITY_NAME: &'static str = stringify!(#name);
+
+            fn solidity_fields() -> Vec<(&'static str, &'static str)> {
+                use #codec_path::alloy_sol_types::{SolType, SolValue};
+                vec![#((<<#tys as SolValue>::SolType as SolType>::SOL_NAME, #field_names)),*]
+            }
+        }
+    };
+
+    result.into()
+}
+
+fn find_codec_path(attrs: &[syn::Attribute]) -> syn::Path {
+    for attr in attrs {
+        if attr.path.is_ident("sp1_codec_path") {
+            if let Ok(syn::Meta::NameValue(meta)) = attr.parse_meta() {
+                if let syn::Lit::Str(lit_str) = &meta.lit {
+                    if let Ok(path) = lit_str.parse::<syn::Path>() {
+                        return path;
+                    }
+                }
+            }
+        }
+    }
+    parse_quote!(sp1_zkvm::lib::codec)
+}
+
 fn find_execution_record_path(attrs: &[syn::Attribute]) -> syn::Path {
     for attr in attrs {
         if attr.path.is_ident("execution_record_path") {
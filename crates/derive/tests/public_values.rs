@@ -0,0 +1,39 @@
+//! Checks that `#[derive(SP1PublicValues)]` compiles next to the blanket `Codec` impl for `Abi`
+//! and round-trips through it. This runs without any features enabled, unlike the codec tests in
+//! `sp1-primitives`.
+
+use sp1_codec::{Abi, Codec, SolidityPublicValues};
+use sp1_derive::SP1PublicValues;
+
+#[derive(Debug, Clone, PartialEq, SP1PublicValues)]
+#[sp1_codec_path = "sp1_codec"]
+struct PublicValuesStruct {
+    n: u32,
+    a: u64,
+    name: String,
+}
+
+#[test]
+fn test_derive_round_trip() {
+    let value = PublicValuesStruct { n: 10, a: 55, name: String::from("fib") };
+    let bytes = <Abi as Codec<PublicValuesStruct>>::encode(&value).unwrap();
+    let (decoded, size) = <Abi as Codec<PublicValuesStruct>>::decode(&bytes).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(size, bytes.len());
+
+    // The blanket impl still applies to plain `SolValue` types.
+    let bytes = <Abi as Codec<u16>>::encode(&1u16).unwrap();
+    assert_eq!(<Abi as Codec<u16>>::decode(&bytes).unwrap(), (1u16, 32));
+}
+
+#[test]
+fn test_derive_solidity_library() {
+    assert_eq!(PublicValuesStruct::SOLIDITY_NAME, "PublicValuesStruct");
+    assert_eq!(
+        PublicValuesStruct::solidity_fields(),
+        vec![("uint32", "n"), ("uint64", "a"), ("string", "name")]
+    );
+    let library = PublicValuesStruct::solidity_library();
+    assert!(library.contains("struct PublicValuesStruct {"));
+    assert!(library.contains("library PublicValuesStructLib {"));
+}
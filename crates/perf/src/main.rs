@@ -139,7 +139,7 @@ fn main() {
                 time_operation(|| prover.execute(&elf, &stdin, context.clone()));
 
             let (core_proof, prove_core_duration) =
-                time_operation(|| server.prove_core(&pk, &stdin).unwrap());
+                time_operation(|| server.prove_core(&pk, &stdin, opts).unwrap());
 
             let (_, verify_core_duration) = time_operation(|| {
                 prover.verify(&core_proof.proof, &vk).expect("Proof verification failed")
@@ -147,18 +147,19 @@ fn main() {
 
             let proofs = stdin.proofs.into_iter().map(|(proof, _)| proof).collect::<Vec<_>>();
             let (compress_proof, compress_duration) =
-                time_operation(|| server.compress(&vk, core_proof, proofs).unwrap());
+                time_operation(|| server.compress(&vk, core_proof, proofs, opts).unwrap());
 
             let (_, verify_compressed_duration) =
                 time_operation(|| prover.verify_compressed(&compress_proof, &vk));
 
             let (shrink_proof, shrink_duration) =
-                time_operation(|| server.shrink(compress_proof).unwrap());
+                time_operation(|| server.shrink(compress_proof, opts).unwrap());
 
             let (_, verify_shrink_duration) =
                 time_operation(|| prover.verify_shrink(&shrink_proof, &vk));
 
-            let (_, wrap_duration) = time_operation(|| server.wrap_bn254(shrink_proof).unwrap());
+            let (_, wrap_duration) =
+                time_operation(|| server.wrap_bn254(shrink_proof, opts).unwrap());
 
             // TODO: FIX
             // let (_, verify_wrap_duration) =
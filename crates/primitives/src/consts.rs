@@ -4,6 +4,12 @@ pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 /// The size of a word in bytes.
 pub const WORD_SIZE: usize = 4;
 
+/// The file descriptor for leaves committed through a Merkle root by
+/// `sp1_zkvm::io::commit_merkle_leaves`.
+///
+/// The executor only collects the leaves if no hook is registered on this file descriptor.
+pub const FD_MERKLE_LEAVES: u32 = 9;
+
 /// Converts a slice of words to a byte vector in little endian.
 pub fn words_to_bytes_le_vec(words: &[u32]) -> Vec<u8> {
     words.iter().flat_map(|word| word.to_le_bytes().to_vec()).collect::<Vec<_>>()
@@ -1,4 +1,8 @@
-use crate::types::Buffer;
+use crate::{
+    codec::Codec,
+    merkle::{merkle_root, MerkleProof},
+    types::Buffer,
+};
 use num_bigint::BigUint;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -7,12 +11,17 @@ use sha2::{Digest, Sha256};
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SP1PublicValues {
     buffer: Buffer,
+    /// The leaves committed through a Merkle root by `sp1_zkvm::io::commit_merkle_leaves`. They
+    /// are not part of the public values digest, and are not serialized, so that the encoding of
+    /// the public values (and of the proofs holding them) is unchanged.
+    #[serde(skip)]
+    merkle_leaves: Vec<Vec<u8>>,
 }
 
 impl SP1PublicValues {
     /// Create a new `SP1PublicValues`.
     pub const fn new() -> Self {
-        Self { buffer: Buffer::new() }
+        Self { buffer: Buffer::new(), merkle_leaves: Vec::new() }
     }
 
     pub fn raw(&self) -> String {
@@ -21,7 +30,7 @@ impl SP1PublicValues {
 
     /// Create a `SP1PublicValues` from a slice of bytes.
     pub fn from(data: &[u8]) -> Self {
-        Self { buffer: Buffer::from(data) }
+        Self { buffer: Buffer::from(data), merkle_leaves: Vec::new() }
     }
 
     pub fn as_slice(&self) -> &[u8] {
@@ -37,16 +46,67 @@ impl SP1PublicValues {
         self.buffer.read()
     }
 
+    /// Read a Merkle root committed by `sp1_zkvm::io::commit_merkle_leaves`.
+    pub fn read_merkle_root(&mut self) -> [u8; 32] {
+        let mut root = [0u8; 32];
+        self.buffer.read_slice(&mut root);
+        root
+    }
+
+    /// The leaves committed through a Merkle root by the program, in commit order.
+    pub fn merkle_leaves(&self) -> &[Vec<u8>] {
+        &self.merkle_leaves
+    }
+
+    /// Set the leaves committed through a Merkle root by the program.
+    pub fn set_merkle_leaves(&mut self, leaves: Vec<Vec<u8>>) {
+        self.merkle_leaves = leaves;
+    }
+
+    /// Generate an inclusion proof of the leaf at `index` against the committed Merkle root.
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    pub fn merkle_proof(&self, index: usize) -> Option<MerkleProof> {
+        MerkleProof::generate(&self.merkle_leaves, index)
+    }
+
+    /// Check that the out-of-band leaves match the given committed Merkle root.
+    ///
+    /// The leaves are not covered by the proof itself, so this should be checked before trusting
+    /// them.
+    pub fn verify_merkle_leaves(&self, root: &[u8; 32]) -> bool {
+        merkle_root(&self.merkle_leaves) == *root
+    }
+
+    /// Read a value from the buffer, decoding it with the codec `C`.
+    ///
+    /// The codec must match the one used by `sp1_zkvm::io::commit_with` in the program.
+    pub fn read_with<C: Codec<T>, T>(&mut self) -> T {
+        self.buffer.read_with::<C, T>()
+    }
+
     /// Read a slice of bytes from the buffer.
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         self.buffer.read_slice(slice);
     }
 
+    /// Read a random number generator seed committed by `sp1_zkvm::io::read_rng_seed`.
+    pub fn read_rng_seed(&mut self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        self.buffer.read_slice(&mut seed);
+        seed
+    }
+
     /// Write a value to the buffer.
     pub fn write<T: Serialize>(&mut self, data: &T) {
         self.buffer.write(data);
     }
 
+    /// Write a value to the buffer, encoding it with the codec `C`.
+    pub fn write_with<C: Codec<T>, T>(&mut self, data: &T) {
+        self.buffer.write_with::<C, T>(data);
+    }
+
     /// Write a slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.buffer.write_slice(slice);
@@ -104,4 +164,122 @@ mod tests {
 
         assert_eq!(hash, expected_hash_biguint);
     }
+
+    #[test]
+    fn test_read_rng_seed() {
+        let seed = [7u8; 32];
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_slice(&seed);
+        public_values.write(&42u32);
+
+        assert_eq!(public_values.read_rng_seed(), seed);
+        assert_eq!(public_values.read::<u32>(), 42);
+    }
+
+    #[test]
+    fn test_codec_round_trip() {
+        use crate::codec::Bincode;
+
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<Bincode, _>(&(1u64, String::from("sp1")));
+        public_values.write(&2u32);
+
+        assert_eq!(public_values.read_with::<Bincode, (u64, String)>(), (1, String::from("sp1")));
+        assert_eq!(public_values.read::<u32>(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "postcard")]
+    fn test_codec_round_trip_postcard() {
+        use crate::codec::Postcard;
+
+        let value = (1u64, String::from("sp1"));
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<Postcard, _>(&value);
+        public_values.write(&2u32);
+
+        assert_eq!(public_values.read_with::<Postcard, (u64, String)>(), value);
+        assert_eq!(public_values.read::<u32>(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "borsh")]
+    fn test_codec_round_trip_borsh() {
+        use crate::codec::Borsh;
+
+        let value = (1u64, String::from("sp1"));
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<Borsh, _>(&value);
+        public_values.write(&2u32);
+
+        assert_eq!(public_values.read_with::<Borsh, (u64, String)>(), value);
+        assert_eq!(public_values.read::<u32>(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "abi")]
+    fn test_codec_round_trip_abi() {
+        use crate::codec::Abi;
+
+        let value = (1u64, String::from("sp1"));
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<Abi, _>(&value);
+        public_values.write_with::<Abi, _>(&7u64);
+
+        assert_eq!(public_values.read_with::<Abi, (u64, String)>(), value);
+        assert_eq!(public_values.read_with::<Abi, u64>(), 7);
+    }
+
+    #[test]
+    #[cfg(feature = "abi")]
+    fn test_sp1_public_values_derive() {
+        use crate::codec::{Abi, SolidityPublicValues};
+        use sp1_derive::SP1PublicValues;
+
+        #[derive(Debug, Clone, PartialEq, SP1PublicValues)]
+        #[sp1_codec_path = "crate::codec"]
+        struct PublicValuesStruct {
+            n: u32,
+            a: u64,
+            name: String,
+        }
+
+        let value = PublicValuesStruct { n: 10, a: 55, name: String::from("fib") };
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<Abi, _>(&value);
+        public_values.write_with::<Abi, _>(&1u16);
+
+        assert_eq!(public_values.read_with::<Abi, PublicValuesStruct>(), value);
+        assert_eq!(public_values.read_with::<Abi, u16>(), 1);
+        assert_eq!(
+            PublicValuesStruct::solidity_fields(),
+            vec![("uint32", "n"), ("uint64", "a"), ("string", "name")]
+        );
+        assert!(PublicValuesStruct::solidity_library().contains("struct PublicValuesStruct {"));
+    }
+
+    #[test]
+    fn test_merkle_leaves() {
+        let leaves = (0..7u8).map(|i| vec![i; i as usize]).collect::<Vec<_>>();
+        let root = merkle_root(&leaves);
+
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_slice(&root);
+        public_values.set_merkle_leaves(leaves.clone());
+
+        let committed_root = public_values.read_merkle_root();
+        assert!(public_values.verify_merkle_leaves(&committed_root));
+        for (i, leaf) in leaves.iter().enumerate() {
+            let proof = public_values.merkle_proof(i).unwrap();
+            assert!(proof.verify(&committed_root, leaf));
+            assert!(!proof.verify(&committed_root, &[0xff]));
+        }
+        assert!(public_values.merkle_proof(leaves.len()).is_none());
+
+        // The root binds the number of leaves, so a proof cannot claim a different tree shape.
+        let mut proof = public_values.merkle_proof(0).unwrap();
+        proof.num_leaves = 8;
+        assert!(!proof.verify(&committed_root, &leaves[0]));
+        assert_ne!(merkle_root::<Vec<u8>>(&[]), merkle_root(&[Vec::<u8>::new()]));
+    }
 }
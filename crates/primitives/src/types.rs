@@ -1,5 +1,7 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+use crate::codec::Codec;
+
 #[derive(Debug, Clone, Copy)]
 pub enum RecursionProgramType {
     Core,
@@ -40,6 +42,13 @@ impl Buffer {
         result
     }
 
+    /// Read an object from the buffer, decoding it with the codec `C`.
+    pub fn read_with<C: Codec<T>, T>(&mut self) -> T {
+        let (result, nb_bytes) = C::decode(&self.data[self.ptr..]).expect("failed to deserialize");
+        self.ptr += nb_bytes;
+        result
+    }
+
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         slice.copy_from_slice(&self.data[self.ptr..self.ptr + slice.len()]);
         self.ptr += slice.len();
@@ -52,6 +61,12 @@ impl Buffer {
         self.data.extend(tmp);
     }
 
+    /// Write an object to the buffer, encoding it with the codec `C`.
+    pub fn write_with<C: Codec<T>, T>(&mut self, data: &T) {
+        let tmp = C::encode(data).expect("serialization failed");
+        self.data.extend(tmp);
+    }
+
     /// Write the slice of bytes to the buffer.                                                   
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.data.extend_from_slice(slice);
@@ -0,0 +1,73 @@
+//! Proves a program with distributed core proving, see `sp1_prover::distributed`.
+//!
+//! Any number of `core_worker` processes can connect to the coordinator's address. Once every
+//! checkpoint is proven, the core proof is compressed and written to `--output`.
+
+use std::{fs, net::TcpListener, path::PathBuf};
+
+use clap::Parser;
+use sp1_core_executor::SP1Context;
+use sp1_core_machine::{io::SP1Stdin, utils::setup_logger};
+use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+use sp1_stark::SP1ProverOpts;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The path of the program ELF.
+    #[clap(long)]
+    elf: PathBuf,
+    /// The path of a bincode-serialized `SP1Stdin`. Defaults to an empty input.
+    #[clap(long)]
+    stdin: Option<PathBuf>,
+    /// The TCP address to accept workers on.
+    #[clap(long, conflicts_with = "unix", required_unless_present = "unix")]
+    listen: Option<String>,
+    /// The path of the Unix socket to accept workers on.
+    #[clap(long)]
+    unix: Option<PathBuf>,
+    /// Where to write the bincode-serialized compressed proof.
+    #[clap(long)]
+    output: PathBuf,
+}
+
+fn main() {
+    setup_logger();
+    let args = Args::parse();
+
+    let elf = fs::read(&args.elf).expect("failed to read elf");
+    let stdin = match &args.stdin {
+        Some(path) => bincode::deserialize(&fs::read(path).expect("failed to read stdin"))
+            .expect("failed to deserialize stdin"),
+        None => SP1Stdin::new(),
+    };
+    let opts = SP1ProverOpts::default();
+    let context = SP1Context::default();
+
+    let prover = SP1Prover::<DefaultProverComponents>::new();
+    let (pk, vk) = prover.setup(&elf);
+
+    tracing::info!("waiting for workers");
+    let core_proof = match (&args.listen, &args.unix) {
+        (Some(addr), _) => {
+            let listener = TcpListener::bind(addr).expect("failed to bind");
+            prover.prove_core_distributed(&pk, &stdin, opts, context, &listener)
+        }
+        #[cfg(unix)]
+        (None, Some(path)) => {
+            let listener = std::os::unix::net::UnixListener::bind(path).expect("failed to bind");
+            let proof = prover.prove_core_distributed(&pk, &stdin, opts, context, &listener);
+            let _ = fs::remove_file(path);
+            proof
+        }
+        _ => unreachable!(),
+    }
+    .unwrap();
+    prover.verify(&core_proof.proof, &vk).unwrap();
+
+    tracing::info!("compress");
+    let deferred_proofs = stdin.proofs.iter().map(|(proof, _)| proof.clone()).collect();
+    let compressed_proof = prover.compress(&vk, core_proof, deferred_proofs, opts).unwrap();
+    fs::write(&args.output, bincode::serialize(&compressed_proof).unwrap())
+        .expect("failed to write proof");
+}
@@ -0,0 +1,48 @@
+//! Runs a worker for distributed core proving, see `sp1_prover::distributed`.
+
+use std::{net::TcpStream, path::PathBuf, thread, time::Duration};
+
+use clap::Parser;
+use sp1_core_machine::utils::setup_logger;
+use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The TCP address of the coordinator.
+    #[clap(long, conflicts_with = "unix", required_unless_present = "unix")]
+    connect: Option<String>,
+    /// The path of the Unix socket of the coordinator.
+    #[clap(long)]
+    unix: Option<PathBuf>,
+    /// Exit after serving one proof instead of reconnecting.
+    #[clap(long, default_value_t = false)]
+    once: bool,
+}
+
+fn main() {
+    setup_logger();
+    let args = Args::parse();
+    let prover = SP1Prover::<DefaultProverComponents>::new();
+
+    loop {
+        let result = match (&args.connect, &args.unix) {
+            (Some(addr), _) => {
+                TcpStream::connect(addr).map(|stream| prover.run_core_worker(stream))
+            }
+            #[cfg(unix)]
+            (None, Some(path)) => std::os::unix::net::UnixStream::connect(path)
+                .map(|stream| prover.run_core_worker(stream)),
+            _ => unreachable!(),
+        };
+        match result {
+            Ok(Ok(proven)) => tracing::info!("proved {proven} checkpoints"),
+            Ok(Err(e)) => tracing::error!("worker failed: {e}"),
+            // The coordinator is not listening yet, or has finished.
+            Err(_) => thread::sleep(Duration::from_secs(1)),
+        }
+        if args.once {
+            break;
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use clap::Parser;
+use sp1_core_machine::utils::setup_logger;
+use sp1_prover::build::{build_constraints_spans_and_witness, dummy_proof};
+use sp1_recursion_gnark_ffi::{ark::SynthesizedWrapCircuit, GnarkWitness};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(short, long)]
+    build_dir: PathBuf,
+}
+
+pub fn main() {
+    setup_logger();
+    let args = Args::parse();
+    std::fs::create_dir_all(&args.build_dir).expect("failed to create build directory");
+
+    let (wrap_vk, wrapped_proof) = dummy_proof();
+    let (constraints, spans, witness) =
+        build_constraints_spans_and_witness(&wrap_vk, &wrapped_proof);
+
+    tracing::info!("synthesizing the r1cs");
+    let witness = GnarkWitness::new(witness);
+    let circuit = SynthesizedWrapCircuit::new(&constraints, &spans, &witness);
+    circuit.is_satisfied().expect("the template witness does not satisfy the circuit");
+
+    tracing::info!("writing the r1cs and the witness");
+    let r1cs_file = BufWriter::new(File::create(args.build_dir.join("wrap.r1cs")).unwrap());
+    circuit.write_r1cs(r1cs_file).unwrap();
+    let wtns_file = BufWriter::new(File::create(args.build_dir.join("wrap.wtns")).unwrap());
+    circuit.write_wtns(wtns_file).unwrap();
+    let stats_file = File::create(args.build_dir.join("wrap_stats.json")).unwrap();
+    serde_json::to_writer_pretty(stats_file, circuit.stats()).unwrap();
+
+    println!("{}", circuit.stats());
+}
@@ -18,12 +18,12 @@ pub use sp1_recursion_core::stark::sp1_dev_mode;
 
 pub use sp1_recursion_circuit::witness::{OuterWitness, Witnessable};
 
-use sp1_recursion_gnark_ffi::{Groth16Bn254Prover, PlonkBn254Prover};
+use sp1_recursion_gnark_ffi::PlonkBn254Prover;
 use sp1_stark::{SP1ProverOpts, ShardProof, StarkVerifyingKey};
 
 use crate::{
     utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes},
-    OuterSC, SP1Prover, WrapAir,
+    Groth16Bn254Prover, OuterSC, SP1Prover, WrapAir,
 };
 
 /// Tries to build the PLONK artifacts inside the development directory.
@@ -54,8 +54,12 @@ pub fn plonk_bn254_artifacts_dev_dir() -> PathBuf {
 }
 
 /// Gets the directory where the groth16 artifacts are installed in development mode.
+///
+/// The artifacts of the arkworks backend have their own setup, so they are kept apart from the
+/// ones built by gnark.
 pub fn groth16_bn254_artifacts_dev_dir() -> PathBuf {
-    dirs::home_dir().unwrap().join(".sp1").join("circuits").join("dev")
+    let dev_dir = if cfg!(feature = "ark-groth16") { "dev-ark" } else { "dev" };
+    dirs::home_dir().unwrap().join(".sp1").join("circuits").join(dev_dir)
 }
 
 /// Build the plonk bn254 artifacts to the given directory for the given verification key and
@@ -84,6 +88,21 @@ pub fn build_groth16_bn254_artifacts(
     Groth16Bn254Prover::build(constraints, witness, build_dir);
 }
 
+/// Builds the groth16 bn254 artifacts to the given directory, unless they were already built
+/// there.
+pub fn try_build_groth16_bn254_artifacts_in(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    build_dir: impl Into<PathBuf>,
+) -> PathBuf {
+    let build_dir = build_dir.into();
+    if !build_dir.join("groth16_vk.bin").exists() {
+        println!("[sp1] building groth16 bn254 artifacts in {}", build_dir.display());
+        build_groth16_bn254_artifacts(template_vk, template_proof, &build_dir);
+    }
+    build_dir
+}
+
 /// Builds the plonk bn254 artifacts to the given directory.
 ///
 /// This may take a while as it needs to first generate a dummy proof and then it needs to compile
@@ -123,12 +142,23 @@ pub fn build_constraints_and_witness(
     template_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: &ShardProof<OuterSC>,
 ) -> (Vec<Constraint>, OuterWitness<OuterConfig>) {
+    let (constraints, _, witness) =
+        build_constraints_spans_and_witness(template_vk, template_proof);
+    (constraints, witness)
+}
+
+/// Build the verifier constraints, the cycle tracker span of each constraint, and the template
+/// witness for the circuit.
+pub fn build_constraints_spans_and_witness(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+) -> (Vec<Constraint>, Vec<String>, OuterWitness<OuterConfig>) {
     tracing::info!("building verifier constraints");
     let template_input = SP1CompressWitnessValues {
         vks_and_proofs: vec![(template_vk.clone(), template_proof.clone())],
         is_complete: true,
     };
-    let constraints =
+    let (constraints, spans) =
         tracing::info_span!("wrap circuit").in_scope(|| build_outer_circuit(&template_input));
 
     let pv: &RecursionPublicValues<BabyBear> = template_proof.public_values.as_slice().borrow();
@@ -143,7 +173,7 @@ pub fn build_constraints_and_witness(
     witness.write_committed_values_digest(committed_values_digest);
     witness.write_vkey_hash(vkey_hash);
 
-    (constraints, witness)
+    (constraints, spans, witness)
 }
 
 /// Generate a dummy proof that we can use to build the circuit. We need this to know the shape of
@@ -176,7 +206,9 @@ pub fn dummy_proof() -> (StarkVerifyingKey<OuterSC>, ShardProof<OuterSC>) {
     (wrapped_proof.vk, wrapped_proof.proof)
 }
 
-fn build_outer_circuit(template_input: &SP1CompressWitnessValues<OuterSC>) -> Vec<Constraint> {
+fn build_outer_circuit(
+    template_input: &SP1CompressWitnessValues<OuterSC>,
+) -> (Vec<Constraint>, Vec<String>) {
     let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
 
     let wrap_span = tracing::debug_span!("build wrap circuit").entered();
@@ -203,8 +235,36 @@ fn build_outer_circuit(template_input: &SP1CompressWitnessValues<OuterSC>) -> Ve
     SP1WrapVerifier::verify(&mut builder, &wrap_machine, input);
 
     let mut backend = ConstraintCompiler::<OuterConfig>::default();
-    let operations = backend.emit(builder.into_operations());
+    let operations = backend.emit_with_spans(builder.into_operations());
     wrap_span.exit();
 
     operations
 }
+
+#[cfg(all(test, feature = "ark-groth16"))]
+mod tests {
+    use serial_test::serial;
+    use sp1_core_machine::utils::setup_logger;
+    use sp1_recursion_gnark_ffi::{ark::SynthesizedWrapCircuit, GnarkWitness};
+
+    use super::*;
+
+    #[test]
+    #[serial]
+    fn test_ark_wrap_circuit_satisfied() {
+        setup_logger();
+        let (wrap_vk, wrapped_proof) = dummy_proof();
+        let (constraints, spans, witness) =
+            build_constraints_spans_and_witness(&wrap_vk, &wrapped_proof);
+
+        let witness = GnarkWitness::new(witness);
+        let circuit = SynthesizedWrapCircuit::new(&constraints, &spans, &witness);
+        circuit.is_satisfied().expect("the wrap witness does not satisfy the circuit");
+
+        // A witness committing to different public values must not satisfy the circuit.
+        let mut tampered = witness.clone();
+        tampered.committed_values_digest = "1".to_string();
+        let circuit = SynthesizedWrapCircuit::new(&constraints, &spans, &tampered);
+        assert!(circuit.is_satisfied().is_err());
+    }
+}
@@ -0,0 +1,527 @@
+//! Distributed core proving across worker processes.
+//!
+//! A coordinator executes the program, commits to the global traces of its shards and hands out
+//! the checkpoints of the execution to workers, which re-execute them and prove their shards. The
+//! coordinator then assembles the core proof, which can be compressed as usual.
+//!
+//! Workers connect to the coordinator over TCP or a Unix socket and exchange length-prefixed
+//! bincode messages:
+//!
+//! ```text
+//! coordinator -> worker   Setup { elf, vk, opts, commitments }
+//! coordinator -> worker   Task { index, task }
+//! worker -> coordinator   Proofs { index, proofs }
+//! ...
+//! coordinator -> worker   Done
+//! ```
+//!
+//! The coordinator checks every returned shard proof against the verifying key and the shard's
+//! commitment. A task whose worker disconnects, times out or returns invalid proofs is handed to
+//! another worker.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read, Seek, Write},
+    net::{TcpListener, TcpStream},
+    ops::Range,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use p3_baby_bear::BabyBear;
+use p3_challenger::FieldChallenger;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_core_executor::{ProgressMonitor, ProofPhase, SP1Context};
+use sp1_core_machine::{
+    io::SP1Stdin,
+    utils::{commit_checkpoints, prove_checkpoint, CheckpointTask, SP1CoreProverError},
+};
+use sp1_stark::{
+    baby_bear_poseidon2::BabyBearPoseidon2, Com, MachineProver, SP1CoreOpts, SP1ProverOpts,
+    ShardProof, StarkGenericConfig, Verifier,
+};
+
+use crate::{
+    components::SP1ProverComponents, CoreSC, HashableKey, SP1CoreProof, SP1CoreProofData,
+    SP1Prover, SP1ProvingKey, SP1VerifyingKey,
+};
+
+/// How long the coordinator waits between polls for new workers and unassigned tasks.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long the coordinator waits on a read from or write to a worker before giving up on it and
+/// requeueing its task. This bounds the time to prove a single checkpoint.
+const WORKER_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The largest message accepted from the other end of a connection.
+const MAX_MESSAGE_SIZE: u64 = 1 << 32;
+
+/// A listener that accepts connections from workers.
+pub trait WorkerListener: Sync {
+    /// The connection to a worker.
+    type Stream: Read + Write + Send;
+
+    /// Switches the listener to non-blocking mode, so that [Self::accept] returns
+    /// [io::ErrorKind::WouldBlock] when no worker is waiting.
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+
+    /// Accepts a connection from a worker, in blocking mode and with read and write timeouts of
+    /// [WORKER_TIMEOUT].
+    fn accept(&self) -> io::Result<Self::Stream>;
+}
+
+impl WorkerListener for TcpListener {
+    type Stream = TcpStream;
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        TcpListener::set_nonblocking(self, nonblocking)
+    }
+
+    fn accept(&self) -> io::Result<TcpStream> {
+        let (stream, _) = TcpListener::accept(self)?;
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(WORKER_TIMEOUT))?;
+        stream.set_write_timeout(Some(WORKER_TIMEOUT))?;
+        Ok(stream)
+    }
+}
+
+#[cfg(unix)]
+impl WorkerListener for std::os::unix::net::UnixListener {
+    type Stream = std::os::unix::net::UnixStream;
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        std::os::unix::net::UnixListener::set_nonblocking(self, nonblocking)
+    }
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        let (stream, _) = std::os::unix::net::UnixListener::accept(self)?;
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(WORKER_TIMEOUT))?;
+        stream.set_write_timeout(Some(WORKER_TIMEOUT))?;
+        Ok(stream)
+    }
+}
+
+/// A message sent by the coordinator to a worker.
+#[derive(Serialize, Deserialize)]
+enum CoordinatorMessage {
+    /// The program being proven and the global commitments of its shards.
+    Setup {
+        elf: Vec<u8>,
+        vk: SP1VerifyingKey,
+        opts: SP1CoreOpts,
+        commitments: Vec<(Com<CoreSC>, Vec<BabyBear>)>,
+    },
+    /// A serialized [CheckpointTask] to prove.
+    Task { index: usize, task: Vec<u8> },
+    /// There are no tasks left.
+    Done,
+}
+
+/// A message sent by a worker to the coordinator.
+#[derive(Serialize, Deserialize)]
+enum WorkerMessage {
+    /// The shard proofs of the checkpoint `index`.
+    Proofs { index: usize, proofs: Vec<ShardProof<CoreSC>> },
+}
+
+fn send<T: Serialize>(stream: &mut impl Write, message: &T) -> Result<(), SP1CoreProverError> {
+    let bytes = bincode::serialize(message).map_err(SP1CoreProverError::SerializationError)?;
+    stream.write_all(&(bytes.len() as u64).to_le_bytes()).map_err(SP1CoreProverError::IoError)?;
+    stream.write_all(&bytes).map_err(SP1CoreProverError::IoError)?;
+    stream.flush().map_err(SP1CoreProverError::IoError)
+}
+
+fn recv<T: DeserializeOwned>(stream: &mut impl Read) -> Result<T, SP1CoreProverError> {
+    let mut len = [0u8; 8];
+    stream.read_exact(&mut len).map_err(SP1CoreProverError::IoError)?;
+    let len = u64::from_le_bytes(len);
+    if len > MAX_MESSAGE_SIZE {
+        return Err(invalid_data(format!("message of {len} bytes exceeds the maximum size")));
+    }
+    // Read through `take` so that the buffer only grows with the bytes actually received.
+    let mut bytes = Vec::new();
+    stream.take(len).read_to_end(&mut bytes).map_err(SP1CoreProverError::IoError)?;
+    if bytes.len() as u64 != len {
+        return Err(SP1CoreProverError::IoError(io::ErrorKind::UnexpectedEof.into()));
+    }
+    bincode::deserialize(&bytes).map_err(SP1CoreProverError::SerializationError)
+}
+
+fn invalid_data(message: impl Into<String>) -> SP1CoreProverError {
+    SP1CoreProverError::IoError(io::Error::new(io::ErrorKind::InvalidData, message.into()))
+}
+
+/// Checks the proof of the shard at the given index of the execution.
+type ShardCheck<'a> =
+    dyn Fn(usize, &ShardProof<CoreSC>) -> Result<(), SP1CoreProverError> + Sync + 'a;
+
+/// The tasks of a distributed proof, shared by the threads serving the workers.
+struct TaskQueue {
+    pending: Mutex<VecDeque<(usize, File)>>,
+    proofs: Mutex<Vec<Option<Vec<ShardProof<CoreSC>>>>>,
+    /// The indices of the shards of each task in the proof.
+    shards: Vec<Range<usize>>,
+    remaining: AtomicUsize,
+    proven_shards: AtomicUsize,
+    num_shards: usize,
+}
+
+impl TaskQueue {
+    /// Takes the next unassigned task, waiting for one to be requeued while other workers still
+    /// have tasks in flight. Returns `None` once all tasks are proven or the proof is cancelled.
+    fn next(&self, progress: &ProgressMonitor) -> Option<(usize, File)> {
+        loop {
+            if self.remaining.load(Ordering::SeqCst) == 0 || progress.is_cancelled() {
+                return None;
+            }
+            if let Some(task) = self.pending.lock().unwrap().pop_front() {
+                return Some(task);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Serves a worker until all tasks are proven. A task the worker fails to prove is requeued.
+    fn serve<S: Read + Write>(
+        &self,
+        mut stream: S,
+        setup: &[u8],
+        check_shard: &ShardCheck<'_>,
+        progress: &ProgressMonitor,
+    ) -> Result<(), SP1CoreProverError> {
+        stream.write_all(setup).map_err(SP1CoreProverError::IoError)?;
+        while let Some((index, mut file)) = self.next(progress) {
+            let result = (|| {
+                let mut task = Vec::new();
+                file.rewind().map_err(SP1CoreProverError::IoError)?;
+                file.read_to_end(&mut task).map_err(SP1CoreProverError::IoError)?;
+                send(&mut stream, &CoordinatorMessage::Task { index, task })?;
+                let WorkerMessage::Proofs { index: proven, proofs } = recv(&mut stream)?;
+                if proven != index {
+                    return Err(invalid_data(format!(
+                        "worker returned checkpoint {proven}, expected {index}"
+                    )));
+                }
+                let shards = self.shards[index].clone();
+                if proofs.len() != shards.len() {
+                    return Err(invalid_data(format!(
+                        "worker returned {} shard proofs for checkpoint {index}, expected {}",
+                        proofs.len(),
+                        shards.len()
+                    )));
+                }
+                for (shard, proof) in shards.zip(proofs.iter()) {
+                    check_shard(shard, proof)?;
+                }
+                Ok(proofs)
+            })();
+            match result {
+                Ok(proofs) => {
+                    for _ in 0..proofs.len() {
+                        let index = self.proven_shards.fetch_add(1, Ordering::SeqCst);
+                        progress.report(ProofPhase::CoreShard {
+                            index: index + 1,
+                            num_shards: self.num_shards,
+                        });
+                    }
+                    self.proofs.lock().unwrap()[index] = Some(proofs);
+                    self.remaining.fetch_sub(1, Ordering::SeqCst);
+                }
+                Err(e) => {
+                    self.pending.lock().unwrap().push_back((index, file));
+                    return Err(e);
+                }
+            }
+        }
+        send(&mut stream, &CoordinatorMessage::Done)
+    }
+}
+
+impl<C: SP1ProverComponents> SP1Prover<C> {
+    /// Generate the core proof of a program with the help of workers connecting to `listener`.
+    ///
+    /// The coordinator executes the program and commits to the global traces of its shards, then
+    /// sends each checkpoint of the execution to a worker running [Self::run_core_worker], which
+    /// proves its shards. This returns once every checkpoint has been proven, so it waits for at
+    /// least one worker to connect. Workers must use the same core shape configuration as the
+    /// coordinator.
+    ///
+    /// The resulting proof is the same as the one of [Self::prove_core], and can be compressed
+    /// with [Self::compress].
+    pub fn prove_core_distributed<'a, L: WorkerListener>(
+        &'a self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        mut context: SP1Context<'a>,
+        listener: &L,
+    ) -> Result<SP1CoreProof, SP1CoreProverError> {
+        context.subproof_verifier.replace(Arc::new(self));
+        let progress = context.progress.clone();
+        let program = self.get_program(&pk.elf).unwrap();
+        let plan = commit_checkpoints::<_, C::CoreProver>(
+            &self.core_prover,
+            program,
+            stdin,
+            opts.core_opts,
+            context,
+            self.core_shape_config.as_ref(),
+        )?;
+        let num_tasks = plan.tasks.len();
+        let shards = plan
+            .num_shards
+            .iter()
+            .scan(0, |start, &num_shards| {
+                *start += num_shards;
+                Some(*start - num_shards..*start)
+            })
+            .collect();
+        let queue = TaskQueue {
+            pending: Mutex::new(plan.tasks.into_iter().enumerate().collect()),
+            proofs: Mutex::new(vec![None; num_tasks]),
+            shards,
+            remaining: AtomicUsize::new(num_tasks),
+            proven_shards: AtomicUsize::new(0),
+            num_shards: plan.commitments.len(),
+        };
+
+        // The setup message is the same for every worker, so serialize it once.
+        let mut setup = Vec::new();
+        send(
+            &mut setup,
+            &CoordinatorMessage::Setup {
+                elf: pk.elf.clone(),
+                vk: pk.vk.clone(),
+                opts: opts.core_opts,
+                commitments: plan.commitments.clone(),
+            },
+        )?;
+
+        // Recompute the challenges the workers prove against, so that every returned shard proof
+        // can be verified as it arrives.
+        let machine = self.core_prover.machine();
+        let mut challenger = self.core_prover.config().challenger();
+        pk.vk.vk.observe_into(&mut challenger);
+        for (commit, public_values) in plan.commitments.iter() {
+            self.core_prover.observe(&mut challenger, *commit, public_values);
+        }
+        let global_permutation_challenges: [<CoreSC as StarkGenericConfig>::Challenge; 2] =
+            [challenger.sample_ext_element(), challenger.sample_ext_element()];
+        let check_shard = |shard: usize, proof: &ShardProof<CoreSC>| {
+            let (commit, public_values) = &plan.commitments[shard];
+            if proof.commitment.global_main_commit != *commit
+                || proof.public_values.get(..public_values.len()) != Some(public_values.as_slice())
+            {
+                return Err(invalid_data(format!("shard {shard} does not match its commitment")));
+            }
+            let chips = machine.shard_chips_ordered(&proof.chip_ordering).collect::<Vec<_>>();
+            Verifier::verify_shard(
+                machine.config(),
+                &pk.vk.vk,
+                &chips,
+                &mut challenger.clone(),
+                proof,
+                &global_permutation_challenges,
+            )
+            .map_err(|e| invalid_data(format!("invalid proof of shard {shard}: {e}")))
+        };
+
+        // Accept workers until every task has been proven.
+        listener.set_nonblocking(true).map_err(SP1CoreProverError::IoError)?;
+        let span = tracing::Span::current().clone();
+        let accepted = thread::scope(|s| loop {
+            if queue.remaining.load(Ordering::SeqCst) == 0 {
+                break Ok(());
+            }
+            progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
+            match listener.accept() {
+                Ok(stream) => {
+                    let (queue, setup, check_shard, progress, span) =
+                        (&queue, &setup, &check_shard, &progress, &span);
+                    s.spawn(move || {
+                        let _span = span.enter();
+                        tracing::info!("core worker connected");
+                        if let Err(e) = queue.serve(stream, setup, check_shard, progress) {
+                            tracing::warn!("core worker failed: {e}");
+                        }
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(e) => break Err(SP1CoreProverError::IoError(e)),
+            }
+        });
+        listener.set_nonblocking(false).map_err(SP1CoreProverError::IoError)?;
+        accepted?;
+        progress.check_cancelled().map_err(SP1CoreProverError::Cancelled)?;
+
+        let shard_proofs =
+            queue.proofs.into_inner().unwrap().into_iter().flat_map(Option::unwrap).collect();
+        let cycles = plan.report.total_instruction_count();
+        Self::check_for_high_cycles(cycles);
+        Ok(SP1CoreProof {
+            proof: SP1CoreProofData(shard_proofs),
+            stdin: stdin.clone(),
+            public_values: plan.public_values,
+            cycles,
+        })
+    }
+
+    /// Prove checkpoints for the coordinator at the other end of `stream`, until it has no tasks
+    /// left. Returns the number of checkpoints proven.
+    pub fn run_core_worker(
+        &self,
+        mut stream: impl Read + Write,
+    ) -> Result<usize, SP1CoreProverError> {
+        let CoordinatorMessage::Setup { elf, vk, opts, commitments } = recv(&mut stream)? else {
+            return Err(SP1CoreProverError::IoError(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected a setup message",
+            )));
+        };
+
+        // Setup the program, checking that the keys match the coordinator's.
+        let program = self.get_program(&elf).unwrap();
+        let (pk, worker_vk) = self.core_prover.setup(&program);
+        if worker_vk.hash_u32() != vk.vk.hash_u32() {
+            return Err(invalid_data(
+                "verifying key mismatch: the worker and coordinator core shape configs differ",
+            ));
+        }
+
+        let mut proven = 0;
+        loop {
+            match recv(&mut stream)? {
+                CoordinatorMessage::Task { index, task } => {
+                    let task: CheckpointTask = bincode::deserialize(&task)
+                        .map_err(SP1CoreProverError::SerializationError)?;
+                    let proofs = tracing::info_span!("prove checkpoint", index).in_scope(|| {
+                        prove_checkpoint::<BabyBearPoseidon2, C::CoreProver>(
+                            &self.core_prover,
+                            &pk,
+                            program.clone(),
+                            task,
+                            &commitments,
+                            opts,
+                            self.core_shape_config.as_ref(),
+                        )
+                    });
+                    send(&mut stream, &WorkerMessage::Proofs { index, proofs })?;
+                    proven += 1;
+                }
+                CoordinatorMessage::Done => return Ok(proven),
+                CoordinatorMessage::Setup { .. } => {
+                    return Err(SP1CoreProverError::IoError(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "unexpected setup message",
+                    )))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpStream;
+
+    use serial_test::serial;
+    use sp1_core_machine::utils::setup_logger;
+
+    use super::*;
+    use crate::components::DefaultProverComponents;
+
+    #[test]
+    #[serial]
+    fn test_prove_core_distributed() {
+        setup_logger();
+        let elf = include_bytes!("../../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        let prover = SP1Prover::<DefaultProverComponents>::new();
+        let (pk, vk) = prover.setup(elf);
+
+        // Use small checkpoints so that the execution is split across both workers.
+        let mut opts = SP1ProverOpts::default();
+        opts.core_opts.shard_size = 1 << 12;
+        opts.core_opts.shard_batch_size = 1;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proof = thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| {
+                    // A worker that connects after the last checkpoint is proven is dropped.
+                    if let Ok(stream) = TcpStream::connect(addr) {
+                        let _ = prover.run_core_worker(stream);
+                    }
+                });
+            }
+            let proof = prover.prove_core_distributed(
+                &pk,
+                &SP1Stdin::new(),
+                opts,
+                SP1Context::default(),
+                &listener,
+            );
+            drop(listener);
+            proof
+        })
+        .unwrap();
+
+        assert!(proof.proof.0.len() > 1);
+        prover.verify(&proof.proof, &vk).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_prove_core_distributed_requeues_invalid_proofs() {
+        setup_logger();
+        let elf = include_bytes!("../../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        let prover = SP1Prover::<DefaultProverComponents>::new();
+        let (pk, vk) = prover.setup(elf);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proof = thread::scope(|s| {
+            s.spawn(|| {
+                // A worker that returns no proofs for its checkpoint is disconnected, and its task
+                // is proven by the next worker.
+                let mut stream = TcpStream::connect(addr).unwrap();
+                let _: CoordinatorMessage = recv(&mut stream).unwrap();
+                let CoordinatorMessage::Task { index, .. } = recv(&mut stream).unwrap() else {
+                    panic!("expected a task");
+                };
+                send(&mut stream, &WorkerMessage::Proofs { index, proofs: vec![] }).unwrap();
+                assert!(recv::<CoordinatorMessage>(&mut stream).is_err());
+
+                let stream = TcpStream::connect(addr).unwrap();
+                prover.run_core_worker(stream).unwrap();
+            });
+            let proof = prover.prove_core_distributed(
+                &pk,
+                &SP1Stdin::new(),
+                SP1ProverOpts::default(),
+                SP1Context::default(),
+                &listener,
+            );
+            drop(listener);
+            proof
+        })
+        .unwrap();
+
+        prover.verify(&proof.proof, &vk).unwrap();
+    }
+
+    #[test]
+    fn test_recv_rejects_oversized_message() {
+        let mut bytes = (MAX_MESSAGE_SIZE + 1).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0; 16]);
+        assert!(recv::<WorkerMessage>(&mut bytes.as_slice()).is_err());
+    }
+}
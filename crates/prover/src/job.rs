@@ -0,0 +1,282 @@
+//! Intermediate artifacts of a proof, persisted to a job directory so that proving can resume
+//! after a failure.
+//!
+//! A job directory contains a `manifest.json` describing the job and one file per completed
+//! stage:
+//!
+//! ```text
+//! manifest.json         {"version": "v3.0.0", "inputs_hash": "...", "opts": {...}, "stage": "shrink"}
+//! public_values.bin
+//! core.bin              SP1CoreProof
+//! compress.bin          SP1ReduceProof<InnerSC>
+//! shrink.bin            SP1ReduceProof<InnerSC>
+//! wrap.bin              SP1ReduceProof<OuterSC>
+//! ```
+//!
+//! Artifacts are written before the manifest is updated, and both are written atomically, so a
+//! job that is interrupted at any point resumes from its last completed stage.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_core_machine::{io::SP1Stdin, SP1_CIRCUIT_VERSION};
+use sp1_primitives::io::SP1PublicValues;
+use sp1_stark::SP1ProverOpts;
+use thiserror::Error;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PUBLIC_VALUES_FILE: &str = "public_values.bin";
+
+/// A stage of proof generation whose output is persisted by a [ProofJob].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofStage {
+    /// The core shard proofs, stored as an [crate::SP1CoreProof].
+    Core,
+    /// The compressed proof, stored as an [crate::SP1ReduceProof] over [crate::InnerSC].
+    Compress,
+    /// The shrunk proof, stored as an [crate::SP1ReduceProof] over [crate::InnerSC].
+    Shrink,
+    /// The wrapped proof, stored as an [crate::SP1ReduceProof] over [crate::OuterSC].
+    Wrap,
+}
+
+impl ProofStage {
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Core => "core.bin",
+            Self::Compress => "compress.bin",
+            Self::Shrink => "shrink.bin",
+            Self::Wrap => "wrap.bin",
+        }
+    }
+}
+
+/// The description of a [ProofJob], stored as `manifest.json` in the job directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobManifest {
+    /// The circuit version the artifacts were generated with.
+    pub version: String,
+    /// The SHA-256 digest of the program and its input, hex encoded.
+    pub inputs_hash: String,
+    /// The prover options the artifacts were generated with.
+    pub opts: SP1ProverOpts,
+    /// The last completed stage, if any.
+    pub stage: Option<ProofStage>,
+}
+
+/// An error that occurred while reading or writing a [ProofJob].
+#[derive(Error, Debug)]
+pub enum ProofJobError {
+    #[error("io error in {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("failed to parse manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+    #[error("failed to (de)serialize artifact: {0}")]
+    Artifact(#[from] bincode::Error),
+    #[error("job was created with circuit version {found}, expected {expected}")]
+    VersionMismatch { expected: String, found: String },
+    #[error("job was created for a different program or input")]
+    InputsMismatch,
+    #[error("job was created with different prover options")]
+    OptsMismatch,
+    #[error("stage {0:?} has not been completed")]
+    StageNotCompleted(ProofStage),
+}
+
+/// A proof whose intermediate artifacts are persisted to a directory.
+#[derive(Debug, Clone)]
+pub struct ProofJob {
+    dir: PathBuf,
+    manifest: JobManifest,
+}
+
+impl ProofJob {
+    /// Opens the job in `dir` for proving `elf` on `stdin` with `opts`, creating it if `dir` has
+    /// no manifest.
+    ///
+    /// Returns an error if the existing job was created with another circuit version, program,
+    /// input or prover options.
+    pub fn open(
+        dir: impl Into<PathBuf>,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+    ) -> Result<Self, ProofJobError> {
+        let dir = dir.into();
+        let inputs_hash = Self::inputs_hash(elf, stdin)?;
+        let manifest_path = dir.join(MANIFEST_FILE);
+
+        match fs::read(&manifest_path) {
+            Ok(bytes) => {
+                let manifest: JobManifest = serde_json::from_slice(&bytes)?;
+                if manifest.version != SP1_CIRCUIT_VERSION {
+                    return Err(ProofJobError::VersionMismatch {
+                        expected: SP1_CIRCUIT_VERSION.to_string(),
+                        found: manifest.version,
+                    });
+                }
+                if manifest.inputs_hash != inputs_hash {
+                    return Err(ProofJobError::InputsMismatch);
+                }
+                if manifest.opts != opts {
+                    return Err(ProofJobError::OptsMismatch);
+                }
+                tracing::info!(
+                    "resuming proof job in {} after {:?}",
+                    dir.display(),
+                    manifest.stage
+                );
+                Ok(Self { dir, manifest })
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                fs::create_dir_all(&dir)
+                    .map_err(|source| ProofJobError::Io { path: dir.clone(), source })?;
+                let job = Self {
+                    dir,
+                    manifest: JobManifest {
+                        version: SP1_CIRCUIT_VERSION.to_string(),
+                        inputs_hash,
+                        opts,
+                        stage: None,
+                    },
+                };
+                job.write_manifest()?;
+                Ok(job)
+            }
+            Err(source) => Err(ProofJobError::Io { path: manifest_path, source }),
+        }
+    }
+
+    /// The directory the job is persisted to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The manifest of the job.
+    pub fn manifest(&self) -> &JobManifest {
+        &self.manifest
+    }
+
+    /// Whether `stage` has been completed.
+    pub fn is_completed(&self, stage: ProofStage) -> bool {
+        self.manifest.stage.is_some_and(|completed| completed >= stage)
+    }
+
+    /// Loads the output of a completed stage.
+    pub fn load<T: DeserializeOwned>(&self, stage: ProofStage) -> Result<T, ProofJobError> {
+        if !self.is_completed(stage) {
+            return Err(ProofJobError::StageNotCompleted(stage));
+        }
+        self.read_artifact(stage.file_name())
+    }
+
+    /// Stores the output of `stage` and marks it as completed.
+    pub fn save<T: Serialize>(
+        &mut self,
+        stage: ProofStage,
+        artifact: &T,
+    ) -> Result<(), ProofJobError> {
+        self.write_artifact(stage.file_name(), &bincode::serialize(artifact)?)?;
+        if !self.is_completed(stage) {
+            self.manifest.stage = Some(stage);
+            self.write_manifest()?;
+        }
+        Ok(())
+    }
+
+    /// Loads the public values of the proof, which are stored with the core stage.
+    pub fn load_public_values(&self) -> Result<SP1PublicValues, ProofJobError> {
+        if !self.is_completed(ProofStage::Core) {
+            return Err(ProofJobError::StageNotCompleted(ProofStage::Core));
+        }
+        self.read_artifact(PUBLIC_VALUES_FILE)
+    }
+
+    /// Stores the public values of the proof. This must be called before the core stage is
+    /// saved.
+    pub fn save_public_values(
+        &mut self,
+        public_values: &SP1PublicValues,
+    ) -> Result<(), ProofJobError> {
+        self.write_artifact(PUBLIC_VALUES_FILE, &bincode::serialize(public_values)?)
+    }
+
+    fn inputs_hash(elf: &[u8], stdin: &SP1Stdin) -> Result<String, ProofJobError> {
+        let mut hasher = Sha256::new();
+        hasher.update((elf.len() as u64).to_le_bytes());
+        hasher.update(elf);
+        hasher.update(bincode::serialize(stdin)?);
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    fn read_artifact<T: DeserializeOwned>(&self, name: &str) -> Result<T, ProofJobError> {
+        let path = self.dir.join(name);
+        let file = fs::File::open(&path).map_err(|source| ProofJobError::Io { path, source })?;
+        Ok(bincode::deserialize_from(io::BufReader::new(file))?)
+    }
+
+    fn write_manifest(&self) -> Result<(), ProofJobError> {
+        self.write_artifact(MANIFEST_FILE, &serde_json::to_vec_pretty(&self.manifest)?)
+    }
+
+    /// Writes `bytes` to `name` through a temporary file, so that the file is never observed
+    /// partially written.
+    fn write_artifact(&self, name: &str, bytes: &[u8]) -> Result<(), ProofJobError> {
+        let path = self.dir.join(name);
+        let io_err = |source| ProofJobError::Io { path: path.clone(), source };
+        let mut file = tempfile::NamedTempFile::new_in(&self.dir).map_err(io_err)?;
+        file.write_all(bytes).map_err(io_err)?;
+        file.persist(&path).map_err(|e| io_err(e.error))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proof_job_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let elf = b"elf";
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&1u32);
+        let opts = SP1ProverOpts::default();
+
+        let mut job = ProofJob::open(dir.path(), elf, &stdin, opts).unwrap();
+        assert_eq!(job.manifest().stage, None);
+        job.save_public_values(&SP1PublicValues::from(&[1, 2, 3])).unwrap();
+        job.save(ProofStage::Core, &vec![4u8, 5]).unwrap();
+        job.save(ProofStage::Compress, &vec![6u8]).unwrap();
+
+        let job = ProofJob::open(dir.path(), elf, &stdin, opts).unwrap();
+        assert!(job.is_completed(ProofStage::Core));
+        assert!(!job.is_completed(ProofStage::Shrink));
+        assert_eq!(job.load::<Vec<u8>>(ProofStage::Core).unwrap(), vec![4, 5]);
+        assert_eq!(job.load_public_values().unwrap().as_slice(), &[1, 2, 3]);
+        assert!(matches!(
+            job.load::<Vec<u8>>(ProofStage::Wrap),
+            Err(ProofJobError::StageNotCompleted(ProofStage::Wrap))
+        ));
+
+        let mut other_stdin = SP1Stdin::new();
+        other_stdin.write(&2u32);
+        assert!(matches!(
+            ProofJob::open(dir.path(), elf, &other_stdin, opts),
+            Err(ProofJobError::InputsMismatch)
+        ));
+
+        let mut other_opts = opts;
+        other_opts.core_opts.shard_size /= 2;
+        assert!(matches!(
+            ProofJob::open(dir.path(), elf, &stdin, other_opts),
+            Err(ProofJobError::OptsMismatch)
+        ));
+    }
+}
@@ -13,6 +13,8 @@
 
 pub mod build;
 pub mod components;
+pub mod distributed;
+pub mod job;
 pub mod shapes;
 pub mod types;
 pub mod utils;
@@ -26,10 +28,11 @@ use std::{
     path::Path,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        mpsc::sync_channel,
+        mpsc::{sync_channel, RecvTimeoutError},
         Arc, Mutex, OnceLock,
     },
     thread,
+    time::Duration,
 };
 
 use lru::LruCache;
@@ -41,7 +44,9 @@ use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
 use p3_field::{AbstractField, PrimeField, PrimeField32};
 use p3_matrix::dense::RowMajorMatrix;
-use sp1_core_executor::{ExecutionError, ExecutionReport, Executor, Program, SP1Context};
+use sp1_core_executor::{
+    ExecutionError, ExecutionReport, Executor, Program, ProgressMonitor, ProofPhase, SP1Context,
+};
 use sp1_core_machine::{
     io::SP1Stdin,
     reduce::SP1ReduceProof,
@@ -72,11 +77,15 @@ use sp1_recursion_core::{
     shape::RecursionShapeConfig, stark::BabyBearPoseidon2Outer, RecursionProgram,
     Runtime as RecursionRuntime,
 };
+#[cfg(feature = "ark-groth16")]
+use sp1_recursion_gnark_ffi::ark::ArkGroth16Bn254Prover as Groth16Bn254Prover;
+#[cfg(not(feature = "ark-groth16"))]
+use sp1_recursion_gnark_ffi::groth16_bn254::Groth16Bn254Prover;
+use sp1_recursion_gnark_ffi::plonk_bn254::PlonkBn254Prover;
 pub use sp1_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
-use sp1_recursion_gnark_ffi::{groth16_bn254::Groth16Bn254Prover, plonk_bn254::PlonkBn254Prover};
 use sp1_stark::{air::InteractionScope, MachineProvingKey, ProofShape};
 use sp1_stark::{
-    air::PublicValues, baby_bear_poseidon2::BabyBearPoseidon2, Challenge, Challenger,
+    air::PublicValues, baby_bear_poseidon2::BabyBearPoseidon2, Challenge, Challenger, FriParams,
     MachineProver, SP1CoreOpts, SP1ProverOpts, ShardProof, StarkGenericConfig, StarkVerifyingKey,
     Val, Word, DIGEST_SIZE,
 };
@@ -164,13 +173,36 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         Self::uninitialized()
     }
 
+    /// Initializes a new [SP1Prover] whose core and compress proofs use the FRI parameters of
+    /// `opts.core_opts` and `opts.recursion_opts`. The shrink and wrap proofs keep their fixed
+    /// parameters, which the circuit artifacts depend on.
+    ///
+    /// The recursion programs verifying proofs with non-default parameters are not part of the
+    /// allowed verifying keys of a release, so compressing them requires `VERIFY_VK=false`. The
+    /// resulting proofs are only accepted by verifiers built with the same parameters.
+    #[instrument(name = "initialize prover", level = "debug", skip_all)]
+    pub fn with_opts(opts: SP1ProverOpts) -> Self {
+        Self::uninitialized_with_fri_params(
+            opts.core_opts.fri_params,
+            opts.recursion_opts.fri_params,
+        )
+    }
+
     /// Creates a new [SP1Prover] with lazily initialized components.
     pub fn uninitialized() -> Self {
+        Self::uninitialized_with_fri_params(FriParams::standard(), FriParams::standard())
+    }
+
+    fn uninitialized_with_fri_params(
+        core_fri_params: FriParams,
+        compress_fri_params: FriParams,
+    ) -> Self {
         // Initialize the provers.
-        let core_machine = RiscvAir::machine(CoreSC::default());
+        let core_machine = RiscvAir::machine(CoreSC::with_fri_params(core_fri_params));
         let core_prover = C::CoreProver::new(core_machine);
 
-        let compress_machine = CompressAir::compress_machine(InnerSC::default());
+        let compress_machine =
+            CompressAir::compress_machine(InnerSC::with_fri_params(compress_fri_params));
         let compress_prover = C::CompressProver::new(compress_machine);
 
         // TODO: Put the correct shrink and wrap machines here.
@@ -264,6 +296,8 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     }
 
     /// Generate a proof of an SP1 program with the specified inputs.
+    ///
+    /// Deferred proofs are verified with this prover, unless `context` sets a subproof verifier.
     #[instrument(name = "execute", level = "info", skip_all)]
     pub fn execute<'a>(
         &'a self,
@@ -271,7 +305,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         stdin: &SP1Stdin,
         mut context: SP1Context<'a>,
     ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
-        context.subproof_verifier.replace(Arc::new(self));
+        if context.subproof_verifier.is_none() {
+            context.subproof_verifier = Some(Arc::new(self));
+        }
         let program = self.get_program(elf).unwrap();
         let opts = SP1CoreOpts::default();
         let mut runtime = Executor::with_context(program, opts, context);
@@ -280,7 +316,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             runtime.write_proof(proof.clone(), vkey.clone());
         }
         runtime.run_fast()?;
-        Ok((SP1PublicValues::from(&runtime.state.public_values_stream), runtime.report))
+        let mut public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+        public_values.set_merkle_leaves(runtime.state.merkle_leaves_stream);
+        Ok((public_values, runtime.report))
     }
 
     /// Generate shard proofs which split up and prove the valid execution of a RISC-V program with
@@ -293,9 +331,16 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         opts: SP1ProverOpts,
         mut context: SP1Context<'a>,
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
+        let fri_params = self.core_prover.config().fri_params();
+        for requested in [opts.core_opts.fri_params, pk.vk.vk.fri_params] {
+            if requested != fri_params {
+                return Err(SP1CoreProverError::FriParamsMismatch(requested, fri_params));
+            }
+        }
+
         context.subproof_verifier.replace(Arc::new(self));
         let program = self.get_program(&pk.elf).unwrap();
-        let (proof, public_values_stream, cycles) = sp1_core_machine::utils::prove_with_context::<
+        let (proof, public_values, cycles) = sp1_core_machine::utils::prove_with_public_values::<
             _,
             C::CoreProver,
         >(
@@ -310,7 +355,6 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             self.core_shape_config.as_ref(),
         )?;
         Self::check_for_high_cycles(cycles);
-        let public_values = SP1PublicValues::from(&public_values_stream);
         Ok(SP1CoreProof {
             proof: SP1CoreProofData(proof.shard_proofs),
             stdin: stdin.clone(),
@@ -620,7 +664,6 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     }
 
     /// Reduce shards proofs to a single shard proof using the recursion prover.
-    #[instrument(name = "compress", level = "info", skip_all)]
     pub fn compress(
         &self,
         vk: &SP1VerifyingKey,
@@ -628,6 +671,38 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
         opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.compress_with_progress(vk, proof, deferred_proofs, opts, &ProgressMonitor::default())
+    }
+
+    /// Reduce shards proofs to a single shard proof using the recursion prover, reporting each
+    /// layer of the recursion tree to `progress` and stopping early if it is cancelled.
+    #[instrument(name = "compress", level = "info", skip_all)]
+    pub fn compress_with_progress(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: SP1CoreProof,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+        opts: SP1ProverOpts,
+        progress: &ProgressMonitor,
+    ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        progress.check_cancelled().map_err(SP1RecursionProverError::Cancelled)?;
+        progress.report(ProofPhase::CompressLayer { layer: 0 });
+
+        let fri_params = self.compress_prover.config().fri_params();
+        if opts.recursion_opts.fri_params != fri_params {
+            return Err(SP1RecursionProverError::FriParamsMismatch(
+                opts.recursion_opts.fri_params,
+                fri_params,
+            ));
+        }
+        let core_fri_params = self.core_prover.config().fri_params();
+        if vk.vk.fri_params != core_fri_params {
+            return Err(SP1RecursionProverError::FriParamsMismatch(
+                vk.vk.fri_params,
+                core_fri_params,
+            ));
+        }
+
         // The batch size for reducing two layers of recursion.
         let batch_size = REDUCE_BATCH_SIZE;
         // The batch size for reducing the first layer of recursion.
@@ -707,6 +782,15 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                     loop {
                         let received = { input_rx.lock().unwrap().recv() };
                         if let Ok((index, height, input)) = received {
+                            // If the proof has been cancelled, skip the input while keeping the
+                            // turn order. Once an input is skipped, all later inputs are skipped
+                            // too, since the check below happens after waiting for the turn.
+                            if progress.is_cancelled() {
+                                record_and_trace_sync.wait_for_turn(index);
+                                record_and_trace_sync.advance_turn();
+                                continue;
+                            }
+
                             // Get the program and witness stream.
                             let (program, witness_stream) = tracing::debug_span!(
                                 "get program and witness stream"
@@ -774,11 +858,13 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                             record_and_trace_sync.wait_for_turn(index);
 
                             // Send the record and traces to the worker.
-                            record_and_trace_tx
-                                .lock()
-                                .unwrap()
-                                .send((index, height, program, record, traces))
-                                .unwrap();
+                            if !progress.is_cancelled() {
+                                record_and_trace_tx
+                                    .lock()
+                                    .unwrap()
+                                    .send((index, height, program, record, traces))
+                                    .unwrap();
+                            }
 
                             // Advance the turn.
                             record_and_trace_sync.advance_turn();
@@ -808,6 +894,13 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                     loop {
                         let received = { record_and_trace_rx.lock().unwrap().recv() };
                         if let Ok((index, height, program, record, traces)) = received {
+                            // Skip the record if the proof has been cancelled, as above.
+                            if progress.is_cancelled() {
+                                prover_sync.wait_for_turn(index);
+                                prover_sync.advance_turn();
+                                continue;
+                            }
+
                             tracing::debug_span!("batch").in_scope(|| {
                                 // Get the keys.
                                 let (pk, vk) = tracing::debug_span!("Setup compress program")
@@ -870,7 +963,13 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                 prover_sync.wait_for_turn(index);
 
                                 // Send the proof.
-                                proofs_tx.lock().unwrap().send((index, height, vk, proof)).unwrap();
+                                if !progress.is_cancelled() {
+                                    proofs_tx
+                                        .lock()
+                                        .unwrap()
+                                        .send((index, height, vk, proof))
+                                        .unwrap();
+                                }
 
                                 // Advance the turn.
                                 prover_sync.advance_turn();
@@ -891,6 +990,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                 s.spawn(move || {
                     let _span = span.enter();
                     let mut count = num_first_layer_inputs;
+                    let mut layer = 0;
                     let mut batch: Vec<(
                         usize,
                         usize,
@@ -898,7 +998,16 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                         ShardProof<InnerSC>,
                     )> = Vec::new();
                     loop {
-                        let received = { proofs_rx.lock().unwrap().recv() };
+                        // Poll for proofs so that a cancelled proof, for which no more proofs
+                        // arrive, is noticed.
+                        if progress.is_cancelled() {
+                            break;
+                        }
+                        let received =
+                            { proofs_rx.lock().unwrap().recv_timeout(Duration::from_millis(100)) };
+                        if let Err(RecvTimeoutError::Timeout) = received {
+                            continue;
+                        }
                         if let Ok((index, height, vk, proof)) = received {
                             batch.push((index, height, vk, proof));
 
@@ -923,6 +1032,10 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                 if is_last { vec![batch[0].clone()] } else { batch.clone() };
 
                             let next_input_index = inputs[0].1 + 1;
+                            if next_input_index > layer {
+                                layer = next_input_index;
+                                progress.report(ProofPhase::CompressLayer { layer });
+                            }
                             let vks_and_proofs = inputs
                                 .into_iter()
                                 .map(|(_, _, vk, proof)| (vk, proof))
@@ -968,21 +1081,36 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                 handle.join().unwrap();
             }
             handle.join().unwrap();
+            progress.check_cancelled().map_err(SP1RecursionProverError::Cancelled)?;
 
             let (_, _, vk, proof) = proofs_rx.lock().unwrap().recv().unwrap();
-            (vk, proof)
-        });
+            Ok((vk, proof))
+        })?;
 
         Ok(SP1ReduceProof { vk, proof })
     }
 
     /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field.
-    #[instrument(name = "shrink", level = "info", skip_all)]
     pub fn shrink(
         &self,
         reduced_proof: SP1ReduceProof<InnerSC>,
         opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.shrink_with_progress(reduced_proof, opts, &ProgressMonitor::default())
+    }
+
+    /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field, reporting the phase to
+    /// `progress` and returning early if it is cancelled.
+    #[instrument(name = "shrink", level = "info", skip_all)]
+    pub fn shrink_with_progress(
+        &self,
+        reduced_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
+        progress: &ProgressMonitor,
+    ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        progress.check_cancelled().map_err(SP1RecursionProverError::Cancelled)?;
+        progress.report(ProofPhase::Shrink);
+
         // Make the compress proof.
         let SP1ReduceProof { vk: compressed_vk, proof: compressed_proof } = reduced_proof;
         let input = SP1CompressWitnessValues {
@@ -1024,12 +1152,26 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     }
 
     /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field.
-    #[instrument(name = "wrap_bn254", level = "info", skip_all)]
     pub fn wrap_bn254(
         &self,
         compressed_proof: SP1ReduceProof<InnerSC>,
         opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
+        self.wrap_bn254_with_progress(compressed_proof, opts, &ProgressMonitor::default())
+    }
+
+    /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field, reporting the phase to
+    /// `progress` and returning early if it is cancelled.
+    #[instrument(name = "wrap_bn254", level = "info", skip_all)]
+    pub fn wrap_bn254_with_progress(
+        &self,
+        compressed_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
+        progress: &ProgressMonitor,
+    ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
+        progress.check_cancelled().map_err(SP1RecursionProverError::Cancelled)?;
+        progress.report(ProofPhase::Wrap);
+
         let SP1ReduceProof { vk: compressed_vk, proof: compressed_proof } = compressed_proof;
         let input = SP1CompressWitnessValues {
             vks_and_proofs: vec![(compressed_vk, compressed_proof)],
@@ -1537,4 +1679,29 @@ pub mod tests {
         setup_logger();
         test_e2e_with_deferred_proofs_prover::<DefaultProverComponents>(SP1ProverOpts::default())
     }
+
+    /// Tests that the vkey hash of a program depends on the FRI parameters it is proven with,
+    /// unless they are a preset.
+    #[test]
+    #[serial]
+    fn test_vkey_hash_binds_fri_params() {
+        let elf = include_bytes!("../../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        setup_logger();
+        let prover = SP1Prover::<DefaultProverComponents>::uninitialized();
+        let (_, vk) = prover.setup(elf);
+
+        let mut opts = SP1ProverOpts::default();
+        opts.core_opts.fri_params = FriParams::new(1, 64, 16);
+        let fast_prover = SP1Prover::<DefaultProverComponents>::with_opts(opts);
+        let (_, fast_vk) = fast_prover.setup(elf);
+
+        assert_eq!(fast_vk.vk.fri_params, opts.core_opts.fri_params);
+        assert_eq!(fast_vk.vk.commit, vk.vk.commit);
+        assert_ne!(fast_vk.hash_u32(), vk.hash_u32());
+
+        // The presets are not hashed, so the hashes of the released keys are unchanged.
+        let mut preset_vk = vk.vk.clone();
+        preset_vk.fri_params = FriParams::compressed();
+        assert_eq!(preset_vk.hash_u32(), vk.hash_u32());
+    }
 }
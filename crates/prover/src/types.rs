@@ -4,10 +4,11 @@ use anyhow::Result;
 use p3_baby_bear::BabyBear;
 use p3_bn254_fr::Bn254Fr;
 use p3_commit::{Pcs, TwoAdicMultiplicativeCoset};
-use p3_field::{AbstractField, PrimeField, PrimeField32, TwoAdicField};
+use p3_field::{PrimeField, PrimeField32};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_core_executor::ProofCancelled;
 use sp1_core_machine::{io::SP1Stdin, reduce::SP1ReduceProof};
-use sp1_primitives::{io::SP1PublicValues, poseidon2_hash};
+use sp1_primitives::io::SP1PublicValues;
 
 use sp1_recursion_circuit::machine::{
     SP1CompressWitnessValues, SP1DeferredWitnessValues, SP1RecursionWitnessValues,
@@ -15,7 +16,9 @@ use sp1_recursion_circuit::machine::{
 
 use sp1_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
 
-use sp1_stark::{ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, DIGEST_SIZE};
+use sp1_stark::{
+    FriParams, ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, DIGEST_SIZE,
+};
 use thiserror::Error;
 
 use crate::{
@@ -77,21 +80,7 @@ where
     <SC::Pcs as Pcs<SC::Challenge, SC::Challenger>>::Commitment: AsRef<[BabyBear; DIGEST_SIZE]>,
 {
     fn hash_babybear(&self) -> [BabyBear; DIGEST_SIZE] {
-        let prep_domains = self.chip_information.iter().map(|(_, domain, _)| domain);
-        let num_inputs = DIGEST_SIZE + 1 + (4 * prep_domains.len());
-        let mut inputs = Vec::with_capacity(num_inputs);
-        inputs.extend(self.commit.as_ref());
-        inputs.push(self.pc_start);
-        for domain in prep_domains {
-            inputs.push(BabyBear::from_canonical_usize(domain.log_n));
-            let size = 1 << domain.log_n;
-            inputs.push(BabyBear::from_canonical_usize(size));
-            let g = BabyBear::two_adic_generator(domain.log_n);
-            inputs.push(domain.shift);
-            inputs.push(g);
-        }
-
-        poseidon2_hash(inputs)
+        StarkVerifyingKey::hash_babybear(self)
     }
 
     fn hash_u32(&self) -> [u32; 8] {
@@ -209,6 +198,10 @@ pub enum SP1ReduceProofWrapper {
 pub enum SP1RecursionProverError {
     #[error("Runtime error: {0}")]
     RuntimeError(String),
+    #[error("{0}")]
+    Cancelled(ProofCancelled),
+    #[error("FRI params mismatch: requested {0:?}, prover uses {1:?}")]
+    FriParamsMismatch(FriParams, FriParams),
 }
 
 #[allow(clippy::large_enum_variant)]
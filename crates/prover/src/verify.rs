@@ -10,9 +10,7 @@ use sp1_primitives::{consts::WORD_SIZE, io::SP1PublicValues};
 
 use sp1_recursion_circuit::machine::RootPublicValues;
 use sp1_recursion_core::{air::RecursionPublicValues, stark::BabyBearPoseidon2Outer};
-use sp1_recursion_gnark_ffi::{
-    Groth16Bn254Proof, Groth16Bn254Prover, PlonkBn254Proof, PlonkBn254Prover,
-};
+use sp1_recursion_gnark_ffi::{Groth16Bn254Proof, PlonkBn254Proof, PlonkBn254Prover};
 use sp1_stark::{
     air::{PublicValues, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS},
     baby_bear_poseidon2::BabyBearPoseidon2,
@@ -23,7 +21,7 @@ use thiserror::Error;
 use crate::{
     components::SP1ProverComponents,
     utils::{assert_recursion_public_values_valid, assert_root_public_values_valid},
-    CoreSC, HashableKey, OuterSC, SP1CoreProofData, SP1Prover, SP1VerifyingKey,
+    CoreSC, Groth16Bn254Prover, HashableKey, OuterSC, SP1CoreProofData, SP1Prover, SP1VerifyingKey,
 };
 
 #[derive(Error, Debug)]
@@ -91,7 +91,8 @@ where
         let pc_start = self.pc_start.read(builder);
         let chip_information = self.chip_information.clone();
         let chip_ordering = self.chip_ordering.clone();
-        VerifyingKeyVariable { commitment, pc_start, chip_information, chip_ordering }
+        let fri_params = self.fri_params;
+        VerifyingKeyVariable { commitment, pc_start, chip_information, chip_ordering, fri_params }
     }
 
     fn write(&self, witness: &mut impl WitnessWriter<C>) {
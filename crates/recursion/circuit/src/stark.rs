@@ -177,6 +177,7 @@ pub fn dummy_vk_and_shard_proof<A: MachineAir<BabyBear>>(
         pc_start: BabyBear::zero(),
         chip_information: preprocessed_chip_information,
         chip_ordering: preprocessed_chip_ordering,
+        fri_params: machine.config().fri_params(),
     };
 
     let shard_proof =
@@ -6,6 +6,7 @@ use p3_matrix::Dimensions;
 use sp1_recursion_compiler::ir::{Builder, Ext, Felt};
 
 use sp1_recursion_core::DIGEST_SIZE;
+use sp1_stark::FriParams;
 
 use crate::{
     challenger::CanObserveVariable, hash::FieldHasherVariable, BabyBearFriConfigVariable,
@@ -19,6 +20,7 @@ pub struct VerifyingKeyVariable<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriCo
     pub pc_start: Felt<C::F>,
     pub chip_information: Vec<(String, TwoAdicMultiplicativeCoset<C::F>, Dimensions)>,
     pub chip_ordering: HashMap<String, usize>,
+    pub fri_params: FriParams,
 }
 
 #[derive(Clone)]
@@ -90,15 +92,17 @@ impl<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriConfigVariable<C>> VerifyingK
         }
     }
 
-    /// Hash the verifying key + prep domains into a single digest.
-    /// poseidon2( commit[0..8] || pc_start || prep_domains[N].{log_n, .size, .shift, .g})
+    /// Hash the verifying key + prep domains + FRI parameters into a single digest.
+    /// poseidon2( commit[0..8] || pc_start || prep_domains[N].{log_n, .size, .shift, .g}
+    ///     || fri_params.{log_blowup, num_queries, proof_of_work_bits})
+    /// where the FRI parameters are only hashed if they are not a preset.
     pub fn hash(&self, builder: &mut Builder<C>) -> SC::DigestVariable
     where
         C::F: TwoAdicField,
         SC::DigestVariable: IntoIterator<Item = Felt<C::F>>,
     {
         let prep_domains = self.chip_information.iter().map(|(_, domain, _)| domain);
-        let num_inputs = DIGEST_SIZE + 1 + (4 * prep_domains.len());
+        let num_inputs = DIGEST_SIZE + 1 + (4 * prep_domains.len()) + 3;
         let mut inputs = Vec::with_capacity(num_inputs);
         inputs.extend(self.commitment);
         inputs.push(self.pc_start);
@@ -110,6 +114,12 @@ impl<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriConfigVariable<C>> VerifyingK
             inputs.push(builder.eval(domain.shift));
             inputs.push(builder.eval(g));
         }
+        if !self.fri_params.is_preset() {
+            let FriParams { log_blowup, num_queries, proof_of_work_bits } = self.fri_params;
+            inputs.push(builder.eval(C::F::from_canonical_usize(log_blowup)));
+            inputs.push(builder.eval(C::F::from_canonical_usize(num_queries)));
+            inputs.push(builder.eval(C::F::from_canonical_usize(proof_of_work_bits)));
+        }
 
         SC::hash(builder, &inputs)
     }
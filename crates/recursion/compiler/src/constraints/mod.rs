@@ -72,8 +72,29 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
 
     /// Emit the constraints from a list of operations in the DSL.
     pub fn emit(&mut self, operations: TracedVec<DslIr<C>>) -> Vec<Constraint> {
+        self.emit_with_spans(operations).0
+    }
+
+    /// Emit the constraints from a list of operations in the DSL, together with the cycle tracker
+    /// span each constraint was emitted in.
+    ///
+    /// Spans are the names of the enclosing `CycleTrackerV2Enter` instructions joined with `/`, and
+    /// the empty string for constraints outside of any span.
+    pub fn emit_with_spans(
+        &mut self,
+        operations: TracedVec<DslIr<C>>,
+    ) -> (Vec<Constraint>, Vec<String>) {
         let mut constraints: Vec<Constraint> = Vec::new();
+        let mut spans: Vec<String> = Vec::new();
+        let mut span_stack: Vec<String> = Vec::new();
         for (instruction, _) in operations {
+            match &instruction {
+                DslIr::CycleTrackerV2Enter(name) => span_stack.push(name.clone()),
+                DslIr::CycleTrackerV2Exit => {
+                    span_stack.pop();
+                }
+                _ => {}
+            }
             match instruction {
                 DslIr::ImmV(a, b) => constraints.push(Constraint {
                     opcode: ConstraintOpcode::ImmV,
@@ -409,7 +430,10 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                 DslIr::CircuitV2CommitPublicValues(_) => {}
                 _ => panic!("unsupported {:?}", instruction),
             };
+            if spans.len() < constraints.len() {
+                spans.resize(constraints.len(), span_stack.join("/"));
+            }
         }
-        constraints
+        (constraints, spans)
     }
 }
@@ -12,7 +12,7 @@ use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 use p3_symmetric::{Hash, MultiField32PaddingFreeSponge, TruncatedPermutation};
 use serde::{Deserialize, Serialize};
-use sp1_stark::{Com, StarkGenericConfig, ZeroCommitment};
+use sp1_stark::{Com, FriParams, StarkGenericConfig, ZeroCommitment};
 
 use super::{poseidon2::bn254_poseidon2_rc3, sp1_dev_mode};
 
@@ -175,6 +175,10 @@ impl StarkGenericConfig for BabyBearPoseidon2Outer {
     fn challenger(&self) -> Self::Challenger {
         OuterChallenger::new(self.perm.clone()).unwrap()
     }
+
+    fn fri_params(&self) -> FriParams {
+        self.pcs.fri_config().into()
+    }
 }
 
 impl ZeroCommitment<BabyBearPoseidon2Outer> for OuterPcs {
@@ -0,0 +1,333 @@
+use ark_bn254::Fr;
+use ark_relations::r1cs::SynthesisError;
+use num_bigint::BigUint;
+use p3_baby_bear::BabyBear;
+use p3_field::{
+    extension::BinomialExtensionField, AbstractExtensionField, AbstractField, Field, PrimeField32,
+};
+
+use super::r1cs::{R1csBuilder, Var};
+
+/// The BabyBear modulus.
+const MODULUS: u32 = 2013265921;
+
+/// A BabyBear element emulated inside the BN254 scalar field.
+///
+/// The value is only reduced lazily, so it is tracked together with an upper bound on its integer
+/// representative, exactly like `babybear.Variable` in the Go circuit.
+#[derive(Debug, Clone)]
+pub struct Felt {
+    pub value: Var,
+    pub upper_bound: BigUint,
+}
+
+/// An element of the degree 4 extension of BabyBear.
+#[derive(Debug, Clone)]
+pub struct Ext(pub [Felt; 4]);
+
+fn modulus() -> BigUint {
+    BigUint::from(MODULUS)
+}
+
+/// The BabyBear chip, a port of the `babybear.Chip` of the Go circuit.
+///
+/// The constraints match the Groth16 flavor of the Go circuit, which range checks with binary
+/// decompositions instead of the gnark range checker.
+pub struct BabyBearChip<'a> {
+    api: &'a R1csBuilder,
+}
+
+impl<'a> BabyBearChip<'a> {
+    pub fn new(api: &'a R1csBuilder) -> Self {
+        Self { api }
+    }
+
+    /// A BabyBear constant, whose upper bound is its value.
+    pub fn constant(&self, value: &BigUint) -> Felt {
+        Felt { value: self.api.constant_biguint(value), upper_bound: value.clone() }
+    }
+
+    /// A BabyBear element that is known to fit in 32 bits.
+    pub fn from_var(&self, value: Var) -> Felt {
+        Felt { value, upper_bound: BigUint::from(1u64 << 32) }
+    }
+
+    pub fn from_u32(&self, value: u32) -> Felt {
+        self.constant(&BigUint::from(value))
+    }
+
+    pub fn add_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        self.reduce_fast(self.add_f_lazy(a, b))
+    }
+
+    fn add_f_lazy(&self, a: &Felt, b: &Felt) -> Felt {
+        Felt {
+            value: self.api.add(&a.value, &b.value),
+            upper_bound: &a.upper_bound + &b.upper_bound,
+        }
+    }
+
+    pub fn sub_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        let neg_b = self.neg_f(b)?;
+        self.add_f(a, &neg_b)
+    }
+
+    pub fn mul_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        let product = self.mul_f_lazy(a, b)?;
+        self.reduce_fast(product)
+    }
+
+    fn mul_f_lazy(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        Ok(Felt {
+            value: self.api.mul(&a.value, &b.value)?,
+            upper_bound: &a.upper_bound * &b.upper_bound,
+        })
+    }
+
+    pub fn mul_f_const(&self, a: &Felt, b: u32) -> Result<Felt, SynthesisError> {
+        self.reduce_fast(self.mul_f_const_lazy(a, b))
+    }
+
+    fn mul_f_const_lazy(&self, a: &Felt, b: u32) -> Felt {
+        Felt { value: self.api.mul_const(&a.value, Fr::from(b)), upper_bound: &a.upper_bound * b }
+    }
+
+    pub fn neg_f(&self, a: &Felt) -> Result<Felt, SynthesisError> {
+        let lifted_modulus = (&a.upper_bound / modulus() + 1u32) * modulus();
+        self.reduce_fast(Felt {
+            value: self.api.sub(&self.api.constant_biguint(&lifted_modulus), &a.value),
+            upper_bound: lifted_modulus,
+        })
+    }
+
+    pub fn inv_f(&self, a: &Felt) -> Result<Felt, SynthesisError> {
+        let inv = babybear(&a.value.biguint()).try_inverse().unwrap_or(BabyBear::zero());
+        let inv = self.api.hint(&BigUint::from(inv.as_canonical_u32()))?;
+        self.api.to_binary(&inv, 31)?;
+        let inv = Felt { value: inv, upper_bound: BigUint::from(1u64 << 31) };
+
+        let product = self.mul_f(a, &inv)?;
+        self.assert_is_equal_f(&product, &self.from_u32(1))?;
+
+        Ok(inv)
+    }
+
+    pub fn div_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        let b_inv = self.inv_f(b)?;
+        self.mul_f(a, &b_inv)
+    }
+
+    pub fn assert_is_equal_f(&self, a: &Felt, b: &Felt) -> Result<(), SynthesisError> {
+        let a = self.reduce_slow(a.clone())?;
+        let b = self.reduce_slow(b.clone())?;
+        self.api.assert_is_equal(&a.value, &b.value)
+    }
+
+    pub fn assert_not_equal_f(&self, a: &Felt, b: &Felt) -> Result<(), SynthesisError> {
+        let a = self.reduce_slow(a.clone())?;
+        let b = self.reduce_slow(b.clone())?;
+        self.api.assert_is_different(&a.value, &b.value)
+    }
+
+    pub fn assert_is_equal_e(&self, a: &Ext, b: &Ext) -> Result<(), SynthesisError> {
+        for (a, b) in a.0.iter().zip(b.0.iter()) {
+            self.assert_is_equal_f(a, b)?;
+        }
+        Ok(())
+    }
+
+    pub fn select_f(&self, cond: &Var, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        Ok(Felt {
+            value: self.api.select(cond, &a.value, &b.value)?,
+            upper_bound: a.upper_bound.clone().max(b.upper_bound.clone()),
+        })
+    }
+
+    pub fn select_e(&self, cond: &Var, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        Ok(Ext([
+            self.select_f(cond, &a.0[0], &b.0[0])?,
+            self.select_f(cond, &a.0[1], &b.0[1])?,
+            self.select_f(cond, &a.0[2], &b.0[2])?,
+            self.select_f(cond, &a.0[3], &b.0[3])?,
+        ]))
+    }
+
+    pub fn add_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let mut result = a.clone();
+        result.0[0] = self.add_f(&a.0[0], b)?;
+        Ok(result)
+    }
+
+    pub fn add_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        Ok(Ext([
+            self.add_f(&a.0[0], &b.0[0])?,
+            self.add_f(&a.0[1], &b.0[1])?,
+            self.add_f(&a.0[2], &b.0[2])?,
+            self.add_f(&a.0[3], &b.0[3])?,
+        ]))
+    }
+
+    pub fn sub_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        Ok(Ext([
+            self.sub_f(&a.0[0], &b.0[0])?,
+            self.sub_f(&a.0[1], &b.0[1])?,
+            self.sub_f(&a.0[2], &b.0[2])?,
+            self.sub_f(&a.0[3], &b.0[3])?,
+        ]))
+    }
+
+    pub fn sub_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let mut result = a.clone();
+        result.0[0] = self.sub_f(&a.0[0], b)?;
+        Ok(result)
+    }
+
+    pub fn mul_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        let zero = self.from_u32(0);
+        let mut result = [zero.clone(), zero.clone(), zero.clone(), zero];
+        for i in 0..4 {
+            for j in 0..4 {
+                let product = self.mul_f_lazy(&a.0[i], &b.0[j])?;
+                if i + j >= 4 {
+                    let product = self.mul_f_const_lazy(&product, 11);
+                    result[i + j - 4] = self.add_f_lazy(&result[i + j - 4], &product);
+                } else {
+                    result[i + j] = self.add_f_lazy(&result[i + j], &product);
+                }
+            }
+        }
+        let [a, b, c, d] = result;
+        Ok(Ext([
+            self.reduce_fast(a)?,
+            self.reduce_fast(b)?,
+            self.reduce_fast(c)?,
+            self.reduce_fast(d)?,
+        ]))
+    }
+
+    pub fn mul_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        Ok(Ext([
+            self.mul_f(&a.0[0], b)?,
+            self.mul_f(&a.0[1], b)?,
+            self.mul_f(&a.0[2], b)?,
+            self.mul_f(&a.0[3], b)?,
+        ]))
+    }
+
+    pub fn inv_e(&self, a: &Ext) -> Result<Ext, SynthesisError> {
+        let value = BinomialExtensionField::<BabyBear, 4>::from_base_slice(
+            &a.0.iter().map(|x| babybear(&x.value.biguint())).collect::<Vec<_>>(),
+        );
+        let inv = value.try_inverse().unwrap_or(BinomialExtensionField::zero());
+        let mut limbs = Vec::with_capacity(4);
+        for limb in inv.as_base_slice() {
+            let limb = self.api.hint(&BigUint::from(limb.as_canonical_u32()))?;
+            self.api.to_binary(&limb, 31)?;
+            limbs.push(Felt { value: limb, upper_bound: BigUint::from(1u64 << 31) });
+        }
+        let inv = Ext(limbs.try_into().unwrap());
+
+        let product = self.mul_e(a, &inv)?;
+        let one = Ext([self.from_u32(1), self.from_u32(0), self.from_u32(0), self.from_u32(0)]);
+        self.assert_is_equal_e(&product, &one)?;
+
+        Ok(inv)
+    }
+
+    pub fn div_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        let b_inv = self.inv_e(b)?;
+        self.mul_e(a, &b_inv)
+    }
+
+    pub fn div_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let b_inv = self.inv_f(b)?;
+        self.mul_ef(a, &b_inv)
+    }
+
+    pub fn neg_e(&self, a: &Ext) -> Result<Ext, SynthesisError> {
+        Ok(Ext([
+            self.neg_f(&a.0[0])?,
+            self.neg_f(&a.0[1])?,
+            self.neg_f(&a.0[2])?,
+            self.neg_f(&a.0[3])?,
+        ]))
+    }
+
+    pub fn to_binary(&self, a: &Felt) -> Result<Vec<Var>, SynthesisError> {
+        let reduced = self.reduce_slow(a.clone())?;
+        self.api.to_binary(&reduced.value, 31)
+    }
+
+    /// Reduces the element if its upper bound is getting close to the BN254 modulus.
+    pub fn reduce_fast(&self, x: Felt) -> Result<Felt, SynthesisError> {
+        if x.upper_bound.bits() >= 120 {
+            let max_bits = x.upper_bound.bits();
+            return Ok(Felt {
+                value: self.reduce_with_max_bits(&x.value, max_bits)?,
+                upper_bound: modulus() - 1u32,
+            });
+        }
+        Ok(x)
+    }
+
+    /// Reduces the element to its canonical representative.
+    pub fn reduce_slow(&self, x: Felt) -> Result<Felt, SynthesisError> {
+        if x.upper_bound < modulus() {
+            return Ok(x);
+        }
+        let max_bits = x.upper_bound.bits();
+        Ok(Felt {
+            value: self.reduce_with_max_bits(&x.value, max_bits)?,
+            upper_bound: modulus() - 1u32,
+        })
+    }
+
+    pub fn reduce_e(&self, x: Ext) -> Result<Ext, SynthesisError> {
+        let [a, b, c, d] = x.0;
+        Ok(Ext([
+            self.reduce_slow(a)?,
+            self.reduce_slow(b)?,
+            self.reduce_slow(c)?,
+            self.reduce_slow(d)?,
+        ]))
+    }
+
+    fn reduce_with_max_bits(&self, x: &Var, max_bits: u64) -> Result<Var, SynthesisError> {
+        if max_bits <= 30 {
+            return Ok(x.clone());
+        }
+
+        let value = x.biguint();
+        let quotient = self.api.hint(&(&value / modulus()))?;
+        let remainder = self.api.hint(&(&value % modulus()))?;
+        self.api.to_binary(&quotient, (max_bits - 30) as usize)?;
+
+        // Check that the remainder is less than the BabyBear modulus, by decomposing it into a 27
+        // bit limb and a 4 bit limb.
+        let remainder_value = remainder.biguint();
+        let low_limb = self.api.hint(&(&remainder_value % (1u32 << 27)))?;
+        let high_limb = self.api.hint(&(&remainder_value >> 27u32))?;
+        let recomposed =
+            self.api.add(&self.api.mul_const(&high_limb, Fr::from(1u32 << 27)), &low_limb);
+        self.api.assert_is_equal(&recomposed, &remainder)?;
+        self.api.to_binary(&high_limb, 4)?;
+        self.api.to_binary(&low_limb, 27)?;
+
+        // If the most significant bits are all 1, then the least significant bits must all be zero
+        // for the element to be less than the BabyBear modulus.
+        let should_check =
+            self.api.is_zero(&self.api.sub(&high_limb, &self.api.constant(Fr::from(15u32))))?;
+        let checked = self.api.mul(&should_check, &low_limb)?;
+        self.api.assert_is_zero(&checked)?;
+
+        let lifted = self.api.add(&self.api.mul_const(&quotient, Fr::from(MODULUS)), &remainder);
+        self.api.assert_is_equal(x, &lifted)?;
+
+        Ok(remainder)
+    }
+}
+
+/// Converts the integer value of a variable into a BabyBear element.
+fn babybear(value: &BigUint) -> BabyBear {
+    BabyBear::from_canonical_u32((value % modulus()).try_into().unwrap())
+}
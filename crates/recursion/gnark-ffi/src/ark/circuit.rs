@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use ark_bn254::Fr;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use num_bigint::BigUint;
+use sp1_recursion_compiler::constraints::{opcodes::ConstraintOpcode, Constraint};
+
+use crate::GnarkWitness;
+
+use super::{
+    babybear::{BabyBearChip, Ext, Felt},
+    poseidon2::{Poseidon2BabyBearChip, Poseidon2Chip},
+    r1cs::{R1csBuilder, Var},
+};
+
+/// The wrap circuit as an arkworks constraint synthesizer.
+///
+/// This interprets the constraints emitted by `ConstraintCompiler` exactly like the `Circuit` of
+/// the Go circuit in Groth16 mode, so the resulting R1CS has the same public inputs: the vkey hash
+/// followed by the committed values digest.
+#[derive(Debug, Clone)]
+pub struct WrapCircuit<'a> {
+    pub constraints: &'a [Constraint],
+    pub witness: &'a GnarkWitness,
+}
+
+fn parse(value: &str) -> BigUint {
+    value.parse().expect("failed to parse witness value")
+}
+
+fn parse_index(value: &str) -> usize {
+    value.parse().expect("failed to parse index")
+}
+
+impl<'a> ConstraintSynthesizer<Fr> for WrapCircuit<'a> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        self.synthesize(cs, |_, _| {})
+    }
+}
+
+impl<'a> WrapCircuit<'a> {
+    /// Synthesizes the circuit, calling `record` with the number of R1CS constraints added by the
+    /// range checks of the witness (with `None`) and by each constraint of the list (with its
+    /// index).
+    pub(crate) fn synthesize(
+        &self,
+        cs: ConstraintSystemRef<Fr>,
+        mut record: impl FnMut(Option<usize>, usize),
+    ) -> Result<(), SynthesisError> {
+        let api = R1csBuilder::new(cs);
+        let field_api = BabyBearChip::new(&api);
+        let hash_api = Poseidon2Chip::new(&api);
+        let hash_babybear_api = Poseidon2BabyBearChip::new(&api, &field_api);
+
+        // Allocate the public inputs first, in the order of the Go circuit.
+        let vkey_hash = api.public_input(Fr::from(parse(&self.witness.vkey_hash)))?;
+        let committed_values_digest =
+            api.public_input(Fr::from(parse(&self.witness.committed_values_digest)))?;
+
+        // Allocate the witnesses and range check them.
+        let witness_vars = self
+            .witness
+            .vars
+            .iter()
+            .map(|v| api.witness(Fr::from(parse(v))))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut witness_felts = Vec::with_capacity(self.witness.felts.len());
+        for value in self.witness.felts.iter() {
+            let var = api.witness(Fr::from(parse(value)))?;
+            api.to_binary(&var, 31)?;
+            witness_felts.push(field_api.from_var(var));
+        }
+        let mut witness_exts = Vec::with_capacity(self.witness.exts.len());
+        for value in self.witness.exts.iter() {
+            let mut limbs = Vec::with_capacity(4);
+            for limb in value.iter() {
+                let var = api.witness(Fr::from(parse(limb)))?;
+                api.to_binary(&var, 31)?;
+                limbs.push(field_api.from_var(var));
+            }
+            witness_exts.push(Ext(limbs.try_into().expect("extension elements have four limbs")));
+        }
+        record(None, api.num_constraints());
+
+        let mut vars: HashMap<String, Var> = HashMap::new();
+        let mut felts: HashMap<String, Felt> = HashMap::new();
+        let mut exts: HashMap<String, Ext> = HashMap::new();
+
+        // Iterate through the instructions and handle each opcode.
+        for (index, cs) in self.constraints.iter().enumerate() {
+            let num_constraints = api.num_constraints();
+            let args = &cs.args;
+            let var = |i: usize| vars[&args[i][0]].clone();
+            let felt = |i: usize| felts[&args[i][0]].clone();
+            let ext = |i: usize| exts[&args[i][0]].clone();
+            match cs.opcode {
+                ConstraintOpcode::ImmV => {
+                    let value = api.constant_biguint(&parse(&args[1][0]));
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::ImmF => {
+                    let value = field_api.from_var(api.constant_biguint(&parse(&args[1][0])));
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::ImmE => {
+                    let limbs = args[1]
+                        .iter()
+                        .map(|x| field_api.from_var(api.constant_biguint(&parse(x))))
+                        .collect::<Vec<_>>();
+                    exts.insert(args[0][0].clone(), Ext(limbs.try_into().unwrap()));
+                }
+                ConstraintOpcode::AddV => {
+                    let value = api.add(&var(1), &var(2));
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::AddF => {
+                    let value = field_api.add_f(&felt(1), &felt(2))?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::AddE => {
+                    let value = field_api.add_e(&ext(1), &ext(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::AddEF => {
+                    let value = field_api.add_ef(&ext(1), &felt(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubV => {
+                    let value = api.sub(&var(1), &var(2));
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubF => {
+                    let value = field_api.sub_f(&felt(1), &felt(2))?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::DivF => {
+                    let value = field_api.div_f(&felt(1), &felt(2))?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubE => {
+                    let value = field_api.sub_e(&ext(1), &ext(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubEF => {
+                    let value = field_api.sub_ef(&ext(1), &felt(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulV => {
+                    let value = api.mul(&var(1), &var(2))?;
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulF => {
+                    let value = field_api.mul_f(&felt(1), &felt(2))?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulE => {
+                    let value = field_api.mul_e(&ext(1), &ext(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulEF => {
+                    let value = field_api.mul_ef(&ext(1), &felt(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::DivE => {
+                    let value = field_api.div_e(&ext(1), &ext(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::DivEF => {
+                    let value = field_api.div_ef(&ext(1), &felt(2))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::NegE => {
+                    let value = field_api.neg_e(&ext(1))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::InvE => {
+                    let value = field_api.inv_e(&ext(1))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::Num2BitsV => {
+                    let num_bits = parse_index(&args[2][0]);
+                    let bits = api.to_binary(&var(1), num_bits)?;
+                    for (name, bit) in args[0].iter().zip(bits) {
+                        vars.insert(name.clone(), bit);
+                    }
+                }
+                ConstraintOpcode::Num2BitsF => {
+                    let bits = field_api.to_binary(&felt(1))?;
+                    for (name, bit) in args[0].iter().zip(bits) {
+                        vars.insert(name.clone(), bit);
+                    }
+                }
+                ConstraintOpcode::Permute => {
+                    let mut state = [var(0), var(1), var(2)];
+                    hash_api.permute_mut(&mut state)?;
+                    for (i, value) in state.into_iter().enumerate() {
+                        vars.insert(args[i][0].clone(), value);
+                    }
+                }
+                ConstraintOpcode::PermuteBabyBear => {
+                    let mut state: [Felt; 16] = core::array::from_fn(felt);
+                    hash_babybear_api.permute_mut(&mut state)?;
+                    for (i, value) in state.into_iter().enumerate() {
+                        felts.insert(args[i][0].clone(), value);
+                    }
+                }
+                ConstraintOpcode::SelectV => {
+                    let value = api.select(&var(1), &var(2), &var(3))?;
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SelectF => {
+                    let value = field_api.select_f(&var(1), &felt(2), &felt(3))?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SelectE => {
+                    let value = field_api.select_e(&var(1), &ext(2), &ext(3))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::Ext2Felt => {
+                    let Ext(out) = ext(4);
+                    for (i, value) in out.into_iter().enumerate() {
+                        felts.insert(args[i][0].clone(), value);
+                    }
+                }
+                ConstraintOpcode::AssertEqV => api.assert_is_equal(&var(0), &var(1))?,
+                ConstraintOpcode::AssertEqF => field_api.assert_is_equal_f(&felt(0), &felt(1))?,
+                ConstraintOpcode::AssertNeF => field_api.assert_not_equal_f(&felt(0), &felt(1))?,
+                ConstraintOpcode::AssertEqE => field_api.assert_is_equal_e(&ext(0), &ext(1))?,
+                ConstraintOpcode::PrintV => log::debug!("{}", var(0).value()),
+                ConstraintOpcode::PrintF => {
+                    log::debug!("{}", field_api.reduce_slow(felt(0))?.value.value())
+                }
+                ConstraintOpcode::PrintE => {
+                    for limb in field_api.reduce_e(ext(0))?.0.iter() {
+                        log::debug!("{}", limb.value.value());
+                    }
+                }
+                ConstraintOpcode::WitnessV => {
+                    let value = witness_vars[parse_index(&args[1][0])].clone();
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::WitnessF => {
+                    let value = witness_felts[parse_index(&args[1][0])].clone();
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::WitnessE => {
+                    let value = witness_exts[parse_index(&args[1][0])].clone();
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::CommitVkeyHash => api.assert_is_equal(&vkey_hash, &var(0))?,
+                ConstraintOpcode::CommitCommitedValuesDigest => {
+                    api.assert_is_equal(&committed_values_digest, &var(0))?
+                }
+                ConstraintOpcode::CircuitFelts2Ext => {
+                    let value = Ext([felt(1), felt(2), felt(3), felt(4)]);
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::CircuitFelt2Var => {
+                    let value = field_api.reduce_slow(felt(1))?.value;
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::ReduceE => {
+                    let value = field_api.reduce_e(ext(0))?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                opcode => panic!("unhandled opcode: {:?}", opcode),
+            }
+            record(Some(index), api.num_constraints() - num_constraints);
+        }
+
+        Ok(())
+    }
+}
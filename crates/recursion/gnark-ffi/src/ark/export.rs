@@ -0,0 +1,294 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Write},
+};
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSystem, ConstraintSystemRef};
+use serde::{Deserialize, Serialize};
+use sp1_recursion_compiler::constraints::Constraint;
+
+use crate::GnarkWitness;
+
+use super::WrapCircuit;
+
+/// The size in bytes of a BN254 scalar field element.
+const FIELD_SIZE: u32 = 32;
+
+/// The number of constraint list entries of an opcode, and the R1CS constraints they add up to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcodeStats {
+    pub count: usize,
+    pub num_constraints: usize,
+}
+
+/// Statistics about the size of the R1CS of the wrap circuit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitStats {
+    /// The total number of R1CS constraints.
+    pub num_constraints: usize,
+    /// The number of public inputs, not counting the constant one.
+    pub num_public_inputs: usize,
+    /// The number of private witness variables.
+    pub num_witness_variables: usize,
+    /// The R1CS constraints spent range checking the felts and exts of the witness.
+    pub witness_range_checks: usize,
+    /// The statistics of each opcode of the constraint list.
+    pub opcodes: BTreeMap<String, OpcodeStats>,
+    /// The R1CS constraints of each cycle tracker span, including the ones of its children.
+    pub spans: BTreeMap<String, usize>,
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "constraints: {}", self.num_constraints)?;
+        writeln!(f, "public inputs: {}", self.num_public_inputs)?;
+        writeln!(f, "witness variables: {}", self.num_witness_variables)?;
+        writeln!(f, "witness range checks: {}", self.witness_range_checks)?;
+
+        let mut opcodes = self.opcodes.iter().collect::<Vec<_>>();
+        opcodes.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.num_constraints));
+        writeln!(f, "opcodes:")?;
+        for (opcode, stats) in opcodes {
+            writeln!(
+                f,
+                "  {:<28} {:>10} constraints {:>10} occurrences",
+                opcode, stats.num_constraints, stats.count
+            )?;
+        }
+
+        writeln!(f, "spans:")?;
+        for (span, num_constraints) in self.spans.iter() {
+            let depth = span.matches('/').count();
+            let name = span.rsplit('/').next().unwrap();
+            writeln!(
+                f,
+                "  {:indent$}{:<28} {:>10} constraints",
+                "",
+                name,
+                num_constraints,
+                indent = 2 * depth
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The wrap circuit synthesized into an arkworks R1CS, ready to be inspected or exported.
+pub struct SynthesizedWrapCircuit {
+    cs: ConstraintSystemRef<Fr>,
+    matrices: ConstraintMatrices<Fr>,
+    stats: CircuitStats,
+}
+
+impl SynthesizedWrapCircuit {
+    /// Synthesizes the circuit of the given constraint list and witness.
+    ///
+    /// `spans` are the cycle tracker spans of the constraints, as returned by
+    /// `ConstraintCompiler::emit_with_spans`. They are only used for the statistics, and may be
+    /// empty.
+    pub fn new(constraints: &[Constraint], spans: &[String], witness: &GnarkWitness) -> Self {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let circuit = WrapCircuit { constraints, witness };
+
+        let mut stats = CircuitStats::default();
+        circuit
+            .synthesize(cs.clone(), |index, num_constraints| {
+                let Some(index) = index else {
+                    stats.witness_range_checks += num_constraints;
+                    return;
+                };
+                let opcode = format!("{:?}", constraints[index].opcode);
+                let opcode_stats = stats.opcodes.entry(opcode).or_default();
+                opcode_stats.count += 1;
+                opcode_stats.num_constraints += num_constraints;
+
+                // Attribute the constraints to the span and to all of its parents.
+                let span = spans.get(index).map(String::as_str).unwrap_or_default();
+                if num_constraints > 0 && !span.is_empty() {
+                    let mut end = 0;
+                    for part in span.split('/') {
+                        end += part.len();
+                        *stats.spans.entry(span[..end].to_string()).or_default() += num_constraints;
+                        end += 1;
+                    }
+                }
+            })
+            .expect("failed to synthesize circuit");
+
+        cs.finalize();
+        let matrices = cs.to_matrices().expect("the constraint matrices were not constructed");
+        stats.num_constraints = matrices.num_constraints;
+        stats.num_public_inputs = matrices.num_instance_variables - 1;
+        stats.num_witness_variables = matrices.num_witness_variables;
+
+        Self { cs, matrices, stats }
+    }
+
+    pub fn stats(&self) -> &CircuitStats {
+        &self.stats
+    }
+
+    /// Checks that the witness satisfies the constraints, returning the name of the first
+    /// unsatisfied constraint otherwise.
+    pub fn is_satisfied(&self) -> Result<(), String> {
+        match self.cs.which_is_unsatisfied().expect("the witness was not assigned") {
+            None => Ok(()),
+            Some(unsatisfied) => Err(unsatisfied),
+        }
+    }
+
+    /// Writes the constraints in the binary `.r1cs` format of circom.
+    ///
+    /// The wires are the variables of the arkworks R1CS: the constant one, the two public inputs,
+    /// then the private witness variables.
+    pub fn write_r1cs(&self, mut writer: impl Write) -> io::Result<()> {
+        let matrices = &self.matrices;
+        let num_wires = matrices.num_instance_variables + matrices.num_witness_variables;
+
+        writer.write_all(b"r1cs")?;
+        write_u32(&mut writer, 1)?;
+        write_u32(&mut writer, 3)?;
+
+        // The header section.
+        write_section_header(&mut writer, 1, 4 + FIELD_SIZE as u64 + 4 * 4 + 8 + 4)?;
+        write_u32(&mut writer, FIELD_SIZE)?;
+        writer.write_all(&Fr::MODULUS.to_bytes_le())?;
+        write_u32(&mut writer, num_wires as u32)?;
+        write_u32(&mut writer, 0)?;
+        write_u32(&mut writer, (matrices.num_instance_variables - 1) as u32)?;
+        write_u32(&mut writer, 0)?;
+        write_u64(&mut writer, num_wires as u64)?;
+        write_u32(&mut writer, matrices.num_constraints as u32)?;
+
+        // The constraints section.
+        let linear_combinations = [&matrices.a, &matrices.b, &matrices.c];
+        let size = linear_combinations
+            .iter()
+            .flat_map(|matrix| matrix.iter())
+            .map(|row| 4 + row.len() as u64 * (4 + FIELD_SIZE as u64))
+            .sum();
+        write_section_header(&mut writer, 2, size)?;
+        for i in 0..matrices.num_constraints {
+            for matrix in linear_combinations {
+                write_u32(&mut writer, matrix[i].len() as u32)?;
+                for (coefficient, wire) in matrix[i].iter() {
+                    write_u32(&mut writer, *wire as u32)?;
+                    write_field(&mut writer, coefficient)?;
+                }
+            }
+        }
+
+        // The wire to label map section, which is the identity.
+        write_section_header(&mut writer, 3, 8 * num_wires as u64)?;
+        for wire in 0..num_wires {
+            write_u64(&mut writer, wire as u64)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the witness in the binary `.wtns` format of circom, with the wires ordered like in
+    /// [Self::write_r1cs].
+    pub fn write_wtns(&self, mut writer: impl Write) -> io::Result<()> {
+        let cs = self.cs.borrow().expect("the constraint system is still in use");
+        let num_wires = cs.instance_assignment.len() + cs.witness_assignment.len();
+
+        writer.write_all(b"wtns")?;
+        write_u32(&mut writer, 2)?;
+        write_u32(&mut writer, 2)?;
+
+        // The header section.
+        write_section_header(&mut writer, 1, 4 + FIELD_SIZE as u64 + 4)?;
+        write_u32(&mut writer, FIELD_SIZE)?;
+        writer.write_all(&Fr::MODULUS.to_bytes_le())?;
+        write_u32(&mut writer, num_wires as u32)?;
+
+        // The witness section.
+        write_section_header(&mut writer, 2, num_wires as u64 * FIELD_SIZE as u64)?;
+        for value in cs.instance_assignment.iter().chain(cs.witness_assignment.iter()) {
+            write_field(&mut writer, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_u32(writer: &mut impl Write, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_u64(writer: &mut impl Write, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_section_header(writer: &mut impl Write, section_type: u32, size: u64) -> io::Result<()> {
+    write_u32(writer, section_type)?;
+    write_u64(writer, size)
+}
+
+fn write_field(writer: &mut impl Write, value: &Fr) -> io::Result<()> {
+    writer.write_all(&value.into_bigint().to_bytes_le())
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use sp1_recursion_compiler::{
+        circuit::CircuitV2Builder,
+        config::OuterConfig,
+        constraints::ConstraintCompiler,
+        ir::{Builder, Config, Felt, Var, Witness},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_export_wrap_circuit() {
+        type N = <OuterConfig as Config>::N;
+
+        let mut builder = Builder::<OuterConfig>::default();
+        builder.cycle_tracker_v2_enter("outer".to_string());
+        let a: Var<_> = builder.eval(N::from_canonical_u32(3));
+        let b: Var<_> = builder.eval(a * a);
+        builder.cycle_tracker_v2_enter("inner".to_string());
+        let c: Felt<_> = builder.eval(BabyBear::from_canonical_u32(5));
+        let d: Felt<_> = builder.eval(c * c);
+        builder.assert_felt_eq(d, BabyBear::from_canonical_u32(25));
+        builder.cycle_tracker_v2_exit();
+        builder.cycle_tracker_v2_exit();
+        builder.assert_var_eq(b, N::from_canonical_u32(9));
+
+        let mut backend = ConstraintCompiler::<OuterConfig>::default();
+        let (constraints, spans) = backend.emit_with_spans(builder.into_operations());
+        assert_eq!(constraints.len(), spans.len());
+
+        let witness = GnarkWitness::new(Witness::<OuterConfig>::default());
+        let circuit = SynthesizedWrapCircuit::new(&constraints, &spans, &witness);
+        circuit.is_satisfied().unwrap();
+
+        let stats = circuit.stats();
+        assert_eq!(stats.num_public_inputs, 2);
+        assert_eq!(
+            stats.opcodes.values().map(|s| s.num_constraints).sum::<usize>()
+                + stats.witness_range_checks,
+            stats.num_constraints
+        );
+        assert!(stats.spans["outer"] >= stats.spans["outer/inner"]);
+        assert!(stats.spans["outer/inner"] > 0);
+
+        let mut r1cs = Vec::new();
+        circuit.write_r1cs(&mut r1cs).unwrap();
+        assert_eq!(&r1cs[..4], b"r1cs");
+        let num_wires = 1 + stats.num_public_inputs + stats.num_witness_variables;
+        assert_eq!(u32::from_le_bytes(r1cs[56..60].try_into().unwrap()) as usize, num_wires);
+
+        let mut wtns = Vec::new();
+        circuit.write_wtns(&mut wtns).unwrap();
+        assert_eq!(&wtns[..4], b"wtns");
+        assert_eq!(wtns.len(), 12 + 12 + 40 + 12 + 32 * num_wires);
+    }
+}
@@ -0,0 +1,343 @@
+//! A pure-Rust Groth16 backend for the wrap circuit, built on arkworks.
+//!
+//! The constraints emitted by `ConstraintCompiler` are synthesized into an arkworks R1CS that
+//! mirrors the Go circuit, so neither the Go toolchain nor Docker is needed to build the circuit
+//! or to generate proofs. The circuit has its own trusted setup, so its artifacts are not
+//! interchangeable with the ones built by gnark, but the proofs have the same shape: they can be
+//! verified by `sp1-verifier` and by the generated `SP1VerifierGroth16.sol`.
+//!
+//! The synthesized R1CS can also be exported to the `.r1cs` and `.wtns` formats of circom with
+//! [SynthesizedWrapCircuit], which also reports how many constraints each opcode and each cycle
+//! tracker span costs.
+
+mod babybear;
+mod circuit;
+mod export;
+mod poseidon2;
+mod r1cs;
+
+pub use circuit::WrapCircuit;
+pub use export::{CircuitStats, OpcodeStats, SynthesizedWrapCircuit};
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use sp1_core_machine::SP1_CIRCUIT_VERSION;
+use sp1_recursion_compiler::{
+    constraints::Constraint,
+    ir::{Config, Witness},
+};
+
+use crate::{witness::GnarkWitness, Groth16Bn254Proof};
+
+/// The arkworks proving key, which is only used by this backend.
+const PK_PATH: &str = "groth16_ark_pk.bin";
+/// The arkworks verifying key, which is only used by this backend.
+const VK_PATH: &str = "groth16_ark_vk.bin";
+/// The verifying key in the layout written by gnark, which is what `sp1-verifier` reads.
+const GNARK_VK_PATH: &str = "groth16_vk.bin";
+const CONSTRAINTS_PATH: &str = "constraints.json";
+
+/// A prover that can generate proofs with the Groth16 protocol using arkworks.
+#[derive(Debug, Clone)]
+pub struct ArkGroth16Bn254Prover;
+
+impl ArkGroth16Bn254Prover {
+    /// Creates a new [ArkGroth16Bn254Prover].
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn get_vkey_hash(build_dir: &Path) -> [u8; 32] {
+        let vkey_path = build_dir.join(GNARK_VK_PATH);
+        let vk_bin_bytes = std::fs::read(vkey_path).unwrap();
+        Sha256::digest(vk_bin_bytes).into()
+    }
+
+    /// Executes the prover in testing mode with a circuit definition and witness.
+    ///
+    /// This only checks that the witness satisfies the circuit, without running a setup.
+    pub fn test<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>) {
+        use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+        let gnark_witness = GnarkWitness::new(witness);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        WrapCircuit { constraints: &constraints, witness: &gnark_witness }
+            .generate_constraints(cs.clone())
+            .expect("failed to synthesize circuit");
+        if let Some(unsatisfied) = cs.which_is_unsatisfied().unwrap() {
+            panic!("constraint {} is not satisfied", unsatisfied);
+        }
+    }
+
+    pub fn build_contracts(build_dir: PathBuf) {
+        let vk = Self::read_vk(&build_dir);
+
+        // Write the corresponding asset files to the build dir.
+        let sp1_verifier_path = build_dir.join("SP1VerifierGroth16.sol");
+        let vkey_hash = Self::get_vkey_hash(&build_dir);
+        let sp1_verifier_str = include_str!("../../assets/SP1VerifierGroth16.txt")
+            .replace("{SP1_CIRCUIT_VERSION}", SP1_CIRCUIT_VERSION)
+            .replace("{VERIFIER_HASH}", format!("0x{}", hex::encode(vkey_hash)).as_str())
+            .replace("{PROOF_SYSTEM}", "Groth16");
+        let mut sp1_verifier_file = File::create(sp1_verifier_path).unwrap();
+        sp1_verifier_file.write_all(sp1_verifier_str.as_bytes()).unwrap();
+
+        let groth16_verifier_path = build_dir.join("Groth16Verifier.sol");
+        let groth16_verifier_str = groth16_verifier_contract(&vk);
+        let mut groth16_verifier_file = File::create(groth16_verifier_path).unwrap();
+        groth16_verifier_file.write_all(groth16_verifier_str.as_bytes()).unwrap();
+    }
+
+    /// Builds the Groth16 circuit locally, including a fresh trusted setup.
+    pub fn build<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>, build_dir: PathBuf) {
+        let serialized = serde_json::to_string(&constraints).unwrap();
+
+        // Write constraints.
+        let constraints_path = build_dir.join(CONSTRAINTS_PATH);
+        let mut file = File::create(constraints_path).unwrap();
+        file.write_all(serialized.as_bytes()).unwrap();
+
+        // Run the setup.
+        let gnark_witness = GnarkWitness::new(witness);
+        let circuit = WrapCircuit { constraints: &constraints, witness: &gnark_witness };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut OsRng)
+            .expect("failed to run the groth16 setup");
+
+        // Check that the setup works with the template witness.
+        let proof =
+            Groth16::<Bn254>::prove(&pk, circuit, &mut OsRng).expect("failed to generate proof");
+        let public_inputs =
+            public_inputs(&gnark_witness.vkey_hash, &gnark_witness.committed_values_digest);
+        assert!(
+            Groth16::<Bn254>::verify(&vk, &public_inputs, &proof).unwrap(),
+            "failed to verify the template proof"
+        );
+
+        // Write the keys.
+        let file = BufWriter::new(File::create(build_dir.join(PK_PATH)).unwrap());
+        pk.serialize_uncompressed(file).expect("failed to write proving key");
+        let file = BufWriter::new(File::create(build_dir.join(VK_PATH)).unwrap());
+        vk.serialize_uncompressed(file).expect("failed to write verifying key");
+        std::fs::write(build_dir.join(GNARK_VK_PATH), gnark_vk_bytes(&pk)).unwrap();
+
+        // Build the contracts.
+        Self::build_contracts(build_dir);
+    }
+
+    /// Generates a Groth16 proof given a witness.
+    pub fn prove<C: Config>(&self, witness: Witness<C>, build_dir: PathBuf) -> Groth16Bn254Proof {
+        let constraints: Vec<Constraint> = serde_json::from_reader(BufReader::new(
+            File::open(build_dir.join(CONSTRAINTS_PATH)).unwrap(),
+        ))
+        .expect("failed to read constraints");
+        let file = BufReader::new(File::open(build_dir.join(PK_PATH)).unwrap());
+        let pk = ProvingKey::<Bn254>::deserialize_uncompressed_unchecked(file)
+            .expect("failed to read proving key");
+
+        let gnark_witness = GnarkWitness::new(witness);
+        let circuit = WrapCircuit { constraints: &constraints, witness: &gnark_witness };
+        let proof =
+            Groth16::<Bn254>::prove(&pk, circuit, &mut OsRng).expect("failed to generate proof");
+
+        let encoded_proof = hex::encode(proof_bytes(&proof));
+        Groth16Bn254Proof {
+            public_inputs: [gnark_witness.vkey_hash, gnark_witness.committed_values_digest],
+            raw_proof: encoded_proof.clone(),
+            encoded_proof,
+            groth16_vkey_hash: Self::get_vkey_hash(&build_dir),
+        }
+    }
+
+    /// Verify a Groth16 proof and verify that the supplied vkey_hash and committed_values_digest
+    /// match.
+    pub fn verify(
+        &self,
+        proof: &Groth16Bn254Proof,
+        vkey_hash: &BigUint,
+        committed_values_digest: &BigUint,
+        build_dir: &Path,
+    ) {
+        if proof.groth16_vkey_hash != Self::get_vkey_hash(build_dir) {
+            panic!(
+                "Proof vkey hash does not match circuit vkey hash, it was generated with a different circuit."
+            );
+        }
+        let vk = Self::read_vk(build_dir);
+        let bytes = hex::decode(&proof.raw_proof).expect("failed to decode proof");
+        let proof = proof_from_bytes(&bytes).expect("failed to decode proof");
+        let public_inputs =
+            public_inputs(&vkey_hash.to_string(), &committed_values_digest.to_string());
+        let pvk = PreparedVerifyingKey::from(vk);
+        let valid = Groth16::<Bn254>::verify_with_processed_vk(&pvk, &public_inputs, &proof)
+            .expect("failed to verify proof");
+        assert!(valid, "failed to verify proof");
+    }
+
+    fn read_vk(build_dir: &Path) -> VerifyingKey<Bn254> {
+        let file = BufReader::new(File::open(build_dir.join(VK_PATH)).unwrap());
+        VerifyingKey::<Bn254>::deserialize_uncompressed_unchecked(file)
+            .expect("failed to read verifying key")
+    }
+}
+
+impl Default for ArkGroth16Bn254Prover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn public_inputs(vkey_hash: &str, committed_values_digest: &str) -> [Fr; 2] {
+    let parse = |x: &str| Fr::from(x.parse::<BigUint>().expect("invalid public input"));
+    [parse(vkey_hash), parse(committed_values_digest)]
+}
+
+fn fq_bytes(x: &Fq) -> [u8; 32] {
+    x.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+fn fq_from_bytes(bytes: &[u8]) -> Option<Fq> {
+    let value = BigUint::from_bytes_be(bytes);
+    (value < BigUint::from(Fq::MODULUS)).then(|| Fq::from(value))
+}
+
+fn is_lexicographically_largest(x: &Fq) -> bool {
+    x.into_bigint() > Fq::MODULUS_MINUS_ONE_DIV_TWO
+}
+
+/// The proof in the layout of gnark: `A.x | A.y | B.x.c1 | B.x.c0 | B.y.c1 | B.y.c0 | C.x | C.y`,
+/// which is also the layout expected by the Solidity verifier.
+fn proof_bytes(proof: &Proof<Bn254>) -> Vec<u8> {
+    let (a_x, a_y) = proof.a.xy().unwrap();
+    let (b_x, b_y) = proof.b.xy().unwrap();
+    let (c_x, c_y) = proof.c.xy().unwrap();
+    [a_x, a_y, &b_x.c1, &b_x.c0, &b_y.c1, &b_y.c0, c_x, c_y]
+        .into_iter()
+        .flat_map(fq_bytes)
+        .collect()
+}
+
+fn proof_from_bytes(bytes: &[u8]) -> Option<Proof<Bn254>> {
+    if bytes.len() != 256 {
+        return None;
+    }
+    let limbs = bytes.chunks(32).map(fq_from_bytes).collect::<Option<Vec<_>>>()?;
+    let a = G1Affine::new_unchecked(limbs[0], limbs[1]);
+    let b = G2Affine::new_unchecked(Fq2::new(limbs[3], limbs[2]), Fq2::new(limbs[5], limbs[4]));
+    let c = G1Affine::new_unchecked(limbs[6], limbs[7]);
+    let valid = a.is_on_curve()
+        && a.is_in_correct_subgroup_assuming_on_curve()
+        && b.is_on_curve()
+        && b.is_in_correct_subgroup_assuming_on_curve()
+        && c.is_on_curve()
+        && c.is_in_correct_subgroup_assuming_on_curve();
+    if !valid {
+        return None;
+    }
+    Some(Proof { a, b, c })
+}
+
+/// Compresses a G1 point like gnark, with the flag in the two most significant bits.
+fn compress_g1(p: &G1Affine) -> [u8; 32] {
+    let Some((x, y)) = p.xy() else {
+        let mut bytes = [0u8; 32];
+        bytes[0] = 0b01 << 6;
+        return bytes;
+    };
+    let mut bytes = fq_bytes(x);
+    bytes[0] |= if is_lexicographically_largest(y) { 0b11 << 6 } else { 0b10 << 6 };
+    bytes
+}
+
+/// Compresses a G2 point like gnark, as `x.c1 | x.c0` with the flag in the two most significant
+/// bits.
+fn compress_g2(p: &G2Affine) -> [u8; 64] {
+    let mut bytes = [0u8; 64];
+    let Some((x, y)) = p.xy() else {
+        bytes[0] = 0b01 << 6;
+        return bytes;
+    };
+    bytes[..32].copy_from_slice(&fq_bytes(&x.c1));
+    bytes[32..].copy_from_slice(&fq_bytes(&x.c0));
+    let largest = if y.c1.is_zero() {
+        is_lexicographically_largest(&y.c0)
+    } else {
+        is_lexicographically_largest(&y.c1)
+    };
+    bytes[0] |= if largest { 0b11 << 6 } else { 0b10 << 6 };
+    bytes
+}
+
+/// Serializes the verifying key in the compressed layout of gnark, which is what `sp1-verifier`
+/// reads and what the vkey hash of the proofs commits to.
+fn gnark_vk_bytes(pk: &ProvingKey<Bn254>) -> Vec<u8> {
+    let vk = &pk.vk;
+    let mut bytes = Vec::new();
+    bytes.extend(compress_g1(&vk.alpha_g1));
+    bytes.extend(compress_g1(&pk.beta_g1));
+    bytes.extend(compress_g2(&vk.beta_g2));
+    bytes.extend(compress_g2(&vk.gamma_g2));
+    bytes.extend(compress_g1(&pk.delta_g1));
+    bytes.extend(compress_g2(&vk.delta_g2));
+    bytes.extend((vk.gamma_abc_g1.len() as u32).to_be_bytes());
+    for k in vk.gamma_abc_g1.iter() {
+        bytes.extend(compress_g1(k));
+    }
+    bytes
+}
+
+/// Fills in the Solidity verifier template with the given verifying key.
+fn groth16_verifier_contract(vk: &VerifyingKey<Bn254>) -> String {
+    let dec = |x: &Fq| BigUint::from(x.into_bigint()).to_string();
+    let g1 = |p: &G1Affine| {
+        let (x, y) = p.xy().unwrap();
+        (dec(x), dec(y))
+    };
+    // The pairing check multiplies with the negations of beta, gamma and delta.
+    let g2_neg = |p: &G2Affine| {
+        let (x, y) = (-*p).xy().map(|(x, y)| (*x, *y)).unwrap();
+        [dec(&x.c1), dec(&x.c0), dec(&y.c1), dec(&y.c0)]
+    };
+
+    let (alpha_x, alpha_y) = g1(&vk.alpha_g1);
+    let beta = g2_neg(&vk.beta_g2);
+    let gamma = g2_neg(&vk.gamma_g2);
+    let delta = g2_neg(&vk.delta_g2);
+    let (ic0_x, ic0_y) = g1(&vk.gamma_abc_g1[0]);
+    let (ic1_x, ic1_y) = g1(&vk.gamma_abc_g1[1]);
+    let (ic2_x, ic2_y) = g1(&vk.gamma_abc_g1[2]);
+
+    include_str!("../../assets/Groth16VerifierArk.txt")
+        .replace("{ALPHA_X}", &alpha_x)
+        .replace("{ALPHA_Y}", &alpha_y)
+        .replace("{BETA_NEG_X_1}", &beta[0])
+        .replace("{BETA_NEG_X_0}", &beta[1])
+        .replace("{BETA_NEG_Y_1}", &beta[2])
+        .replace("{BETA_NEG_Y_0}", &beta[3])
+        .replace("{GAMMA_NEG_X_1}", &gamma[0])
+        .replace("{GAMMA_NEG_X_0}", &gamma[1])
+        .replace("{GAMMA_NEG_Y_1}", &gamma[2])
+        .replace("{GAMMA_NEG_Y_0}", &gamma[3])
+        .replace("{DELTA_NEG_X_1}", &delta[0])
+        .replace("{DELTA_NEG_X_0}", &delta[1])
+        .replace("{DELTA_NEG_Y_1}", &delta[2])
+        .replace("{DELTA_NEG_Y_0}", &delta[3])
+        .replace("{IC0_X}", &ic0_x)
+        .replace("{IC0_Y}", &ic0_y)
+        .replace("{IC1_X}", &ic1_x)
+        .replace("{IC1_Y}", &ic1_y)
+        .replace("{IC2_X}", &ic2_x)
+        .replace("{IC2_Y}", &ic2_y)
+}
@@ -0,0 +1,230 @@
+use ark_bn254::Fr;
+use ark_relations::r1cs::SynthesisError;
+use num_bigint::BigUint;
+use p3_field::PrimeField;
+use sp1_primitives::RC_16_30_U32;
+use sp1_recursion_core::stark::bn254_poseidon2_rc3;
+
+use super::{
+    babybear::{BabyBearChip, Felt},
+    r1cs::{R1csBuilder, Var},
+};
+
+const WIDTH: usize = 3;
+const NUM_EXTERNAL_ROUNDS: usize = 8;
+const NUM_INTERNAL_ROUNDS: usize = 56;
+
+const BABYBEAR_WIDTH: usize = 16;
+const BABYBEAR_NUM_EXTERNAL_ROUNDS: usize = 8;
+const BABYBEAR_NUM_INTERNAL_ROUNDS: usize = 13;
+
+/// The Poseidon2 permutation over a state of three BN254 elements, a port of
+/// `poseidon2.Poseidon2Chip` of the Go circuit.
+pub struct Poseidon2Chip<'a> {
+    api: &'a R1csBuilder,
+    round_constants: Vec<[Fr; WIDTH]>,
+}
+
+impl<'a> Poseidon2Chip<'a> {
+    pub fn new(api: &'a R1csBuilder) -> Self {
+        let round_constants = bn254_poseidon2_rc3()
+            .into_iter()
+            .map(|rc| rc.map(|x| Fr::from(x.as_canonical_biguint())))
+            .collect();
+        Self { api, round_constants }
+    }
+
+    pub fn permute_mut(&self, state: &mut [Var; WIDTH]) -> Result<(), SynthesisError> {
+        // The initial linear layer.
+        self.matrix_permute_mut(state);
+
+        // The first half of the external rounds.
+        let rounds = NUM_EXTERNAL_ROUNDS + NUM_INTERNAL_ROUNDS;
+        let rounds_f_beginning = NUM_EXTERNAL_ROUNDS / 2;
+        for r in 0..rounds_f_beginning {
+            self.add_rc(state, r);
+            self.sbox(state)?;
+            self.matrix_permute_mut(state);
+        }
+
+        // The internal rounds.
+        let p_end = rounds_f_beginning + NUM_INTERNAL_ROUNDS;
+        for r in rounds_f_beginning..p_end {
+            state[0] = self.api.add(&state[0], &self.api.constant(self.round_constants[r][0]));
+            state[0] = self.sbox_p(&state[0])?;
+            self.diffusion_permute_mut(state);
+        }
+
+        // The second half of the external rounds.
+        for r in p_end..rounds {
+            self.add_rc(state, r);
+            self.sbox(state)?;
+            self.matrix_permute_mut(state);
+        }
+
+        Ok(())
+    }
+
+    fn add_rc(&self, state: &mut [Var; WIDTH], round: usize) {
+        for (x, rc) in state.iter_mut().zip(self.round_constants[round]) {
+            *x = self.api.add(x, &self.api.constant(rc));
+        }
+    }
+
+    fn sbox_p(&self, input: &Var) -> Result<Var, SynthesisError> {
+        let squared = self.api.mul(input, input)?;
+        let input_4 = self.api.mul(&squared, &squared)?;
+        self.api.mul(&input_4, input)
+    }
+
+    fn sbox(&self, state: &mut [Var; WIDTH]) -> Result<(), SynthesisError> {
+        for x in state.iter_mut() {
+            *x = self.sbox_p(x)?;
+        }
+        Ok(())
+    }
+
+    fn diffusion_permute_mut(&self, state: &mut [Var; WIDTH]) {
+        let sum = state.iter().fold(self.api.zero(), |acc, x| self.api.add(&acc, x));
+        for (x, diag) in state.iter_mut().zip([1u32, 1, 2]) {
+            *x = self.api.add(&self.api.mul_const(x, Fr::from(diag)), &sum);
+        }
+    }
+
+    fn matrix_permute_mut(&self, state: &mut [Var; WIDTH]) {
+        let sum = state.iter().fold(self.api.zero(), |acc, x| self.api.add(&acc, x));
+        for x in state.iter_mut() {
+            *x = self.api.add(x, &sum);
+        }
+    }
+}
+
+/// The Poseidon2 permutation over a state of sixteen BabyBear elements, a port of
+/// `poseidon2.Poseidon2BabyBearChip` of the Go circuit.
+pub struct Poseidon2BabyBearChip<'a> {
+    api: &'a R1csBuilder,
+    field: &'a BabyBearChip<'a>,
+}
+
+impl<'a> Poseidon2BabyBearChip<'a> {
+    pub fn new(api: &'a R1csBuilder, field: &'a BabyBearChip<'a>) -> Self {
+        Self { api, field }
+    }
+
+    pub fn permute_mut(&self, state: &mut [Felt; BABYBEAR_WIDTH]) -> Result<(), SynthesisError> {
+        // The initial linear layer.
+        self.external_linear_layer(state)?;
+
+        // The first half of the external rounds.
+        let rounds = BABYBEAR_NUM_EXTERNAL_ROUNDS + BABYBEAR_NUM_INTERNAL_ROUNDS;
+        let rounds_f_beginning = BABYBEAR_NUM_EXTERNAL_ROUNDS / 2;
+        for r in 0..rounds_f_beginning {
+            self.add_rc(state, r)?;
+            self.sbox(state)?;
+            self.external_linear_layer(state)?;
+        }
+
+        // The internal rounds.
+        let p_end = rounds_f_beginning + BABYBEAR_NUM_INTERNAL_ROUNDS;
+        for r in rounds_f_beginning..p_end {
+            state[0] = self.field.add_f(&state[0], &self.field.from_u32(RC_16_30_U32[r][0]))?;
+            state[0] = self.sbox_p(&state[0])?;
+            self.diffusion_permute_mut(state)?;
+        }
+
+        // The second half of the external rounds.
+        for r in p_end..rounds {
+            self.add_rc(state, r)?;
+            self.sbox(state)?;
+            self.external_linear_layer(state)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_rc(
+        &self,
+        state: &mut [Felt; BABYBEAR_WIDTH],
+        round: usize,
+    ) -> Result<(), SynthesisError> {
+        for (x, rc) in state.iter_mut().zip(RC_16_30_U32[round]) {
+            *x = self.field.add_f(x, &self.field.from_u32(rc))?;
+        }
+        Ok(())
+    }
+
+    fn sbox_p(&self, input: &Felt) -> Result<Felt, SynthesisError> {
+        let input = self.field.add_f(input, &self.field.from_u32(0))?;
+        let input = self.field.reduce_slow(input)?.value;
+        let i2 = self.api.mul(&input, &input)?;
+        let i4 = self.api.mul(&i2, &i2)?;
+        let i6 = self.api.mul(&i4, &i2)?;
+        let i7 = self.api.mul(&i6, &input)?;
+        self.field.reduce_slow(Felt { value: i7, upper_bound: BigUint::from(2013265921u32).pow(7) })
+    }
+
+    fn sbox(&self, state: &mut [Felt; BABYBEAR_WIDTH]) -> Result<(), SynthesisError> {
+        for x in state.iter_mut() {
+            *x = self.sbox_p(x)?;
+        }
+        Ok(())
+    }
+
+    fn mds_light_permutation_4x4(&self, state: &mut [Felt]) -> Result<(), SynthesisError> {
+        let f = self.field;
+        let t01 = f.add_f(&state[0], &state[1])?;
+        let t23 = f.add_f(&state[2], &state[3])?;
+        let t0123 = f.add_f(&t01, &t23)?;
+        let t01123 = f.add_f(&t0123, &state[1])?;
+        let t01233 = f.add_f(&t0123, &state[3])?;
+        state[3] = f.add_f(&t01233, &f.mul_f_const(&state[0], 2)?)?;
+        state[1] = f.add_f(&t01123, &f.mul_f_const(&state[2], 2)?)?;
+        state[0] = f.add_f(&t01123, &t01)?;
+        state[2] = f.add_f(&t01233, &t23)?;
+        Ok(())
+    }
+
+    fn external_linear_layer(
+        &self,
+        state: &mut [Felt; BABYBEAR_WIDTH],
+    ) -> Result<(), SynthesisError> {
+        for chunk in state.chunks_mut(4) {
+            self.mds_light_permutation_4x4(chunk)?;
+        }
+
+        let mut sums = [state[0].clone(), state[1].clone(), state[2].clone(), state[3].clone()];
+        for i in (4..BABYBEAR_WIDTH).step_by(4) {
+            for j in 0..4 {
+                sums[j] = self.field.add_f(&sums[j], &state[i + j])?;
+            }
+        }
+
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = self.field.add_f(x, &sums[i % 4])?;
+        }
+        Ok(())
+    }
+
+    fn diffusion_permute_mut(
+        &self,
+        state: &mut [Felt; BABYBEAR_WIDTH],
+    ) -> Result<(), SynthesisError> {
+        const MAT_INTERNAL_DIAG_M1: [u32; BABYBEAR_WIDTH] =
+            [2013265919, 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192, 32768];
+        const MONTY_INVERSE: u32 = 943718400;
+
+        let mut sum = self.field.from_u32(0);
+        for x in state.iter() {
+            sum = self.field.add_f(&sum, x)?;
+        }
+
+        for (x, diag) in state.iter_mut().zip(MAT_INTERNAL_DIAG_M1) {
+            *x = self.field.mul_f(x, &self.field.from_u32(diag))?;
+            *x = self.field.add_f(x, &sum)?;
+        }
+        for x in state.iter_mut() {
+            *x = self.field.mul_f(x, &self.field.from_u32(MONTY_INVERSE))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use ark_bn254::Fr;
+use ark_ff::{Field, One, Zero};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSystemRef, LinearCombination, SynthesisError, Variable},
+};
+use num_bigint::BigUint;
+
+/// A BN254 variable of the circuit: a linear combination of R1CS variables, together with its
+/// value in the witness.
+///
+/// Additions and multiplications by constants only grow the linear combination, like variables of
+/// the gnark frontend, so only multiplications of two non-constant variables add constraints.
+#[derive(Debug, Clone)]
+pub struct Var {
+    lc: LinearCombination<Fr>,
+    value: Fr,
+    constant: bool,
+}
+
+impl Var {
+    /// The value of the variable in the witness.
+    pub fn value(&self) -> Fr {
+        self.value
+    }
+
+    /// The value of the variable in the witness, as an integer.
+    pub fn biguint(&self) -> BigUint {
+        self.value.into()
+    }
+}
+
+/// A thin wrapper around an arkworks constraint system that mirrors the `frontend.API` of gnark
+/// used by the Go circuit.
+pub struct R1csBuilder {
+    cs: ConstraintSystemRef<Fr>,
+}
+
+impl R1csBuilder {
+    pub fn new(cs: ConstraintSystemRef<Fr>) -> Self {
+        Self { cs }
+    }
+
+    /// The number of constraints added so far.
+    pub fn num_constraints(&self) -> usize {
+        self.cs.num_constraints()
+    }
+
+    pub fn constant(&self, value: Fr) -> Var {
+        Var { lc: lc!() + (value, Variable::One), value, constant: true }
+    }
+
+    pub fn constant_biguint(&self, value: &BigUint) -> Var {
+        self.constant(Fr::from(value.clone()))
+    }
+
+    pub fn zero(&self) -> Var {
+        self.constant(Fr::zero())
+    }
+
+    /// Allocates a private witness variable.
+    pub fn witness(&self, value: Fr) -> Result<Var, SynthesisError> {
+        let variable = self.cs.new_witness_variable(|| Ok(value))?;
+        Ok(Var { lc: lc!() + variable, value, constant: false })
+    }
+
+    /// Allocates a public input variable.
+    pub fn public_input(&self, value: Fr) -> Result<Var, SynthesisError> {
+        let variable = self.cs.new_input_variable(|| Ok(value))?;
+        Ok(Var { lc: lc!() + variable, value, constant: false })
+    }
+
+    pub fn add(&self, a: &Var, b: &Var) -> Var {
+        Var { lc: &a.lc + &b.lc, value: a.value + b.value, constant: a.constant && b.constant }
+    }
+
+    pub fn sub(&self, a: &Var, b: &Var) -> Var {
+        Var { lc: &a.lc - &b.lc, value: a.value - b.value, constant: a.constant && b.constant }
+    }
+
+    pub fn neg(&self, a: &Var) -> Var {
+        Var { lc: -a.lc.clone(), value: -a.value, constant: a.constant }
+    }
+
+    pub fn mul_const(&self, a: &Var, c: Fr) -> Var {
+        Var { lc: a.lc.clone() * c, value: a.value * c, constant: a.constant }
+    }
+
+    pub fn mul(&self, a: &Var, b: &Var) -> Result<Var, SynthesisError> {
+        if a.constant {
+            return Ok(self.mul_const(b, a.value));
+        }
+        if b.constant {
+            return Ok(self.mul_const(a, b.value));
+        }
+        let product = self.witness(a.value * b.value)?;
+        self.cs.enforce_constraint(a.lc.clone(), b.lc.clone(), product.lc.clone())?;
+        Ok(product)
+    }
+
+    pub fn assert_is_equal(&self, a: &Var, b: &Var) -> Result<(), SynthesisError> {
+        self.cs.enforce_constraint(&a.lc - &b.lc, lc!() + Variable::One, lc!())
+    }
+
+    pub fn assert_is_zero(&self, a: &Var) -> Result<(), SynthesisError> {
+        self.assert_is_equal(a, &self.zero())
+    }
+
+    pub fn assert_is_different(&self, a: &Var, b: &Var) -> Result<(), SynthesisError> {
+        let diff = self.sub(a, b);
+        let inv = self.witness(diff.value.inverse().unwrap_or_default())?;
+        self.cs.enforce_constraint(diff.lc, inv.lc, lc!() + Variable::One)
+    }
+
+    pub fn assert_is_boolean(&self, a: &Var) -> Result<(), SynthesisError> {
+        self.cs.enforce_constraint(a.lc.clone(), a.lc.clone() - Variable::One, lc!())
+    }
+
+    /// Returns 1 if `a` is zero and 0 otherwise.
+    pub fn is_zero(&self, a: &Var) -> Result<Var, SynthesisError> {
+        let out = self.witness(if a.value.is_zero() { Fr::one() } else { Fr::zero() })?;
+        let inv = self.witness(a.value.inverse().unwrap_or_default())?;
+        // a * inv = 1 - out and a * out = 0.
+        self.cs.enforce_constraint(a.lc.clone(), inv.lc, lc!() + Variable::One - &out.lc)?;
+        self.cs.enforce_constraint(a.lc.clone(), out.lc.clone(), lc!())?;
+        Ok(out)
+    }
+
+    /// Returns `a` if `cond` is 1 and `b` if `cond` is 0.
+    pub fn select(&self, cond: &Var, a: &Var, b: &Var) -> Result<Var, SynthesisError> {
+        let diff = self.sub(a, b);
+        let scaled = self.mul(cond, &diff)?;
+        Ok(self.add(b, &scaled))
+    }
+
+    /// Decomposes `a` into `num_bits` little-endian bits, which also range checks it.
+    pub fn to_binary(&self, a: &Var, num_bits: usize) -> Result<Vec<Var>, SynthesisError> {
+        let value = a.biguint();
+        let mut bits = Vec::with_capacity(num_bits);
+        let mut recomposed = self.zero();
+        let mut coeff = Fr::one();
+        for i in 0..num_bits {
+            let bit = self.witness(if value.bit(i as u64) { Fr::one() } else { Fr::zero() })?;
+            self.assert_is_boolean(&bit)?;
+            recomposed = self.add(&recomposed, &self.mul_const(&bit, coeff));
+            coeff.double_in_place();
+            bits.push(bit);
+        }
+        self.assert_is_equal(a, &recomposed)?;
+        Ok(bits)
+    }
+
+    /// Allocates the result of a hint, i.e. a value computed outside of the circuit that the
+    /// caller constrains.
+    pub fn hint(&self, value: &BigUint) -> Result<Var, SynthesisError> {
+        self.witness(Fr::from(value.clone()))
+    }
+}
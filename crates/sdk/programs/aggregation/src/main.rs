@@ -1,4 +1,4 @@
-//! A simple program that aggregates the proofs of multiple programs proven with the zkVM.
+//! The built-in aggregation program of the SDK, see `sp1_sdk::aggregation`.
 
 #![no_main]
 sp1_zkvm::entrypoint!(main);
@@ -6,6 +6,10 @@ sp1_zkvm::entrypoint!(main);
 use sha2::Digest;
 use sha2::Sha256;
 
+/// The version of the program and of the format of its committed values, which must match
+/// `sp1_sdk::aggregation::AGGREGATION_PROGRAM_VERSION`.
+const AGGREGATION_PROGRAM_VERSION: u32 = 1;
+
 pub fn words_to_bytes_le(words: &[u32; 8]) -> [u8; 32] {
     let mut bytes = [0u8; 32];
     for i in 0..8 {
@@ -15,19 +19,20 @@ pub fn words_to_bytes_le(words: &[u32; 8]) -> [u8; 32] {
     bytes
 }
 
-/// Encode a list of vkeys and committed values into a single byte array. In the future this could
-/// be a merkle tree or some other commitment scheme.
+/// Encode a list of vkeys and committed values into a single byte array.
 ///
-/// ( vkeys.len() || vkeys || committed_values[0].len as u32 || committed_values[0] || ... )
+/// ( version || vkeys.len() || vkeys || committed_values[0].len as u32 || committed_values[0] ||
+/// ... )
 pub fn commit_proof_pairs(vkeys: &[[u32; 8]], committed_values: &[Vec<u8>]) -> Vec<u8> {
     assert_eq!(vkeys.len(), committed_values.len());
     let mut res = Vec::with_capacity(
-        4 + vkeys.len() * 32
+        8 + vkeys.len() * 32
             + committed_values.len() * 4
             + committed_values.iter().map(|vals| vals.len()).sum::<usize>(),
     );
 
     // Note we use big endian because abi.encodePacked in solidity does also
+    res.extend_from_slice(&AGGREGATION_PROGRAM_VERSION.to_be_bytes());
     res.extend_from_slice(&(vkeys.len() as u32).to_be_bytes());
     for vkey in vkeys.iter() {
         res.extend_from_slice(&words_to_bytes_le(vkey));
@@ -56,10 +61,8 @@ pub fn main() {
         sp1_zkvm::lib::verify::verify_sp1_proof(vkey, &public_values_digest.into());
     }
 
-    // TODO: Do something interesting with the proofs here.
-    //
-    // For example, commit to the verified proofs in a merkle tree. For now, we'll just commit to
-    // all the (vkey, input) pairs.
+    // Commit to all the (vkey, public values) pairs, so that the inclusion of a proof can be
+    // checked from the public values of the aggregate.
     let commitment = commit_proof_pairs(&vkeys, &public_values);
     sp1_zkvm::io::commit_slice(&commitment);
 }
@@ -1,13 +1,19 @@
-use sp1_core_executor::{ExecutionReport, HookEnv, SP1ContextBuilder};
+use sp1_core_executor::{
+    CancellationToken, ExecutionReport, HookEnv, ProgressListener, SP1Context, SP1ContextBuilder,
+};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_primitives::io::SP1PublicValues;
-use sp1_prover::{components::DefaultProverComponents, SP1ProvingKey};
+use sp1_prover::{components::DefaultProverComponents, SP1ProvingKey, SP1VerifyingKey};
 
 use anyhow::{Ok, Result};
 use sp1_stark::{SP1CoreOpts, SP1ProverOpts};
-use std::time::Duration;
+use std::{ops::Deref, path::PathBuf, sync::Arc, time::Duration};
 
-use crate::{provers::ProofOpts, Prover, SP1ProofKind, SP1ProofWithPublicValues};
+use crate::{
+    aggregation::{aggregation_stdin, AGGREGATION_ELF},
+    provers::ProofOpts,
+    Prover, SP1ProofKind, SP1ProofWithPublicValues,
+};
 
 /// Builder to prepare and configure execution of a program on an input.
 /// May be run with [Self::run].
@@ -38,6 +44,16 @@ impl<'a> Execute<'a> {
         Ok(prover.sp1_prover().execute(elf, &stdin, context)?)
     }
 
+    /// Execute the program on the input asynchronously, consuming the built action `self`.
+    ///
+    /// Execution runs on tokio's blocking thread pool, so this must be called from within a tokio
+    /// runtime. Hooks registered with [Self::with_hook] are not supported and return an error.
+    pub async fn run_async(self) -> Result<(SP1PublicValues, ExecutionReport)> {
+        let Self { prover, elf, stdin, mut context_builder } = self;
+        let context = context_builder.build();
+        prover.execute_async(elf, &stdin, context).await
+    }
+
     /// Add a runtime [Hook](super::Hook) into the context.
     ///
     /// Hooks may be invoked from within SP1 by writing to the specified file descriptor `fd`
@@ -77,11 +93,39 @@ pub struct Prove<'a> {
     prover: &'a dyn Prover<DefaultProverComponents>,
     kind: SP1ProofKind,
     context_builder: SP1ContextBuilder<'a>,
-    pk: &'a SP1ProvingKey,
+    pk: ProvingKeyRef<'a>,
     stdin: SP1Stdin,
     core_opts: SP1CoreOpts,
     recursion_opts: SP1CoreOpts,
     timeout: Option<Duration>,
+    job_dir: Option<PathBuf>,
+}
+
+/// The proving key of a [Prove] action.
+enum ProvingKeyRef<'a> {
+    Borrowed(&'a SP1ProvingKey),
+    Shared(Arc<SP1ProvingKey>),
+}
+
+impl ProvingKeyRef<'_> {
+    /// Returns the key as shared, cloning it if it is borrowed.
+    fn into_shared(self) -> Arc<SP1ProvingKey> {
+        match self {
+            Self::Borrowed(pk) => Arc::new(pk.clone()),
+            Self::Shared(pk) => pk,
+        }
+    }
+}
+
+impl Deref for ProvingKeyRef<'_> {
+    type Target = SP1ProvingKey;
+
+    fn deref(&self) -> &SP1ProvingKey {
+        match self {
+            Self::Borrowed(pk) => pk,
+            Self::Shared(pk) => pk,
+        }
+    }
 }
 
 impl<'a> Prove<'a> {
@@ -93,6 +137,27 @@ impl<'a> Prove<'a> {
         prover: &'a dyn Prover<DefaultProverComponents>,
         pk: &'a SP1ProvingKey,
         stdin: SP1Stdin,
+    ) -> Self {
+        Self::with_pk(prover, ProvingKeyRef::Borrowed(pk), stdin)
+    }
+
+    /// Prepare to prove the execution of the given program with the given input, sharing the
+    /// proving key with the action.
+    ///
+    /// Prefer using [ProverClient::prove_shared](super::ProverClient::prove_shared).
+    /// See there for more documentation.
+    pub fn new_shared(
+        prover: &'a dyn Prover<DefaultProverComponents>,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+    ) -> Self {
+        Self::with_pk(prover, ProvingKeyRef::Shared(pk), stdin)
+    }
+
+    fn with_pk(
+        prover: &'a dyn Prover<DefaultProverComponents>,
+        pk: ProvingKeyRef<'a>,
+        stdin: SP1Stdin,
     ) -> Self {
         Self {
             prover,
@@ -103,11 +168,42 @@ impl<'a> Prove<'a> {
             core_opts: SP1CoreOpts::default(),
             recursion_opts: SP1CoreOpts::recursion(),
             timeout: None,
+            job_dir: None,
         }
     }
 
     /// Prove the execution of the program on the input, consuming the built action `self`.
     pub fn run(self) -> Result<SP1ProofWithPublicValues> {
+        let (prover, pk, stdin, proof_opts, context, kind) = self.into_parts();
+        prover.prove(&pk, stdin, proof_opts, context, kind)
+    }
+
+    /// Prove the execution of the program on the input asynchronously, consuming the built action
+    /// `self`.
+    ///
+    /// Local proving runs on tokio's blocking thread pool, so this must be called from within a
+    /// tokio runtime. Hooks registered with [Self::with_hook] are not supported and return an
+    /// error. The network prover awaits its requests without blocking.
+    ///
+    /// A borrowed proving key is cloned for the proving job. Build the action with
+    /// [ProverClient::prove_shared](super::ProverClient::prove_shared) to share it instead.
+    pub async fn run_async(self) -> Result<SP1ProofWithPublicValues> {
+        let (prover, pk, stdin, proof_opts, context, kind) = self.into_parts();
+        prover.prove_async(pk.into_shared(), stdin, proof_opts, context, kind).await
+    }
+
+    /// Splits the action into the arguments of [Prover::prove].
+    #[allow(clippy::type_complexity)]
+    fn into_parts(
+        self,
+    ) -> (
+        &'a dyn Prover<DefaultProverComponents>,
+        ProvingKeyRef<'a>,
+        SP1Stdin,
+        ProofOpts,
+        SP1Context<'a>,
+        SP1ProofKind,
+    ) {
         let Self {
             prover,
             kind,
@@ -117,9 +213,10 @@ impl<'a> Prove<'a> {
             core_opts,
             recursion_opts,
             timeout,
+            job_dir,
         } = self;
         let opts = SP1ProverOpts { core_opts, recursion_opts };
-        let proof_opts = ProofOpts { sp1_prover_opts: opts, timeout };
+        let proof_opts = ProofOpts { sp1_prover_opts: opts, timeout, job_dir };
         let context = context_builder.build();
 
         // Dump the program and stdin to files for debugging if `SP1_DUMP` is set.
@@ -133,7 +230,20 @@ impl<'a> Prove<'a> {
             std::fs::write("stdin.bin", stdin.clone()).unwrap();
         }
 
-        prover.prove(pk, stdin, proof_opts, context, kind)
+        (prover, pk, stdin, proof_opts, context, kind)
+    }
+
+    /// Set the prover options, timeout and job directory, overriding any previously configured
+    /// values.
+    ///
+    /// [ProverClient::prove](super::ProverClient::prove) calls this with the defaults the client
+    /// was built with.
+    pub fn with_opts(mut self, opts: ProofOpts) -> Self {
+        self.core_opts = opts.sp1_prover_opts.core_opts;
+        self.recursion_opts = opts.sp1_prover_opts.recursion_opts;
+        self.timeout = opts.timeout;
+        self.job_dir = opts.job_dir;
+        self
     }
 
     /// Set the proof kind to the core mode. This is the default.
@@ -210,6 +320,24 @@ impl<'a> Prove<'a> {
         self
     }
 
+    /// Set a token that cancels the proof's generation.
+    ///
+    /// The local prover checks the token between shards and recursion layers and returns an
+    /// error once it has been cancelled. The gnark step of Plonk and Groth16 proofs cannot be
+    /// interrupted.
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.context_builder.cancellation_token(token);
+        self
+    }
+
+    /// Set a listener that is notified as the proof's generation moves through its phases.
+    ///
+    /// This is only used by the local prover.
+    pub fn progress_listener(mut self, listener: impl ProgressListener + 'static) -> Self {
+        self.context_builder.progress_listener(Arc::new(listener));
+        self
+    }
+
     /// Set the timeout for the proof's generation.
     ///
     /// This parameter is only used when the prover is run in network mode.
@@ -217,4 +345,209 @@ impl<'a> Prove<'a> {
         self.timeout = Some(timeout);
         self
     }
+
+    /// Persist the intermediate artifacts of the proof to the job directory `dir`, and resume
+    /// from the last stage completed there.
+    ///
+    /// The core proof, compressed proof, shrink proof and wrap proof are written to `dir` as they
+    /// are generated, together with a `manifest.json` recording the last completed stage. Running
+    /// the same proof again with the same directory, for example after the gnark step failed,
+    /// skips the completed stages. Proving fails if `dir` holds a job for another program, input
+    /// or circuit version.
+    ///
+    /// This is only used by the local prover.
+    pub fn resume(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.job_dir = Some(dir.into());
+        self
+    }
+}
+
+/// Builder to prepare and configure proving the execution of a program on many inputs.
+/// May be run with [Self::run].
+pub struct ProveBatch<'a> {
+    prover: &'a dyn Prover<DefaultProverComponents>,
+    kind: SP1ProofKind,
+    pk: &'a SP1ProvingKey,
+    stdins: Vec<SP1Stdin>,
+    core_opts: SP1CoreOpts,
+    recursion_opts: SP1CoreOpts,
+    timeout: Option<Duration>,
+}
+
+impl<'a> ProveBatch<'a> {
+    /// Prepare to prove the execution of the given program on each of the given inputs.
+    ///
+    /// Prefer using [ProverClient::prove_batch](super::ProverClient::prove_batch).
+    /// See there for more documentation.
+    pub fn new(
+        prover: &'a dyn Prover<DefaultProverComponents>,
+        pk: &'a SP1ProvingKey,
+        stdins: Vec<SP1Stdin>,
+    ) -> Self {
+        Self {
+            prover,
+            kind: Default::default(),
+            pk,
+            stdins,
+            core_opts: SP1CoreOpts::default(),
+            recursion_opts: SP1CoreOpts::recursion(),
+            timeout: None,
+        }
+    }
+
+    /// Prove the execution of the program on the inputs, consuming the built action `self`.
+    ///
+    /// Returns one result per input, in the order of the inputs. An input that fails to execute
+    /// or prove does not stop the others from being proven.
+    pub fn run(self) -> Vec<Result<SP1ProofWithPublicValues>> {
+        let Self { prover, kind, pk, stdins, core_opts, recursion_opts, timeout } = self;
+        let opts = ProofOpts {
+            sp1_prover_opts: SP1ProverOpts { core_opts, recursion_opts },
+            timeout,
+            job_dir: None,
+        };
+        prover.prove_batch(pk, stdins, opts, kind)
+    }
+
+    /// Set the prover options and timeout, overriding any previously configured values. The job
+    /// directory of `opts` is ignored, since it can only hold the job of a single input.
+    ///
+    /// [ProverClient::prove_batch](super::ProverClient::prove_batch) calls this with the defaults
+    /// the client was built with.
+    pub fn with_opts(mut self, opts: ProofOpts) -> Self {
+        self.core_opts = opts.sp1_prover_opts.core_opts;
+        self.recursion_opts = opts.sp1_prover_opts.recursion_opts;
+        self.timeout = opts.timeout;
+        self
+    }
+
+    /// Set the proof kind to the core mode. This is the default.
+    pub fn core(mut self) -> Self {
+        self.kind = SP1ProofKind::Core;
+        self
+    }
+
+    /// Set the proof kind to the compressed mode.
+    pub fn compressed(mut self) -> Self {
+        self.kind = SP1ProofKind::Compressed;
+        self
+    }
+
+    /// Set the proof mode to the plonk bn254 mode.
+    pub fn plonk(mut self) -> Self {
+        self.kind = SP1ProofKind::Plonk;
+        self
+    }
+
+    /// Set the proof mode to the groth16 bn254 mode.
+    pub fn groth16(mut self) -> Self {
+        self.kind = SP1ProofKind::Groth16;
+        self
+    }
+
+    /// Set the shard size for proving.
+    pub fn shard_size(mut self, value: usize) -> Self {
+        self.core_opts.shard_size = value;
+        self
+    }
+
+    /// Set the shard batch size for proving.
+    pub fn shard_batch_size(mut self, value: usize) -> Self {
+        self.core_opts.shard_batch_size = value;
+        self
+    }
+
+    /// Set the timeout for each proof's generation.
+    ///
+    /// This parameter is only used when the prover is run in network mode.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Builder to prepare and configure the aggregation of many compressed proofs into one proof.
+/// May be run with [Self::run].
+pub struct Aggregate<'a> {
+    prover: &'a dyn Prover<DefaultProverComponents>,
+    kind: SP1ProofKind,
+    proofs: &'a [(SP1ProofWithPublicValues, SP1VerifyingKey)],
+    core_opts: SP1CoreOpts,
+    recursion_opts: SP1CoreOpts,
+    timeout: Option<Duration>,
+}
+
+impl<'a> Aggregate<'a> {
+    /// Prepare to aggregate the given proofs with the built-in aggregation program.
+    ///
+    /// Prefer using [ProverClient::aggregate](super::ProverClient::aggregate).
+    /// See there for more documentation.
+    pub fn new(
+        prover: &'a dyn Prover<DefaultProverComponents>,
+        proofs: &'a [(SP1ProofWithPublicValues, SP1VerifyingKey)],
+    ) -> Self {
+        Self {
+            prover,
+            kind: SP1ProofKind::Compressed,
+            proofs,
+            core_opts: SP1CoreOpts::default(),
+            recursion_opts: SP1CoreOpts::recursion(),
+            timeout: None,
+        }
+    }
+
+    /// Aggregate the proofs, consuming the built action `self`.
+    ///
+    /// Returns the aggregate proof and the verifying key of the aggregation program.
+    pub fn run(self) -> Result<(SP1ProofWithPublicValues, SP1VerifyingKey)> {
+        let Self { prover, kind, proofs, core_opts, recursion_opts, timeout } = self;
+        let stdin = aggregation_stdin(proofs)?;
+        let (pk, vk) = prover.setup(AGGREGATION_ELF);
+        let opts = ProofOpts {
+            sp1_prover_opts: SP1ProverOpts { core_opts, recursion_opts },
+            timeout,
+            job_dir: None,
+        };
+        let proof = prover.prove(&pk, stdin, opts, Default::default(), kind)?;
+        Ok((proof, vk))
+    }
+
+    /// Set the prover options and timeout, overriding any previously configured values. The job
+    /// directory of `opts` is ignored.
+    ///
+    /// [ProverClient::aggregate](super::ProverClient::aggregate) calls this with the defaults the
+    /// client was built with.
+    pub fn with_opts(mut self, opts: ProofOpts) -> Self {
+        self.core_opts = opts.sp1_prover_opts.core_opts;
+        self.recursion_opts = opts.sp1_prover_opts.recursion_opts;
+        self.timeout = opts.timeout;
+        self
+    }
+
+    /// Set the proof kind of the aggregate to the compressed mode. This is the default, so that
+    /// aggregates can themselves be aggregated.
+    pub fn compressed(mut self) -> Self {
+        self.kind = SP1ProofKind::Compressed;
+        self
+    }
+
+    /// Set the proof mode of the aggregate to the plonk bn254 mode.
+    pub fn plonk(mut self) -> Self {
+        self.kind = SP1ProofKind::Plonk;
+        self
+    }
+
+    /// Set the proof mode of the aggregate to the groth16 bn254 mode.
+    pub fn groth16(mut self) -> Self {
+        self.kind = SP1ProofKind::Groth16;
+        self
+    }
+
+    /// Set the timeout for the aggregate proof's generation.
+    ///
+    /// This parameter is only used when the prover is run in network mode.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
 }
@@ -0,0 +1,214 @@
+//! Aggregation of many compressed proofs into one proof with a built-in program.
+//!
+//! The aggregation program, whose source is in `crates/sdk/programs/aggregation`, verifies every
+//! input proof with `verify_sp1_proof` and commits to its version and the list of
+//! `(vkey_hash, public_values)` pairs of the inputs, encoded as
+//!
+//! ```text
+//! version as u32 || n as u32 || vkey_hash[0] || ... || vkey_hash[n - 1] || len(public_values[0]) as u32 || public_values[0] || ...
+//! ```
+//!
+//! with big-endian lengths and each vkey hash as the little-endian bytes of its eight words.
+//! [AggregatedValues] decodes this commitment so that the inclusion of an inner proof can be
+//! checked on the host.
+//!
+//! [AGGREGATION_ELF] is built reproducibly from its source with
+//!
+//! ```bash
+//! cd crates/sdk/programs/aggregation && cargo prove build --docker --tag v3.4.0 --output-directory elf
+//! ```
+//!
+//! and CI checks that rebuilding it leaves the committed ELF unchanged.
+
+use anyhow::{bail, ensure, Result};
+use sp1_primitives::io::SP1PublicValues;
+use sp1_prover::HashableKey;
+
+use crate::{SP1Proof, SP1ProofKind, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey};
+
+/// The ELF of the built-in aggregation program.
+pub const AGGREGATION_ELF: &[u8] =
+    include_bytes!("../programs/aggregation/elf/riscv32im-succinct-zkvm-elf");
+
+/// The version of the aggregation program and of the format of its committed values.
+///
+/// The verifying key of the program also changes with the SP1 circuit version.
+pub const AGGREGATION_PROGRAM_VERSION: u32 = 1;
+
+/// Writes the inputs of the aggregation program for the given proofs to a new [SP1Stdin].
+///
+/// Returns an error if a proof is not compressed, since only compressed proofs can be verified
+/// inside the zkVM.
+pub fn aggregation_stdin(
+    proofs: &[(SP1ProofWithPublicValues, SP1VerifyingKey)],
+) -> Result<SP1Stdin> {
+    ensure!(!proofs.is_empty(), "no proofs to aggregate");
+
+    let mut stdin = SP1Stdin::new();
+    let vkeys = proofs.iter().map(|(_, vk)| vk.hash_u32()).collect::<Vec<_>>();
+    stdin.write::<Vec<[u32; 8]>>(&vkeys);
+    let public_values =
+        proofs.iter().map(|(proof, _)| proof.public_values.to_vec()).collect::<Vec<_>>();
+    stdin.write::<Vec<Vec<u8>>>(&public_values);
+
+    // The proofs are not read by the program, but witnessed by the prover when it verifies the
+    // deferred proofs.
+    for (i, (proof, vk)) in proofs.iter().enumerate() {
+        let SP1Proof::Compressed(reduce_proof) = &proof.proof else {
+            let kind = SP1ProofKind::from(&proof.proof);
+            bail!("proof {i} is a {kind:?} proof, but only compressed proofs can be aggregated");
+        };
+        stdin.write_proof(*reduce_proof.clone(), vk.vk.clone());
+    }
+    Ok(stdin)
+}
+
+/// The values committed by the aggregation program: the vkey hash and public values of every
+/// aggregated proof, in the order they were aggregated in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatedValues {
+    /// The version of the aggregation program, see [AGGREGATION_PROGRAM_VERSION].
+    pub version: u32,
+    /// The vkey hashes and public values of the aggregated proofs.
+    pub proofs: Vec<([u32; 8], Vec<u8>)>,
+}
+
+impl AggregatedValues {
+    /// Decodes the public values of an aggregate proof.
+    ///
+    /// Returns an error if the proof was generated by a different version of the aggregation
+    /// program.
+    pub fn decode(public_values: &SP1PublicValues) -> Result<Self> {
+        let mut bytes = public_values.as_slice();
+        let version = read_u32(&mut bytes)?;
+        ensure!(
+            version == AGGREGATION_PROGRAM_VERSION,
+            "unsupported aggregation program version {version}, expected \
+             {AGGREGATION_PROGRAM_VERSION}"
+        );
+        let n = read_u32(&mut bytes)? as usize;
+        ensure!(bytes.len() >= n * 32, "truncated vkey hashes");
+        let (vkeys, mut bytes) = bytes.split_at(n * 32);
+
+        let mut proofs = Vec::with_capacity(n);
+        for vkey in vkeys.chunks_exact(32) {
+            let mut words = [0u32; 8];
+            for (word, chunk) in words.iter_mut().zip(vkey.chunks_exact(4)) {
+                *word = u32::from_le_bytes(chunk.try_into().unwrap());
+            }
+            let len = read_u32(&mut bytes)? as usize;
+            ensure!(bytes.len() >= len, "truncated public values");
+            let (values, rest) = bytes.split_at(len);
+            proofs.push((words, values.to_vec()));
+            bytes = rest;
+        }
+        ensure!(bytes.is_empty(), "trailing bytes after the aggregated values");
+        Ok(Self { version, proofs })
+    }
+
+    /// Returns whether a proof of the program with verifying key `vk` and public values
+    /// `public_values` was aggregated.
+    pub fn contains(&self, vk: &SP1VerifyingKey, public_values: &SP1PublicValues) -> bool {
+        let vkey_hash = vk.hash_u32();
+        self.proofs.iter().any(|(hash, values)| {
+            *hash == vkey_hash && values.as_slice() == public_values.as_slice()
+        })
+    }
+}
+
+/// Returns whether `proof`, a proof of the program with verifying key `vk`, was aggregated into
+/// `aggregate`.
+///
+/// This only checks the committed values of `aggregate`. The aggregate proof itself must be
+/// verified against the verifying key of [AGGREGATION_ELF].
+pub fn is_aggregated(
+    aggregate: &SP1ProofWithPublicValues,
+    proof: &SP1ProofWithPublicValues,
+    vk: &SP1VerifyingKey,
+) -> Result<bool> {
+    Ok(AggregatedValues::decode(&aggregate.public_values)?.contains(vk, &proof.public_values))
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Result<u32> {
+    ensure!(bytes.len() >= 4, "truncated length");
+    let (value, rest) = bytes.split_at(4);
+    *bytes = rest;
+    Ok(u32::from_be_bytes(value.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_primitives::io::SP1PublicValues;
+
+    use super::{is_aggregated, AggregatedValues, AGGREGATION_PROGRAM_VERSION};
+    use crate::{utils, ProverClient, SP1Stdin};
+
+    const FIBONACCI_ELF: &[u8] =
+        include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+
+    #[test]
+    fn test_decode_aggregated_values() {
+        let vkey = [1u32, 2, 3, 4, 5, 6, 7, 8];
+        let mut bytes = AGGREGATION_PROGRAM_VERSION.to_be_bytes().to_vec();
+        bytes.extend(2u32.to_be_bytes());
+        for _ in 0..2 {
+            bytes.extend(vkey.iter().flat_map(|w| w.to_le_bytes()));
+        }
+        bytes.extend(3u32.to_be_bytes());
+        bytes.extend([1, 2, 3]);
+        bytes.extend(0u32.to_be_bytes());
+
+        let values = AggregatedValues::decode(&SP1PublicValues::from(&bytes)).unwrap();
+        assert_eq!(values.version, AGGREGATION_PROGRAM_VERSION);
+        assert_eq!(values.proofs, vec![(vkey, vec![1, 2, 3]), (vkey, vec![])]);
+
+        let mut other_version = bytes.clone();
+        other_version[..4].copy_from_slice(&(AGGREGATION_PROGRAM_VERSION + 1).to_be_bytes());
+        assert!(AggregatedValues::decode(&SP1PublicValues::from(&other_version)).is_err());
+
+        bytes.push(0);
+        assert!(AggregatedValues::decode(&SP1PublicValues::from(&bytes)).is_err());
+        bytes.truncate(14);
+        assert!(AggregatedValues::decode(&SP1PublicValues::from(&bytes)).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_mock() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let (pk, vk) = client.setup(FIBONACCI_ELF);
+        let proofs = [10usize, 20]
+            .map(|n| {
+                let mut stdin = SP1Stdin::new();
+                stdin.write(&n);
+                (client.prove(&pk, stdin).compressed().run().unwrap(), vk.clone())
+            })
+            .to_vec();
+
+        let (aggregate, _) = client.aggregate(&proofs).run().unwrap();
+        for (proof, vk) in &proofs {
+            assert!(is_aggregated(&aggregate, proof, vk).unwrap());
+        }
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&30usize);
+        let other = client.prove(&pk, stdin).compressed().run().unwrap();
+        assert!(!is_aggregated(&aggregate, &other, &vk).unwrap());
+    }
+
+    #[test]
+    fn test_aggregate_rejects_core_proofs() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let (pk, vk) = client.setup(FIBONACCI_ELF);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove(&pk, stdin).run().unwrap();
+
+        assert!(client.aggregate(&[]).run().is_err());
+        let Err(err) = client.aggregate(&[(proof, vk)]).run() else {
+            panic!("a core proof was aggregated");
+        };
+        assert!(err.to_string().contains("only compressed proofs can be aggregated"));
+    }
+}
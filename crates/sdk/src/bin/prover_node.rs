@@ -0,0 +1,63 @@
+//! Fulfills proof requests of the prover network, see `sp1_sdk::network::node`.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Parser;
+use sp1_sdk::{
+    network::{
+        client::NetworkClient,
+        node::{ProverNode, ProverNodeConfig, DEFAULT_MAX_CYCLES},
+    },
+    utils::setup_logger,
+    CpuProver, MockProver,
+};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The RPC endpoint of the prover network.
+    #[clap(long, env = "PROVER_NETWORK_RPC", default_value = "https://rpc.succinct.xyz/")]
+    rpc_url: String,
+    /// The private key the node claims and fulfills requests with.
+    #[clap(long, env = "SP1_PRIVATE_KEY", hide_env_values = true)]
+    private_key: String,
+    /// The maximum number of requests proven at the same time.
+    #[clap(long, default_value_t = 1)]
+    max_concurrent_proofs: usize,
+    /// The interval between two polls for requested proofs, in seconds.
+    #[clap(long, default_value_t = 5)]
+    poll_interval_secs: u64,
+    /// The maximum number of cycles a request may execute for.
+    #[clap(long, default_value_t = DEFAULT_MAX_CYCLES)]
+    max_cycles: u64,
+    /// Fulfills requests with mock proofs, for testing against a local network.
+    #[clap(long)]
+    mock: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    setup_logger();
+    let args = Args::parse();
+
+    let client = NetworkClient::try_new(&args.private_key, &args.rpc_url)?;
+    let config = ProverNodeConfig {
+        poll_interval: Duration::from_secs(args.poll_interval_secs),
+        max_concurrent_proofs: args.max_concurrent_proofs,
+        max_cycles: args.max_cycles,
+        ..Default::default()
+    };
+    let node = if args.mock {
+        ProverNode::new(client, MockProver::new(), config)
+    } else {
+        ProverNode::new(client, CpuProver::new(), config)
+    };
+
+    tracing::info!("fulfilling proof requests of {}", args.rpc_url);
+    node.run(async {
+        let _ = tokio::signal::ctrl_c().await;
+        tracing::info!("shutting down after the claimed requests");
+    })
+    .await
+}
@@ -0,0 +1,385 @@
+//! Explicit configuration for [ProverClient].
+//!
+//! A [ProverClientConfig] describes which prover to use and how to set it up. It can be built in
+//! code, read from a TOML file, or read from the `SP1_PROVER`, `SP1_PRIVATE_KEY`,
+//! `PROVER_NETWORK_RPC` and `SP1_PROVER_ENDPOINT` environment variables, and is turned into a
+//! client with [ProverClientBuilder::build].
+//!
+//! ```toml
+//! prover = "network"
+//! circuits_dir = "/var/lib/sp1/circuits"
+//! timeout_secs = 3600
+//!
+//! [network]
+//! rpc_url = "https://rpc.succinct.xyz/"
+//! private_key = "0x..."
+//! ```
+
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use sp1_stark::SP1ProverOpts;
+use thiserror::Error;
+
+use crate::{
+    provers::{
+        pool::{set_max_async_jobs, DEFAULT_MAX_ASYNC_JOBS},
+        ProofOpts,
+    },
+    CpuProver, MockProver, Prover, ProverClient,
+};
+use sp1_prover::components::DefaultProverComponents;
+
+/// The kind of prover used by a [ProverClient].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProverKind {
+    /// Generates proofs locally with [CpuProver], or with [crate::CudaProver] if the `cuda`
+    /// feature is enabled.
+    #[default]
+    Local,
+    /// Generates mock proofs with [MockProver].
+    Mock,
+    /// Requests proofs from the prover network.
+    Network,
+    /// Requests proofs from a self-hosted proving service with [crate::RemoteProver].
+    Remote,
+}
+
+impl FromStr for ProverKind {
+    type Err = ProverClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "mock" => Ok(Self::Mock),
+            "network" => Ok(Self::Network),
+            "remote" => Ok(Self::Remote),
+            _ => Err(ProverClientError::InvalidProverKind(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ProverKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local"),
+            Self::Mock => write!(f, "mock"),
+            Self::Network => write!(f, "network"),
+            Self::Remote => write!(f, "remote"),
+        }
+    }
+}
+
+/// Configuration of the prover network.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// The RPC endpoint of the prover network. Defaults to the public prover network.
+    pub rpc_url: Option<String>,
+    /// The private key used to authenticate requests to the prover network.
+    pub private_key: Option<String>,
+}
+
+/// Configuration of a self-hosted proving service.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RemoteConfig {
+    /// The endpoint of the proving service. Defaults to `http://localhost:3000`.
+    pub endpoint: Option<String>,
+}
+
+/// Configuration of a [ProverClient].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProverClientConfig {
+    /// The kind of prover to use.
+    pub prover: ProverKind,
+    /// The default options used by [ProverClient::prove]. Defaults to [SP1ProverOpts::default].
+    ///
+    /// The local prover is built with the FRI parameters of these options.
+    pub prover_opts: Option<SP1ProverOpts>,
+    /// The directory under which the Plonk and Groth16 circuit artifacts are installed. Defaults
+    /// to `~/.sp1/circuits`.
+    pub circuits_dir: Option<PathBuf>,
+    /// The default timeout for proof generation, in seconds.
+    ///
+    /// This is only used when the prover is run in network mode.
+    pub timeout_secs: Option<u64>,
+    /// The maximum number of proofs and executions the local provers run at once for the async
+    /// methods, across the process. Defaults to [DEFAULT_MAX_ASYNC_JOBS].
+    ///
+    /// See [set_max_async_jobs].
+    pub max_async_jobs: Option<usize>,
+    /// The prover network configuration, used when `prover` is `network`.
+    pub network: NetworkConfig,
+    /// The proving service configuration, used when `prover` is `remote`.
+    pub remote: RemoteConfig,
+}
+
+impl ProverClientConfig {
+    /// Reads the configuration from the `SP1_PROVER`, `SP1_PRIVATE_KEY`, `PROVER_NETWORK_RPC` and
+    /// `SP1_PROVER_ENDPOINT` environment variables.
+    pub fn from_env() -> Result<Self, ProverClientError> {
+        let prover = match env::var("SP1_PROVER") {
+            Ok(value) => value.parse()?,
+            Err(_) => ProverKind::default(),
+        };
+        Ok(Self {
+            prover,
+            network: NetworkConfig {
+                rpc_url: env::var("PROVER_NETWORK_RPC").ok(),
+                private_key: env::var("SP1_PRIVATE_KEY").ok(),
+            },
+            remote: RemoteConfig { endpoint: env::var("SP1_PROVER_ENDPOINT").ok() },
+            ..Default::default()
+        })
+    }
+
+    /// Parses the configuration from a TOML string.
+    pub fn from_toml_str(s: &str) -> Result<Self, ProverClientError> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// Reads the configuration from a TOML file.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, ProverClientError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|source| ProverClientError::Io { path: path.to_path_buf(), source })?;
+        Self::from_toml_str(&contents)
+    }
+}
+
+/// An error that occurred while building a [ProverClient].
+#[derive(Error, Debug)]
+pub enum ProverClientError {
+    #[error("invalid prover kind '{0}': expected 'local', 'mock', 'network', or 'remote'")]
+    InvalidProverKind(String),
+    #[error("failed to read config file {path}: {source}")]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("failed to parse config: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("a private key is required for the network prover")]
+    MissingPrivateKey,
+    #[error("the {0} feature is not enabled")]
+    FeatureNotEnabled(&'static str),
+    #[error("failed to create the network prover: {0}")]
+    Network(anyhow::Error),
+    #[error("failed to connect to the remote prover: {0}")]
+    Remote(anyhow::Error),
+    #[error("failed to set the maximum number of async jobs: {0}")]
+    MaxAsyncJobs(anyhow::Error),
+}
+
+/// A builder for [ProverClient].
+///
+/// ### Examples
+///
+/// ```no_run
+/// use sp1_sdk::{ProverClient, ProverKind};
+///
+/// let client = ProverClient::builder()
+///     .prover(ProverKind::Local)
+///     .circuits_dir("/var/lib/sp1/circuits")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProverClientBuilder {
+    config: ProverClientConfig,
+}
+
+impl ProverClientBuilder {
+    /// Creates a new builder with the default configuration, which uses the local prover.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new builder from an existing configuration.
+    pub fn from_config(config: ProverClientConfig) -> Self {
+        Self { config }
+    }
+
+    /// Sets the kind of prover to use.
+    pub fn prover(mut self, prover: ProverKind) -> Self {
+        self.config.prover = prover;
+        self
+    }
+
+    /// Sets the default options used by [ProverClient::prove].
+    pub fn prover_opts(mut self, opts: SP1ProverOpts) -> Self {
+        self.config.prover_opts = Some(opts);
+        self
+    }
+
+    /// Sets the directory under which the Plonk and Groth16 circuit artifacts are installed.
+    pub fn circuits_dir(mut self, circuits_dir: impl Into<PathBuf>) -> Self {
+        self.config.circuits_dir = Some(circuits_dir.into());
+        self
+    }
+
+    /// Sets the default timeout for proof generation, with a resolution of one second.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout_secs = Some(timeout.as_secs());
+        self
+    }
+
+    /// Sets the maximum number of proofs and executions the local provers run at once for the
+    /// async methods, across the process.
+    pub fn max_async_jobs(mut self, max_jobs: usize) -> Self {
+        self.config.max_async_jobs = Some(max_jobs);
+        self
+    }
+
+    /// Sets the RPC endpoint of the prover network.
+    pub fn rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.config.network.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    /// Sets the private key used to authenticate requests to the prover network.
+    pub fn private_key(mut self, private_key: impl Into<String>) -> Self {
+        self.config.network.private_key = Some(private_key.into());
+        self
+    }
+
+    /// Sets the endpoint of the proving service used by the remote prover.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.config.remote.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Builds the [ProverClient].
+    pub fn build(self) -> Result<ProverClient, ProverClientError> {
+        let ProverClientConfig {
+            prover,
+            prover_opts,
+            circuits_dir,
+            timeout_secs,
+            max_async_jobs,
+            network,
+            remote,
+        } = self.config;
+        if let Some(max_jobs) = max_async_jobs {
+            set_max_async_jobs(max_jobs).map_err(ProverClientError::MaxAsyncJobs)?;
+        }
+        let opts = ProofOpts {
+            sp1_prover_opts: prover_opts.unwrap_or_default(),
+            timeout: timeout_secs.map(Duration::from_secs),
+            job_dir: None,
+        };
+        let prover: Box<dyn Prover<DefaultProverComponents>> = match prover {
+            ProverKind::Mock => Box::new(MockProver::new()),
+            ProverKind::Local => {
+                #[cfg(debug_assertions)]
+                println!("Warning: Local prover in dev mode is not recommended. Proof generation may be slow.");
+                cfg_if::cfg_if! {
+                    if #[cfg(feature = "cuda")] {
+                        let _ = circuits_dir;
+                        Box::new(crate::CudaProver::new(sp1_prover::SP1Prover::with_opts(
+                            opts.sp1_prover_opts,
+                        )))
+                    } else {
+                        let mut cpu_prover = CpuProver::from_prover(
+                            sp1_prover::SP1Prover::with_opts(opts.sp1_prover_opts),
+                        );
+                        if let Some(circuits_dir) = circuits_dir {
+                            cpu_prover = cpu_prover.with_circuits_dir(circuits_dir);
+                        }
+                        Box::new(cpu_prover)
+                    }
+                }
+            }
+            ProverKind::Network => {
+                cfg_if::cfg_if! {
+                    if #[cfg(any(feature = "network", feature = "network-v2"))] {
+                        #[cfg(feature = "network-v2")]
+                        use crate::network_v2::{client::NetworkClient, prover::NetworkProver};
+                        #[cfg(not(feature = "network-v2"))]
+                        use crate::network::{client::NetworkClient, prover::NetworkProver};
+
+                        let private_key =
+                            network.private_key.ok_or(ProverClientError::MissingPrivateKey)?;
+                        let rpc_url = network.rpc_url.unwrap_or_else(NetworkClient::rpc_url);
+                        let mut network_prover = NetworkProver::try_new(&private_key, &rpc_url)
+                            .map_err(ProverClientError::Network)?;
+                        if let Some(circuits_dir) = circuits_dir {
+                            network_prover = network_prover.with_circuits_dir(circuits_dir);
+                        }
+                        Box::new(network_prover)
+                    } else {
+                        let _ = network;
+                        return Err(ProverClientError::FeatureNotEnabled("network"));
+                    }
+                }
+            }
+            ProverKind::Remote => {
+                cfg_if::cfg_if! {
+                    if #[cfg(feature = "remote")] {
+                        let endpoint =
+                            remote.endpoint.as_deref().unwrap_or("http://localhost:3000");
+                        Box::new(
+                            crate::RemoteProver::new(endpoint).map_err(ProverClientError::Remote)?,
+                        )
+                    } else {
+                        let _ = remote;
+                        return Err(ProverClientError::FeatureNotEnabled("remote"));
+                    }
+                }
+            }
+        };
+        Ok(ProverClient { prover, opts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_from_toml() {
+        let config = ProverClientConfig::from_toml_str(
+            r#"
+            prover = "network"
+            circuits_dir = "/tmp/circuits"
+            timeout_secs = 60
+            max_async_jobs = 2
+
+            [network]
+            rpc_url = "http://127.0.0.1:50051"
+            private_key = "0x01"
+
+            [remote]
+            endpoint = "http://10.0.0.2:3000"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.prover, ProverKind::Network);
+        assert_eq!(config.circuits_dir, Some(PathBuf::from("/tmp/circuits")));
+        assert_eq!(config.timeout_secs, Some(60));
+        assert_eq!(config.max_async_jobs, Some(2));
+        assert_eq!(config.network.rpc_url.as_deref(), Some("http://127.0.0.1:50051"));
+        assert_eq!(config.remote.endpoint.as_deref(), Some("http://10.0.0.2:3000"));
+        assert_eq!(config.prover_opts, None);
+
+        assert_eq!(ProverClientConfig::from_toml_str("").unwrap(), ProverClientConfig::default());
+        assert!(ProverClientConfig::from_toml_str("prover = \"gpu\"").is_err());
+        assert!(ProverClientConfig::from_toml_str("shard_size = 1").is_err());
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let result = ProverClientBuilder::new().prover(ProverKind::Network).build();
+        assert!(result.is_err());
+        let result = ProverClientBuilder::new().prover(ProverKind::Mock).max_async_jobs(0).build();
+        assert!(matches!(result, Err(ProverClientError::MaxAsyncJobs(_))));
+        assert!("gpu".parse::<ProverKind>().is_err());
+        assert_eq!("Mock".parse::<ProverKind>().unwrap(), ProverKind::Mock);
+        assert_eq!("remote".parse::<ProverKind>().unwrap(), ProverKind::Remote);
+    }
+}
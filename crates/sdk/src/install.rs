@@ -1,5 +1,15 @@
 use cfg_if::cfg_if;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use sp1_recursion_gnark_ffi::{groth16_bn254::Groth16Bn254Prover, plonk_bn254::PlonkBn254Prover};
+use sp1_verifier::{GROTH16_VK_BYTES, PLONK_VK_BYTES};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use thiserror::Error;
 
 #[cfg(any(feature = "network", feature = "network-v2"))]
 use {
@@ -7,7 +17,7 @@ use {
     futures::StreamExt,
     indicatif::{ProgressBar, ProgressStyle},
     reqwest::Client,
-    std::{cmp::min, io::Write, process::Command},
+    std::{cmp::min, io::Write},
 };
 
 use crate::SP1_CIRCUIT_VERSION;
@@ -15,25 +25,69 @@ use crate::SP1_CIRCUIT_VERSION;
 /// The base URL for the S3 bucket containing the circuit artifacts.
 pub const CIRCUIT_ARTIFACTS_URL_BASE: &str = "https://sp1-circuits.s3-us-east-2.amazonaws.com";
 
+/// The expected SHA-256 checksums of the circuit artifacts, keyed by circuit version, artifacts
+/// type and file name.
+const CIRCUIT_ARTIFACTS_MANIFEST: &str = include_str!("../circuit-artifacts.toml");
+
+#[derive(Error, Debug)]
+pub enum CircuitArtifactsError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("unsupported artifacts type: {0}")]
+    UnsupportedType(String),
+    #[error("no {artifacts_type} checksums for circuit version {version} in the manifest")]
+    UnknownVersion { artifacts_type: String, version: String },
+    #[error("the manifest does not pin {file} of the {artifacts_type} artifacts for circuit version {version}")]
+    UnpinnedFile { artifacts_type: String, version: String, file: String },
+    #[error("missing circuit artifact {0}")]
+    MissingFile(PathBuf),
+    #[error("checksum mismatch for {path}: expected {expected}, found {found}")]
+    ChecksumMismatch { path: PathBuf, expected: String, found: String },
+    #[error("vkey hash mismatch: expected {expected}, found {found}")]
+    VkeyHashMismatch { expected: String, found: String },
+    #[error("failed to extract {0}")]
+    Extract(PathBuf),
+}
+
+/// The default directory under which circuit artifacts are stored, `~/.sp1/circuits`.
+pub fn default_circuits_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".sp1").join("circuits")
+}
+
 /// The directory where the groth16 circuit artifacts will be stored.
 pub fn groth16_circuit_artifacts_dir() -> PathBuf {
-    dirs::home_dir().unwrap().join(".sp1").join("circuits/groth16").join(SP1_CIRCUIT_VERSION)
+    default_circuits_dir().join("groth16").join(SP1_CIRCUIT_VERSION)
 }
 
 /// The directory where the plonk circuit artifacts will be stored.
 pub fn plonk_circuit_artifacts_dir() -> PathBuf {
-    dirs::home_dir().unwrap().join(".sp1").join("circuits/plonk").join(SP1_CIRCUIT_VERSION)
+    default_circuits_dir().join("plonk").join(SP1_CIRCUIT_VERSION)
+}
+
+/// The directory of the Groth16 circuit artifacts of the arkworks backend under `circuits_dir`.
+///
+/// There are no released artifacts for the arkworks backend, so they are built locally by the
+/// first Groth16 proof, and both proving and verifying read them from this directory.
+pub fn ark_groth16_circuit_artifacts_dir(circuits_dir: &Path) -> PathBuf {
+    circuits_dir.join("groth16-ark").join(SP1_CIRCUIT_VERSION)
 }
 
 /// Tries to install the groth16 circuit artifacts if they are not already installed.
 pub fn try_install_circuit_artifacts(artifacts_type: &str) -> PathBuf {
-    let build_dir = if artifacts_type == "groth16" {
-        groth16_circuit_artifacts_dir()
-    } else if artifacts_type == "plonk" {
-        plonk_circuit_artifacts_dir()
-    } else {
+    try_install_circuit_artifacts_in(&default_circuits_dir(), artifacts_type)
+}
+
+/// Tries to install the circuit artifacts under `circuits_dir` if they are not already installed.
+///
+/// The artifacts are stored in `<circuits_dir>/<artifacts_type>/<SP1_CIRCUIT_VERSION>`.
+///
+/// The artifacts are verified against the embedded checksum manifest before being returned, and
+/// this panics if they don't match.
+pub fn try_install_circuit_artifacts_in(circuits_dir: &Path, artifacts_type: &str) -> PathBuf {
+    if artifacts_type != "groth16" && artifacts_type != "plonk" {
         unimplemented!("unsupported artifacts type: {}", artifacts_type);
-    };
+    }
+    let build_dir = circuits_dir.join(artifacts_type).join(SP1_CIRCUIT_VERSION);
 
     if build_dir.exists() {
         println!(
@@ -54,18 +108,153 @@ pub fn try_install_circuit_artifacts(artifacts_type: &str) -> PathBuf {
             }
         }
     }
+
+    if let Err(err) = verify_circuit_artifacts(&build_dir, artifacts_type) {
+        panic!(
+            "[sp1] the {} circuit artifacts at {} failed verification: {}. delete the directory to re-download them, or import them with `cargo prove circuits import`",
+            artifacts_type,
+            build_dir.display(),
+            err
+        );
+    }
     build_dir
 }
 
+/// Returns the expected SHA-256 checksums of the circuit artifacts of the given type for
+/// [SP1_CIRCUIT_VERSION], keyed by file name.
+///
+/// The verifying key is always pinned, since the vkey hash of the proofs is derived from it. Any
+/// other file listed in the manifest is pinned as well.
+pub fn expected_circuit_artifacts_checksums(
+    artifacts_type: &str,
+) -> Result<BTreeMap<String, String>, CircuitArtifactsError> {
+    if artifacts_type != "groth16" && artifacts_type != "plonk" {
+        return Err(CircuitArtifactsError::UnsupportedType(artifacts_type.to_string()));
+    }
+    let mut manifest: BTreeMap<String, BTreeMap<String, BTreeMap<String, String>>> =
+        toml::from_str(CIRCUIT_ARTIFACTS_MANIFEST).expect("invalid circuit artifacts manifest");
+
+    let checksums = manifest
+        .remove(SP1_CIRCUIT_VERSION)
+        .and_then(|mut types| types.remove(artifacts_type))
+        .ok_or_else(|| CircuitArtifactsError::UnknownVersion {
+            artifacts_type: artifacts_type.to_string(),
+            version: SP1_CIRCUIT_VERSION.to_string(),
+        })?;
+    let vk_file = format!("{}_vk.bin", artifacts_type);
+    if !checksums.contains_key(&vk_file) {
+        return Err(CircuitArtifactsError::UnpinnedFile {
+            artifacts_type: artifacts_type.to_string(),
+            version: SP1_CIRCUIT_VERSION.to_string(),
+            file: vk_file,
+        });
+    }
+    Ok(checksums)
+}
+
+/// Computes the SHA-256 checksums of the files in `dir`, keyed by file name.
+///
+/// The output has the format of an entry of the embedded checksum manifest.
+pub fn circuit_artifacts_checksums(dir: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut checksums = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            checksums.insert(name, file_checksum(&entry.path())?);
+        }
+    }
+    Ok(checksums)
+}
+
+/// Verifies the circuit artifacts in `build_dir` against the embedded checksum manifest, and
+/// checks that the vkey hash of the prover matches the verifying key embedded in `sp1-verifier`.
+pub fn verify_circuit_artifacts(
+    build_dir: &Path,
+    artifacts_type: &str,
+) -> Result<(), CircuitArtifactsError> {
+    let expected = expected_circuit_artifacts_checksums(artifacts_type)?;
+    for (file, checksum) in expected.iter() {
+        let path = build_dir.join(file);
+        if !path.is_file() {
+            return Err(CircuitArtifactsError::MissingFile(path));
+        }
+        let found = file_checksum(&path)?;
+        if !found.eq_ignore_ascii_case(checksum) {
+            return Err(CircuitArtifactsError::ChecksumMismatch {
+                path,
+                expected: checksum.clone(),
+                found,
+            });
+        }
+    }
+
+    let (vkey_hash, verifier_vk): (_, &[u8]) = match artifacts_type {
+        "groth16" => (Groth16Bn254Prover::get_vkey_hash(build_dir), *GROTH16_VK_BYTES),
+        _ => (PlonkBn254Prover::get_vkey_hash(build_dir), *PLONK_VK_BYTES),
+    };
+    let expected = hex::encode(Sha256::digest(verifier_vk));
+    let found = hex::encode(vkey_hash);
+    if found != expected {
+        return Err(CircuitArtifactsError::VkeyHashMismatch { expected, found });
+    }
+    Ok(())
+}
+
+/// Imports the circuit artifacts from a local tarball, without network access.
+///
+/// The tarball is extracted to a staging directory and verified before replacing
+/// `<circuits_dir>/<artifacts_type>/<SP1_CIRCUIT_VERSION>`, which is returned.
+pub fn import_circuit_artifacts(
+    tarball: &Path,
+    circuits_dir: &Path,
+    artifacts_type: &str,
+) -> Result<PathBuf, CircuitArtifactsError> {
+    if artifacts_type != "groth16" && artifacts_type != "plonk" {
+        return Err(CircuitArtifactsError::UnsupportedType(artifacts_type.to_string()));
+    }
+    let build_dir = circuits_dir.join(artifacts_type).join(SP1_CIRCUIT_VERSION);
+    install_circuit_artifacts_from(tarball, &build_dir, artifacts_type)?;
+    Ok(build_dir)
+}
+
+/// Extracts the tarball next to `build_dir`, verifies it, then moves it into place.
+fn install_circuit_artifacts_from(
+    tarball: &Path,
+    build_dir: &Path,
+    artifacts_type: &str,
+) -> Result<(), CircuitArtifactsError> {
+    let parent = build_dir.parent().expect("build directory has no parent");
+    fs::create_dir_all(parent)?;
+    let staging_dir = tempfile::tempdir_in(parent)?;
+
+    let status = Command::new("tar")
+        .args(["-xzf", tarball.to_str().unwrap(), "-C", staging_dir.path().to_str().unwrap()])
+        .status()?;
+    if !status.success() {
+        return Err(CircuitArtifactsError::Extract(tarball.to_path_buf()));
+    }
+    verify_circuit_artifacts(staging_dir.path(), artifacts_type)?;
+
+    if build_dir.exists() {
+        fs::remove_dir_all(build_dir)?;
+    }
+    fs::rename(staging_dir.into_path(), build_dir)?;
+    Ok(())
+}
+
+fn file_checksum(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
 /// Install the latest circuit artifacts.
 ///
-/// This function will download the latest circuit artifacts from the S3 bucket and extract them
-/// to the directory specified by [groth16_bn254_artifacts_dir()].
+/// This function will download the latest circuit artifacts from the S3 bucket, verify them and
+/// extract them to `build_dir`.
 #[cfg(any(feature = "network", feature = "network-v2"))]
 pub fn install_circuit_artifacts(build_dir: PathBuf, artifacts_type: &str) {
-    // Create the build directory.
-    std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
-
     // Download the artifacts.
     let download_url =
         format!("{}/{}-{}.tar.gz", CIRCUIT_ARTIFACTS_URL_BASE, SP1_CIRCUIT_VERSION, artifacts_type);
@@ -75,17 +264,9 @@ pub fn install_circuit_artifacts(build_dir: PathBuf, artifacts_type: &str) {
     block_on(download_file(&client, &download_url, &mut artifacts_tar_gz_file))
         .expect("failed to download file");
 
-    // Extract the tarball to the build directory.
-    let mut res = Command::new("tar")
-        .args([
-            "-Pxzf",
-            artifacts_tar_gz_file.path().to_str().unwrap(),
-            "-C",
-            build_dir.to_str().unwrap(),
-        ])
-        .spawn()
-        .expect("failed to extract tarball");
-    res.wait().unwrap();
+    // Verify and extract the tarball to the build directory.
+    install_circuit_artifacts_from(artifacts_tar_gz_file.path(), &build_dir, artifacts_type)
+        .unwrap_or_else(|err| panic!("failed to install {}: {}", download_url, err));
 
     println!("[sp1] downloaded {} to {:?}", download_url, build_dir.to_str().unwrap(),);
 }
@@ -120,3 +301,41 @@ pub async fn download_file(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_artifacts_manifest() {
+        for artifacts_type in ["groth16", "plonk"] {
+            let checksums = expected_circuit_artifacts_checksums(artifacts_type).unwrap();
+            assert!(checksums.values().all(|checksum| checksum.len() == 64));
+        }
+
+        // The pinned verifying keys are the ones the proofs are verified against.
+        let groth16 = expected_circuit_artifacts_checksums("groth16").unwrap();
+        assert_eq!(groth16["groth16_vk.bin"], hex::encode(Sha256::digest(*GROTH16_VK_BYTES)));
+        let plonk = expected_circuit_artifacts_checksums("plonk").unwrap();
+        assert_eq!(plonk["plonk_vk.bin"], hex::encode(Sha256::digest(*PLONK_VK_BYTES)));
+        assert!(matches!(
+            expected_circuit_artifacts_checksums("stark"),
+            Err(CircuitArtifactsError::UnsupportedType(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_circuit_artifacts_rejects_tampered_vk() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            verify_circuit_artifacts(dir.path(), "groth16"),
+            Err(CircuitArtifactsError::MissingFile(_))
+        ));
+
+        fs::write(dir.path().join("groth16_vk.bin"), b"not the vk").unwrap();
+        assert!(matches!(
+            verify_circuit_artifacts(dir.path(), "groth16"),
+            Err(CircuitArtifactsError::ChecksumMismatch { .. })
+        ));
+    }
+}
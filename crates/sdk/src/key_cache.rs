@@ -0,0 +1,210 @@
+//! An on-disk cache of proving and verifying keys.
+//!
+//! Computing the keys of a program commits to its preprocessed traces, which is slow for large
+//! programs. [KeyCache] stores the keys under `<dir>/<SP1_CIRCUIT_VERSION>/<elf sha256>.bin`, so
+//! that they are only computed once per program and circuit version.
+//!
+//! Each entry starts with the SHA-256 digest of its contents, which is checked when the entry is
+//! loaded. Entries that are corrupted, or that were written for a different program, circuit
+//! version, shape configuration or FRI parameters, are recomputed and overwritten.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_prover::{components::DefaultProverComponents, SP1ProvingKey, SP1VerifyingKey};
+use sp1_stark::{MachineProver, StarkGenericConfig};
+use thiserror::Error;
+
+use crate::{Prover, SP1_CIRCUIT_VERSION};
+
+/// An error that occurred while reading or writing a [KeyCache] entry.
+#[derive(Error, Debug)]
+pub enum KeyCacheError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] bincode::Error),
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+    #[error("entry was written for circuit version {0}")]
+    VersionMismatch(String),
+    #[error("entry was written for a different program")]
+    ElfMismatch,
+}
+
+/// The contents of a cache entry.
+#[derive(Serialize, Deserialize)]
+struct CachedKeys {
+    version: String,
+    fixed_core_shapes: bool,
+    pk: SP1ProvingKey,
+}
+
+/// An on-disk cache of proving and verifying keys, keyed by ELF digest and circuit version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCache {
+    dir: PathBuf,
+}
+
+impl Default for KeyCache {
+    fn default() -> Self {
+        Self::new(Self::default_dir())
+    }
+}
+
+impl KeyCache {
+    /// Creates a cache that stores its entries under `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The default cache directory, `~/.sp1/keys`.
+    pub fn default_dir() -> PathBuf {
+        dirs::home_dir().unwrap().join(".sp1").join("keys")
+    }
+
+    /// The directory the cache stores its entries under.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The path of the entry for `elf`.
+    pub fn path(&self, elf: &[u8], fixed_core_shapes: bool) -> PathBuf {
+        let digest = hex::encode(Sha256::digest(elf));
+        let file_name = if fixed_core_shapes {
+            format!("{digest}-fixed-shapes.bin")
+        } else {
+            format!("{digest}.bin")
+        };
+        self.dir.join(SP1_CIRCUIT_VERSION).join(file_name)
+    }
+
+    /// Loads the keys of `elf` from the cache.
+    ///
+    /// Returns `Ok(None)` if there is no entry for `elf`, and an error if the entry is invalid.
+    pub fn load(
+        &self,
+        elf: &[u8],
+        fixed_core_shapes: bool,
+    ) -> Result<Option<(SP1ProvingKey, SP1VerifyingKey)>, KeyCacheError> {
+        let bytes = match fs::read(self.path(elf, fixed_core_shapes)) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if bytes.len() < 32 {
+            return Err(KeyCacheError::ChecksumMismatch);
+        }
+        let (checksum, payload) = bytes.split_at(32);
+        if Sha256::digest(payload).as_slice() != checksum {
+            return Err(KeyCacheError::ChecksumMismatch);
+        }
+        let entry: CachedKeys = bincode::deserialize(payload)?;
+        if entry.version != SP1_CIRCUIT_VERSION {
+            return Err(KeyCacheError::VersionMismatch(entry.version));
+        }
+        if entry.pk.elf != elf || entry.fixed_core_shapes != fixed_core_shapes {
+            return Err(KeyCacheError::ElfMismatch);
+        }
+        let vk = entry.pk.vk.clone();
+        Ok(Some((entry.pk, vk)))
+    }
+
+    /// Stores the keys of the program of `pk` in the cache.
+    ///
+    /// The entry is written to a temporary file first, so that concurrent readers never observe a
+    /// partially written entry.
+    pub fn store(&self, pk: &SP1ProvingKey, fixed_core_shapes: bool) -> Result<(), KeyCacheError> {
+        let path = self.path(&pk.elf, fixed_core_shapes);
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir)?;
+
+        let entry = CachedKeys {
+            version: SP1_CIRCUIT_VERSION.to_string(),
+            fixed_core_shapes,
+            pk: pk.clone(),
+        };
+        let payload = bincode::serialize(&entry)?;
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        file.write_all(&Sha256::digest(&payload))?;
+        file.write_all(&payload)?;
+        file.persist(&path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Returns the keys of `elf`, loading them from the cache or computing them with `prover` and
+    /// storing them on a miss.
+    ///
+    /// Errors while reading or writing the cache are logged and fall back to computing the keys.
+    pub fn setup(
+        &self,
+        prover: &dyn Prover<DefaultProverComponents>,
+        elf: &[u8],
+    ) -> (SP1ProvingKey, SP1VerifyingKey) {
+        let fixed_core_shapes = prover.sp1_prover().core_shape_config.is_some();
+        let fri_params = prover.sp1_prover().core_prover.config().fri_params();
+        match self.load(elf, fixed_core_shapes) {
+            Ok(Some(keys)) if keys.1.vk.fri_params == fri_params => {
+                tracing::debug!("loaded keys from {}", self.path(elf, fixed_core_shapes).display());
+                return keys;
+            }
+            Ok(Some(_)) => tracing::warn!("ignoring key cache entry with different FRI params"),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("ignoring invalid key cache entry: {e}"),
+        }
+
+        let (pk, vk) = prover.setup(elf);
+        if let Err(e) = self.store(&pk, fixed_core_shapes) {
+            tracing::warn!("failed to write key cache entry: {e}");
+        }
+        (pk, vk)
+    }
+
+    /// Removes every entry from the cache.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProver;
+
+    #[test]
+    fn test_key_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = KeyCache::new(dir.path());
+        let prover = MockProver::new();
+        let elf = include_bytes!("../../../tests/panic/elf/riscv32im-succinct-zkvm-elf");
+        let fixed = prover.sp1_prover().core_shape_config.is_some();
+
+        // A miss computes the keys and stores them.
+        assert!(cache.load(elf, fixed).unwrap().is_none());
+        let (_, vk) = cache.setup(&prover, elf);
+        let (pk, cached_vk) = cache.load(elf, fixed).unwrap().unwrap();
+        assert_eq!(pk.elf, elf);
+        assert_eq!(cached_vk.vk.commit, vk.vk.commit);
+
+        // A corrupted entry is rejected and recomputed.
+        let path = cache.path(elf, fixed);
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&path, bytes).unwrap();
+        assert!(matches!(cache.load(elf, fixed), Err(KeyCacheError::ChecksumMismatch)));
+        cache.setup(&prover, elf);
+        assert!(cache.load(elf, fixed).unwrap().is_some());
+
+        cache.clear().unwrap();
+        assert!(cache.load(elf, fixed).unwrap().is_none());
+    }
+}
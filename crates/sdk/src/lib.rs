@@ -6,10 +6,15 @@
 //! in the official SP1 documentation for a quick start guide.
 
 pub mod action;
+pub mod aggregation;
 pub mod artifacts;
+pub mod config;
 pub mod install;
+pub mod key_cache;
 #[cfg(feature = "network")]
 pub mod network;
+#[cfg(feature = "network-mock")]
+pub mod network_mock;
 #[cfg(feature = "network-v2")]
 #[path = "network-v2/mod.rs"]
 pub mod network_v2;
@@ -19,37 +24,54 @@ pub use crate::network::prover::NetworkProver as NetworkProverV1;
 pub use crate::network_v2::prover::NetworkProver as NetworkProverV2;
 #[cfg(feature = "cuda")]
 pub use crate::provers::CudaProver;
+#[cfg(feature = "remote")]
+pub use crate::provers::RemoteProver;
 
+pub mod pipeline;
 pub mod proof;
 pub mod provers;
 pub mod utils {
     pub use sp1_core_machine::utils::setup_logger;
 }
 
+use std::sync::Arc;
+
 use cfg_if::cfg_if;
+pub use config::{
+    NetworkConfig, ProverClientBuilder, ProverClientConfig, ProverClientError, ProverKind,
+    RemoteConfig,
+};
+pub use key_cache::{KeyCache, KeyCacheError};
 pub use proof::*;
-pub use provers::SP1VerificationError;
+pub use provers::{
+    pool::{set_max_async_jobs, DEFAULT_MAX_ASYNC_JOBS},
+    ProofOpts, SP1VerificationError,
+};
 use sp1_prover::components::DefaultProverComponents;
 
-use std::env;
-
 #[cfg(any(feature = "network", feature = "network-v2"))]
 use {std::future::Future, tokio::task::block_in_place};
 
 pub use provers::{CpuProver, MockProver, Prover};
 
-pub use sp1_core_executor::{ExecutionReport, HookEnv, SP1Context, SP1ContextBuilder};
+pub use sp1_core_executor::{
+    CancellationToken, ExecutionReport, HookEnv, ProgressListener, ProofCancelled, ProofPhase,
+    SP1Context, SP1ContextBuilder,
+};
 pub use sp1_core_machine::{io::SP1Stdin, riscv::cost::CostEstimator, SP1_CIRCUIT_VERSION};
-pub use sp1_primitives::io::SP1PublicValues;
+pub use sp1_primitives::{codec, io::SP1PublicValues, merkle};
 pub use sp1_prover::{
     CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, SP1Prover, SP1ProvingKey,
     SP1VerifyingKey,
 };
+pub use sp1_stark::{FriParams, SP1CoreOpts, SP1ProverOpts};
 
 /// A client for interacting with SP1.
 pub struct ProverClient {
     /// The underlying prover implementation.
     pub prover: Box<dyn Prover<DefaultProverComponents>>,
+    /// The default options used by [Self::prove].
+    pub opts: ProofOpts,
 }
 
 impl ProverClient {
@@ -60,6 +82,11 @@ impl ProverClient {
     ///   Recommended for proving end-to-end locally.
     /// - `mock`: Uses [MockProver]. Recommended for testing and development.
     /// - `network`: Uses [NetworkProver]. Recommended for outsourcing proof generation to an RPC.
+    /// - `remote`: Uses [RemoteProver] with the proving service at `SP1_PROVER_ENDPOINT`.
+    ///   Recommended for sharing a self-hosted proving service.
+    ///
+    /// Panics if the environment does not describe a valid configuration. Use [Self::builder] to
+    /// configure the client explicitly and handle errors.
     ///
     /// ### Examples
     ///
@@ -70,38 +97,29 @@ impl ProverClient {
     /// let client = ProverClient::new();
     /// ```
     pub fn new() -> Self {
-        #[allow(unreachable_code)]
-        match env::var("SP1_PROVER").unwrap_or("local".to_string()).to_lowercase().as_str() {
-            "mock" => Self { prover: Box::new(MockProver::new()) },
-            "local" => {
-                #[cfg(debug_assertions)]
-                println!("Warning: Local prover in dev mode is not recommended. Proof generation may be slow.");
-                Self {
-                    #[cfg(not(feature = "cuda"))]
-                    prover: Box::new(CpuProver::new()),
-                    #[cfg(feature = "cuda")]
-                    prover: Box::new(CudaProver::new(SP1Prover::new())),
-                }
-            }
-            "network" => {
-                cfg_if! {
-                    if #[cfg(feature = "network-v2")] {
-                        Self {
-                            prover: Box::new(NetworkProverV2::new()),
-                        }
-                    } else if #[cfg(feature = "network")] {
-                        Self {
-                            prover: Box::new(NetworkProverV1::new()),
-                        }
-                    } else {
-                        panic!("network feature is not enabled")
-                    }
-                }
-            }
-            _ => panic!(
-                "invalid value for SP1_PROVER environment variable: expected 'local', 'mock', or 'network'"
-            ),
-        }
+        ProverClientConfig::from_env()
+            .and_then(|config| ProverClientBuilder::from_config(config).build())
+            .unwrap_or_else(|e| panic!("failed to create prover client: {e}"))
+    }
+
+    /// Creates a [ProverClientBuilder] to configure a [ProverClient] explicitly, without reading
+    /// any environment variables.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, ProverClientConfig};
+    ///
+    /// let config = ProverClientConfig::from_toml_file("sp1.toml").unwrap();
+    /// let client = ProverClient::builder().prover(config.prover).build().unwrap();
+    /// ```
+    pub fn builder() -> ProverClientBuilder {
+        ProverClientBuilder::new()
+    }
+
+    /// Creates a new [ProverClient] from the given configuration.
+    pub fn from_config(config: ProverClientConfig) -> Result<Self, ProverClientError> {
+        ProverClientBuilder::from_config(config).build()
     }
 
     /// Creates a new [ProverClient] with the mock prover.
@@ -117,7 +135,7 @@ impl ProverClient {
     /// let client = ProverClient::mock();
     /// ```
     pub fn mock() -> Self {
-        Self { prover: Box::new(MockProver::new()) }
+        Self { prover: Box::new(MockProver::new()), opts: ProofOpts::default() }
     }
 
     /// Creates a new [ProverClient] with the local prover.
@@ -133,7 +151,7 @@ impl ProverClient {
     /// let client = ProverClient::local();
     /// ```
     pub fn local() -> Self {
-        Self { prover: Box::new(CpuProver::new()) }
+        Self { prover: Box::new(CpuProver::new()), opts: ProofOpts::default() }
     }
 
     /// Creates a new [ProverClient] with the network prover.
@@ -153,10 +171,12 @@ impl ProverClient {
             if #[cfg(feature = "network-v2")] {
                 Self {
                     prover: Box::new(NetworkProverV2::new()),
+                    opts: ProofOpts::default(),
                 }
             } else if #[cfg(feature = "network")] {
                 Self {
                     prover: Box::new(NetworkProverV1::new()),
+                    opts: ProofOpts::default(),
                 }
             } else {
                 panic!("network feature is not enabled")
@@ -164,6 +184,28 @@ impl ProverClient {
         }
     }
 
+    /// Creates a new [ProverClient] with the remote prover, which requests proofs from the proving
+    /// service at `endpoint`, e.g. `http://localhost:3000`.
+    ///
+    /// You can also use [ProverClient::new] to set the prover to `remote` with the `SP1_PROVER`
+    /// environment variable, and the endpoint with `SP1_PROVER_ENDPOINT`.
+    ///
+    /// Panics if the service does not become ready within a minute.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// let client = ProverClient::remote("http://localhost:3000");
+    /// ```
+    #[cfg(feature = "remote")]
+    pub fn remote(endpoint: &str) -> Self {
+        let prover = RemoteProver::new(endpoint)
+            .unwrap_or_else(|e| panic!("failed to connect to the remote prover: {e}"));
+        Self { prover: Box::new(prover), opts: ProofOpts::default() }
+    }
+
     /// Prepare to execute the given program on the given input (without generating a proof).
     /// The returned [action::Execute] may be configured via its methods before running.
     /// For example, calling [action::Execute::with_hook] registers hooks for execution.
@@ -222,7 +264,103 @@ impl ProverClient {
     /// let proof = client.prove(&pk, stdin).run().unwrap();
     /// ```
     pub fn prove<'a>(&'a self, pk: &'a SP1ProvingKey, stdin: SP1Stdin) -> action::Prove<'a> {
-        action::Prove::new(self.prover.as_ref(), pk, stdin)
+        action::Prove::new(self.prover.as_ref(), pk, stdin).with_opts(self.opts.clone())
+    }
+
+    /// Prepare to prove the execution of the given program with the given input, sharing the
+    /// proving key with the returned [action::Prove] instead of borrowing it.
+    ///
+    /// This is the same as [Self::prove], except that [action::Prove::run_async] passes the key to
+    /// the proving job without cloning it. Prefer this when proving asynchronously.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// # async fn prove() {
+    /// let elf = include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let pk = Arc::new(pk);
+    ///
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    ///
+    /// let proof = client.prove_shared(pk.clone(), stdin).run_async().await.unwrap();
+    /// # }
+    /// ```
+    pub fn prove_shared(&self, pk: Arc<SP1ProvingKey>, stdin: SP1Stdin) -> action::Prove<'_> {
+        action::Prove::new_shared(self.prover.as_ref(), pk, stdin).with_opts(self.opts.clone())
+    }
+
+    /// Prepare to prove the execution of the given program on each of the given inputs. The
+    /// returned [action::ProveBatch] may be configured via its methods before running.
+    ///
+    /// Proving many inputs of one program in a batch shares the proving key and the recursion
+    /// programs compiled for it. The local prover also generates the core proof of an input while
+    /// the previous one is being compressed, which uses more memory than proving the inputs one at
+    /// a time. [action::ProveBatch::run] returns one result per input, so that a failing input
+    /// does not discard the proofs of the others.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    ///
+    /// let stdins = (0..4u32)
+    ///     .map(|n| {
+    ///         let mut stdin = SP1Stdin::new();
+    ///         stdin.write(&n);
+    ///         stdin
+    ///     })
+    ///     .collect();
+    /// for proof in client.prove_batch(&pk, stdins).compressed().run() {
+    ///     client.verify(&proof.unwrap(), &vk).unwrap();
+    /// }
+    /// ```
+    pub fn prove_batch<'a>(
+        &'a self,
+        pk: &'a SP1ProvingKey,
+        stdins: Vec<SP1Stdin>,
+    ) -> action::ProveBatch<'a> {
+        action::ProveBatch::new(self.prover.as_ref(), pk, stdins).with_opts(self.opts.clone())
+    }
+
+    /// Prepare to aggregate the given compressed proofs into one proof with the built-in
+    /// aggregation program. The returned [action::Aggregate] may be configured via its methods
+    /// before running.
+    ///
+    /// The aggregate commits to the vkey hash and public values of every input proof. Use
+    /// [aggregation::is_aggregated] to check that a proof was aggregated after verifying the
+    /// aggregate against the returned verifying key.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{aggregation::is_aggregated, ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    ///
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let proof = client.prove(&pk, stdin).compressed().run().unwrap();
+    ///
+    /// let proofs = vec![(proof, vk)];
+    /// let (aggregate, aggregation_vk) = client.aggregate(&proofs).run().unwrap();
+    /// client.verify(&aggregate, &aggregation_vk).unwrap();
+    /// assert!(is_aggregated(&aggregate, &proofs[0].0, &proofs[0].1).unwrap());
+    /// ```
+    pub fn aggregate<'a>(
+        &'a self,
+        proofs: &'a [(SP1ProofWithPublicValues, SP1VerifyingKey)],
+    ) -> action::Aggregate<'a> {
+        action::Aggregate::new(self.prover.as_ref(), proofs).with_opts(self.opts.clone())
     }
 
     /// Verifies that the given proof is valid and matches the given verification key produced by
@@ -274,6 +412,24 @@ impl ProverClient {
     pub fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
         self.prover.setup(elf)
     }
+
+    /// Setup a program like [Self::setup], reusing the keys stored in the on-disk [KeyCache] at
+    /// `~/.sp1/keys` if they were computed before.
+    ///
+    /// Entries are keyed by the SHA-256 digest of the ELF and by [SP1_CIRCUIT_VERSION], and are
+    /// checked for corruption when they are loaded.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// let elf = include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup_cached(elf);
+    /// ```
+    pub fn setup_cached(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        KeyCache::default().setup(self.prover.as_ref(), elf)
+    }
 }
 
 impl Default for ProverClient {
@@ -333,6 +489,24 @@ mod tests {
         tracing::info!("gas = {}", report.estimate_gas());
     }
 
+    #[tokio::test]
+    async fn test_execute_async() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let (public_values, _) = client.execute(elf, stdin.clone()).run_async().await.unwrap();
+        let proof = client.prove(&pk, stdin.clone()).run_async().await.unwrap();
+        assert_eq!(proof.public_values.as_slice(), public_values.as_slice());
+
+        // Hooks may borrow from the caller, so they are only supported by the blocking methods.
+        let result = client.execute(elf, stdin).with_hook(10, |_, _| vec![]).run_async().await;
+        assert!(result.is_err());
+    }
+
     #[test]
     #[should_panic]
     fn test_execute_panic() {
@@ -418,6 +592,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_prove_batch() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let stdins = [10usize, 20]
+            .iter()
+            .map(|n| {
+                let mut stdin = SP1Stdin::new();
+                stdin.write(n);
+                stdin
+            })
+            .collect::<Vec<_>>();
+
+        // Every input is proven, and a failing input does not affect the others.
+        let proofs = client.prove_batch(&pk, stdins.clone()).run();
+        assert_eq!(proofs.len(), 2);
+        for proof in proofs {
+            client.verify(&proof.unwrap(), &vk).unwrap();
+        }
+        let proofs = client.prove_batch(&pk, vec![SP1Stdin::new(), stdins[0].clone()]).run();
+        assert!(proofs[0].is_err());
+        client.verify(proofs[1].as_ref().unwrap(), &vk).unwrap();
+    }
+
     #[test]
     fn test_e2e_prove_plonk_mock() {
         utils::setup_logger();
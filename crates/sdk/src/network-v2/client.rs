@@ -35,20 +35,36 @@ pub struct NetworkClient {
     signer: PrivateKeySigner,
     http: HttpClientWithMiddleware,
     s3: OnceCell<S3Client>,
+    endpoint: String,
 }
 
 impl NetworkClient {
     /// Create a new network client with the given private key.
     pub fn new(private_key: &str) -> Self {
-        let signer = PrivateKeySigner::from_str(private_key).unwrap();
+        Self::try_new(private_key, &Self::rpc_url()).unwrap()
+    }
+
+    /// Create a new network client with the given private key, connecting to the given RPC
+    /// endpoint.
+    pub fn try_new(private_key: &str, rpc_url: &str) -> Result<Self> {
+        let signer = PrivateKeySigner::from_str(private_key).context("invalid private key")?;
 
         let http_client = reqwest::Client::builder()
             .pool_max_idle_per_host(0)
             .pool_idle_timeout(Duration::from_secs(240))
-            .build()
-            .unwrap();
+            .build()?;
+
+        Ok(Self {
+            signer,
+            http: http_client.into(),
+            s3: OnceCell::new(),
+            endpoint: rpc_url.to_string(),
+        })
+    }
 
-        Self { signer, http: http_client.into(), s3: OnceCell::new() }
+    /// Returns the RPC endpoint this client is connected to.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
     }
 
     /// Returns the currently configured RPC endpoint for the Succinct prover network.
@@ -58,7 +74,7 @@ impl NetworkClient {
 
     /// Get a connected RPC client.
     async fn get_rpc(&self) -> Result<ProverNetworkClient<Channel>> {
-        let rpc_url = Self::rpc_url();
+        let rpc_url = self.endpoint.clone();
         let mut endpoint = Channel::from_shared(rpc_url.clone())?;
 
         // Check if the URL scheme is HTTPS and configure TLS.
@@ -74,7 +90,7 @@ impl NetworkClient {
 
     /// Get a connected artifact store client.
     async fn get_store(&self) -> Result<ArtifactStoreClient<Channel>> {
-        let rpc_url = Self::rpc_url();
+        let rpc_url = self.endpoint.clone();
         let mut endpoint = Channel::from_shared(rpc_url.clone())?;
 
         // Check if the URL scheme is HTTPS and configure TLS.
@@ -230,8 +246,19 @@ impl NetworkClient {
         Ok(uri)
     }
 
-    /// Download an artifact from S3.
+    /// Download an artifact from S3, or over HTTPS if the URI is a URL.
+    ///
+    /// Plain `http://` URLs are only accepted with the `network-mock` feature, which serves
+    /// artifacts from a local HTTP server.
     async fn download_artifact(&self, uri: &str) -> Result<Vec<u8>> {
+        let is_url = uri.starts_with("https://")
+            || (cfg!(feature = "network-mock") && uri.starts_with("http://"));
+        if is_url {
+            let response = self.http.get(uri).send().await.context("Failed to get artifact")?;
+            let response = response.error_for_status().context("Failed to get artifact")?;
+            return Ok(response.bytes().await.context("Failed to read artifact body")?.to_vec());
+        }
+
         let s3_client = self.get_s3_client().await;
         let uri = uri.strip_prefix("s3://").context("Invalid S3 URI")?;
         let (bucket, key) = uri.split_once('/').context("Invalid S3 URI format")?;
@@ -1,5 +1,7 @@
 use std::{
     env,
+    path::PathBuf,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -9,8 +11,11 @@ use crate::{
     Prover, SP1Context, SP1ProofKind, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
 };
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::de::DeserializeOwned;
+use sp1_core_executor::ExecutionReport;
 use sp1_core_machine::io::SP1Stdin;
+use sp1_primitives::io::SP1PublicValues;
 use sp1_prover::{components::DefaultProverComponents, SP1Prover, SP1_CIRCUIT_VERSION};
 use sp1_stark::SP1ProverOpts;
 
@@ -40,11 +45,23 @@ impl NetworkProver {
 
     /// Creates a new [NetworkProver] with the given private key.
     pub fn new_from_key(private_key: &str) -> Self {
+        Self::try_new(private_key, &NetworkClient::rpc_url()).unwrap()
+    }
+
+    /// Creates a new [NetworkProver] with the given private key, connecting to the given RPC
+    /// endpoint.
+    pub fn try_new(private_key: &str, rpc_url: &str) -> Result<Self> {
         let version = SP1_CIRCUIT_VERSION;
         log::info!("Client circuit version: {}", version);
         let local_prover = CpuProver::new();
-        let client = NetworkClient::new(private_key);
-        Self { client, local_prover }
+        let client = NetworkClient::try_new(private_key, rpc_url)?;
+        Ok(Self { client, local_prover })
+    }
+
+    /// Sets the directory under which the Plonk and Groth16 circuit artifacts are installed.
+    pub fn with_circuits_dir(mut self, circuits_dir: impl Into<PathBuf>) -> Self {
+        self.local_prover = self.local_prover.with_circuits_dir(circuits_dir);
+        self
     }
 
     /// Requests a proof from the prover network, returning the request ID.
@@ -59,7 +76,7 @@ impl NetworkProver {
         let skip_simulation = env::var("SKIP_SIMULATION").map(|val| val == "true").unwrap_or(false);
         let cycle_limit = if !skip_simulation {
             let (_, report) =
-                self.local_prover.sp1_prover().execute(elf, &stdin, Default::default())?;
+                self.local_prover.execute_async(elf, &stdin, Default::default()).await?;
             let cycles = report.total_instruction_count();
             log::info!("Simulation complete, cycles: {}", cycles);
             cycles
@@ -97,7 +114,7 @@ impl NetworkProver {
         let request_id_hex = "0x".to_string() + &hex::encode(request_id.clone());
         log::info!("Created request {} in transaction {}", request_id_hex, tx_hash_hex);
 
-        if NetworkClient::rpc_url() == DEFAULT_PROVER_NETWORK_RPC {
+        if self.client.endpoint() == DEFAULT_PROVER_NETWORK_RPC {
             log::info!("View in explorer: https://explorer-v2.succinct.xyz/{}", request_id_hex);
         }
 
@@ -152,11 +169,16 @@ impl NetworkProver {
     }
 }
 
+#[async_trait]
 impl Prover<DefaultProverComponents> for NetworkProver {
     fn id(&self) -> ProverType {
         ProverType::Network
     }
 
+    fn circuits_dir(&self) -> PathBuf {
+        self.local_prover.circuits_dir()
+    }
+
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
         self.local_prover.setup(elf)
     }
@@ -173,9 +195,30 @@ impl Prover<DefaultProverComponents> for NetworkProver {
         context: SP1Context<'a>,
         kind: SP1ProofKind,
     ) -> Result<SP1ProofWithPublicValues> {
-        warn_if_not_default(&opts.sp1_prover_opts, &context);
+        warn_if_not_default(&opts, &context);
         block_on(self.prove(&pk.elf, stdin, kind.into(), opts.timeout))
     }
+
+    async fn prove_async<'a>(
+        &'a self,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+        opts: ProofOpts,
+        context: SP1Context<'a>,
+        kind: SP1ProofKind,
+    ) -> Result<SP1ProofWithPublicValues> {
+        warn_if_not_default(&opts, &context);
+        self.prove(&pk.elf, stdin, kind.into(), opts.timeout).await
+    }
+
+    async fn execute_async<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        context: SP1Context<'a>,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        self.local_prover.execute_async(elf, stdin, context).await
+    }
 }
 
 impl Default for NetworkProver {
@@ -185,14 +228,18 @@ impl Default for NetworkProver {
 }
 
 /// Warns if `opts` or `context` are not default values, since they are currently unsupported.
-fn warn_if_not_default(opts: &SP1ProverOpts, context: &SP1Context) {
+fn warn_if_not_default(opts: &ProofOpts, context: &SP1Context) {
     let _guard = tracing::warn_span!("network_prover").entered();
-    if opts != &SP1ProverOpts::default() {
-        tracing::warn!("non-default opts will be ignored: {:?}", opts.core_opts);
+    if opts.sp1_prover_opts != SP1ProverOpts::default() {
+        tracing::warn!("non-default opts will be ignored: {:?}", opts.sp1_prover_opts.core_opts);
         tracing::warn!("custom SP1ProverOpts are currently unsupported by the network prover");
     }
+    if let Some(job_dir) = &opts.job_dir {
+        tracing::warn!("job directory {} will be ignored", job_dir.display());
+        tracing::warn!("resumable proving is currently unsupported by the network prover");
+    }
     // Exhaustive match is done to ensure we update the warnings if the types change.
-    let SP1Context { hook_registry, subproof_verifier, .. } = context;
+    let SP1Context { hook_registry, subproof_verifier, progress, .. } = context;
     if hook_registry.is_some() {
         tracing::warn!("non-default context.hook_registry will be ignored: {:?}", hook_registry);
         tracing::warn!("custom runtime hooks are currently unsupported by the network prover");
@@ -202,6 +249,12 @@ fn warn_if_not_default(opts: &SP1ProverOpts, context: &SP1Context) {
         tracing::warn!("non-default context.subproof_verifier will be ignored");
         tracing::warn!("custom subproof verifiers are currently unsupported by the network prover");
     }
+    if progress.cancellation_token.is_some() || progress.listener.is_some() {
+        tracing::warn!("non-default context.progress will be ignored");
+        tracing::warn!(
+            "cancellation tokens and progress listeners are currently unsupported by the network prover"
+        );
+    }
 }
 
 impl From<SP1ProofKind> for ProofMode {
@@ -59,8 +59,13 @@ pub struct NetworkAuth {
 
 impl NetworkAuth {
     pub fn new(private_key: &str) -> Self {
-        let wallet = LocalWallet::from_str(private_key).unwrap();
-        Self { wallet }
+        Self::try_new(private_key).unwrap()
+    }
+
+    /// Creates a new [NetworkAuth], returning an error if the private key is invalid.
+    pub fn try_new(private_key: &str) -> Result<Self> {
+        let wallet = LocalWallet::from_str(private_key)?;
+        Ok(Self { wallet })
     }
 
     /// Gets the EIP-712 domain separator for the Succinct prover network.
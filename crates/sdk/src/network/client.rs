@@ -40,6 +40,7 @@ pub struct NetworkClient {
     pub rpc: TwirpClient,
     pub http: HttpClientWithMiddleware,
     pub auth: NetworkAuth,
+    endpoint: String,
 }
 
 impl NetworkClient {
@@ -50,27 +51,35 @@ impl NetworkClient {
 
     /// Create a new NetworkClient with the given private key for authentication.
     pub fn new(private_key: &str) -> Self {
-        let auth = NetworkAuth::new(private_key);
+        Self::try_new(private_key, &Self::rpc_url()).unwrap()
+    }
+
+    /// Create a new NetworkClient with the given private key for authentication, connecting to
+    /// the given RPC endpoint.
+    pub fn try_new(private_key: &str, rpc_url: &str) -> Result<Self> {
+        let auth = NetworkAuth::try_new(private_key).context("invalid private key")?;
 
         let twirp_http_client = HttpClient::builder()
             .timeout(REQUEST_TIMEOUT)
             .pool_max_idle_per_host(0)
             .pool_idle_timeout(Duration::from_secs(240))
-            .build()
-            .unwrap();
+            .build()?;
 
-        let rpc_url = Self::rpc_url();
-        let rpc =
-            TwirpClient::new(Url::parse(&rpc_url).unwrap(), twirp_http_client, vec![]).unwrap();
+        let url = Url::parse(rpc_url).with_context(|| format!("invalid RPC url: {rpc_url}"))?;
+        let rpc = TwirpClient::new(url, twirp_http_client, vec![])?;
 
         let http_client = HttpClient::builder()
             .timeout(REQUEST_TIMEOUT)
             .pool_max_idle_per_host(0)
             .pool_idle_timeout(Duration::from_secs(240))
-            .build()
-            .unwrap();
+            .build()?;
 
-        Self { auth, rpc, http: http_client.into() }
+        Ok(Self { auth, rpc, http: http_client.into(), endpoint: rpc_url.to_string() })
+    }
+
+    /// Returns the RPC endpoint this client is connected to.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
     }
 
     /// Gets the latest nonce for this auth's account.
@@ -89,6 +98,28 @@ impl NetworkClient {
         Ok(())
     }
 
+    /// Downloads the artifact at the given url, such as one returned by [Self::claim_proof].
+    pub async fn download_artifact(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .http
+            .get(url)
+            .timeout(Duration::from_secs(120))
+            .send()
+            .await
+            .context("Failed to send HTTP request for artifact")?
+            .error_for_status()
+            .context("Failed to download artifact")?;
+        Ok(response.bytes().await.context("Failed to load artifact bytes")?.to_vec())
+    }
+
+    /// Uploads an artifact to the given url, such as the proof artifact returned by
+    /// [Self::claim_proof].
+    pub async fn upload_artifact(&self, url: &str, data: Vec<u8>) -> Result<()> {
+        let response = self.http.put(url).body(data).send().await?;
+        response.error_for_status().context("Failed to upload artifact")?;
+        Ok(())
+    }
+
     /// Get the status and the proof if available of a given proof request. The proof is returned
     /// only if the status is Fulfilled.
     pub async fn get_proof_status(
@@ -1,5 +1,6 @@
 pub mod auth;
 pub mod client;
+pub mod node;
 pub mod prover;
 
 #[rustfmt::skip]
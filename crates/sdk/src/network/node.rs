@@ -0,0 +1,296 @@
+//! A prover node that fulfills proof requests of the prover network.
+//!
+//! [ProverNode] polls the network for requested proofs, claims them, downloads the program and
+//! stdin artifacts, proves them with a local [Prover], uploads the proof and fulfills the
+//! request. The `sp1-prover-node` binary runs a node with a [crate::CpuProver].
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use sp1_core_executor::SP1Context;
+use sp1_core_machine::io::SP1Stdin;
+use sp1_prover::{components::DefaultProverComponents, SP1_CIRCUIT_VERSION};
+use tokio::{sync::Semaphore, task::JoinSet, time::sleep};
+
+use crate::{
+    network::{
+        client::NetworkClient,
+        proto::network::{ProofMode, ProofStatus, UnclaimReason},
+    },
+    provers::ProofOpts,
+    Prover, SP1ProofKind,
+};
+
+/// The default cycle limit of a request, matching the default limit of the network.
+pub const DEFAULT_MAX_CYCLES: u64 = 1_000_000_000;
+
+/// Configuration of a [ProverNode].
+#[derive(Debug, Clone)]
+pub struct ProverNodeConfig {
+    /// The interval between two polls for requested proofs.
+    pub poll_interval: Duration,
+    /// The maximum number of requests proven at the same time.
+    pub max_concurrent_proofs: usize,
+    /// The circuit version of the requests to claim.
+    pub circuit_version: String,
+    /// The maximum number of cycles a request may execute for. Requests that exceed it are
+    /// unclaimed.
+    pub max_cycles: u64,
+}
+
+impl Default for ProverNodeConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(5),
+            max_concurrent_proofs: 1,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            max_cycles: DEFAULT_MAX_CYCLES,
+        }
+    }
+}
+
+/// A node that claims and fulfills proof requests of the prover network.
+pub struct ProverNode {
+    client: Arc<NetworkClient>,
+    prover: Arc<dyn Prover<DefaultProverComponents>>,
+    config: ProverNodeConfig,
+}
+
+impl ProverNode {
+    /// Creates a new [ProverNode] that fulfills the requests of `client`'s network with `prover`.
+    pub fn new(
+        client: NetworkClient,
+        prover: impl Prover<DefaultProverComponents> + 'static,
+        config: ProverNodeConfig,
+    ) -> Self {
+        assert!(config.max_concurrent_proofs > 0, "max_concurrent_proofs must be positive");
+        Self { client: Arc::new(client), prover: Arc::new(prover), config }
+    }
+
+    /// Fulfills requests until `shutdown` completes.
+    ///
+    /// Once `shutdown` completes no new requests are claimed, and the claimed requests are
+    /// finished before returning. Errors of the network are logged and retried at the next poll,
+    /// and requests that fail or panic are unclaimed.
+    pub async fn run(&self, shutdown: impl Future<Output = ()>) -> Result<()> {
+        let permits = Arc::new(Semaphore::new(self.config.max_concurrent_proofs));
+        let mut tasks = JoinSet::new();
+        tokio::pin!(shutdown);
+
+        loop {
+            while let Some(result) = tasks.try_join_next() {
+                log_task_result(result);
+            }
+
+            if permits.available_permits() > 0 {
+                if let Err(e) = self.claim_requests(&permits, &mut tasks).await {
+                    log::warn!("Failed to claim proof requests: {:?}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = sleep(self.config.poll_interval) => {}
+            }
+        }
+
+        log::info!("Shutting down, waiting for {} claimed requests", tasks.len());
+        while let Some(result) = tasks.join_next().await {
+            log_task_result(result);
+        }
+        Ok(())
+    }
+
+    /// Claims requested proofs while proving slots are available, and proves them in `tasks`.
+    async fn claim_requests(
+        &self,
+        permits: &Arc<Semaphore>,
+        tasks: &mut JoinSet<()>,
+    ) -> Result<()> {
+        let requests = self
+            .client
+            .get_proof_requests(ProofStatus::ProofRequested, Some(&self.config.circuit_version))
+            .await?;
+
+        for request in requests.proofs {
+            let Ok(permit) = permits.clone().try_acquire_owned() else {
+                break;
+            };
+            let kind = match proof_kind(request.mode()) {
+                Some(kind) => kind,
+                None => {
+                    log::warn!("Skipping {} with unknown mode {}", request.proof_id, request.mode);
+                    continue;
+                }
+            };
+            let claim = match self.client.claim_proof(&request.proof_id).await {
+                Ok(claim) => claim,
+                Err(e) => {
+                    // Another prover may have claimed the request first.
+                    log::warn!("Failed to claim {}: {:?}", request.proof_id, e);
+                    continue;
+                }
+            };
+            log::info!("Claimed {}", request.proof_id);
+
+            let client = self.client.clone();
+            let prover = self.prover.clone();
+            let max_cycles = self.config.max_cycles;
+            tasks.spawn(async move {
+                let proof_id = request.proof_id;
+                // Fulfill in a task of its own, so that a panic is caught and the request is
+                // unclaimed like any other failure.
+                let result = tokio::spawn({
+                    let (client, proof_id) = (client.clone(), proof_id.clone());
+                    async move { fulfill(&client, prover, &proof_id, kind, claim, max_cycles).await }
+                })
+                .await
+                .unwrap_or_else(|e| Err(anyhow!("proof task panicked: {e}")));
+                if let Err(e) = result {
+                    log::error!("Failed to fulfill {}: {:?}", proof_id, e);
+                    let description = format!("{e:#}");
+                    if let Err(e) = client
+                        .unclaim_proof(proof_id.clone(), UnclaimReason::Abandoned, description)
+                        .await
+                    {
+                        log::error!("Failed to unclaim {}: {:?}", proof_id, e);
+                    }
+                }
+                drop(permit);
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Logs the result of a proof task that did not finish normally.
+fn log_task_result(result: Result<(), tokio::task::JoinError>) {
+    if let Err(e) = result {
+        log::error!("Proof task failed: {e}");
+    }
+}
+
+/// Proves a claimed request and fulfills it.
+///
+/// The artifact identifiers of the claim are the URLs the artifacts are downloaded from and the
+/// proof is uploaded to. Execution and proving stop after `max_cycles` cycles.
+async fn fulfill(
+    client: &NetworkClient,
+    prover: Arc<dyn Prover<DefaultProverComponents>>,
+    proof_id: &str,
+    kind: SP1ProofKind,
+    claim: crate::network::proto::network::ClaimProofResponse,
+    max_cycles: u64,
+) -> Result<()> {
+    let program = client.download_artifact(&claim.program_artifact_id).await?;
+    let stdin = client.download_artifact(&claim.stdin_artifact_id).await?;
+    let elf: Vec<u8> = bincode::deserialize(&program).context("invalid program artifact")?;
+    let stdin: SP1Stdin = bincode::deserialize(&stdin).context("invalid stdin artifact")?;
+
+    let context = || SP1Context::builder().max_cycles(max_cycles).build();
+    let (_, report) = prover.execute_async(&elf, &stdin, context()).await?;
+    client.modify_cpu_cycles(proof_id, report.total_instruction_count()).await?;
+
+    let proof = tokio::task::spawn_blocking(move || {
+        let (pk, _) = prover.setup(&elf);
+        prover.prove(&pk, stdin, ProofOpts::default(), context(), kind)
+    })
+    .await
+    .context("prover panicked")??;
+
+    client.upload_artifact(&claim.proof_artifact_id, bincode::serialize(&proof)?).await?;
+    client.fulfill_proof(proof_id).await?;
+    log::info!("Fulfilled {}", proof_id);
+    Ok(())
+}
+
+/// The proof kind of a requested [ProofMode].
+fn proof_kind(mode: ProofMode) -> Option<SP1ProofKind> {
+    match mode {
+        ProofMode::Core => Some(SP1ProofKind::Core),
+        ProofMode::Compressed => Some(SP1ProofKind::Compressed),
+        ProofMode::Plonk => Some(SP1ProofKind::Plonk),
+        ProofMode::Groth16 => Some(SP1ProofKind::Groth16),
+        ProofMode::Unspecified => None,
+    }
+}
+
+#[cfg(all(test, feature = "network-mock"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ProverNode, ProverNodeConfig};
+    use crate::{
+        network::{client::NetworkClient, proto::network::ProofMode},
+        network_mock::{MockNetwork, MockNetworkConfig, MockRequestStatus},
+        utils, MockProver, NetworkProverV1, SP1Proof, SP1Stdin,
+    };
+
+    const ELF: &[u8] =
+        include_bytes!("../../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    const PRIVATE_KEY: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prover_node() {
+        utils::setup_logger();
+        let config = MockNetworkConfig { auto_fulfill: false, ..Default::default() };
+        let network = MockNetwork::start(MockProver::new(), config).await.unwrap();
+
+        let client = NetworkClient::try_new(PRIVATE_KEY, &network.rpc_url()).unwrap();
+        let config =
+            ProverNodeConfig { poll_interval: Duration::from_millis(100), ..Default::default() };
+        let node = ProverNode::new(client, MockProver::new(), config);
+        let (shutdown, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let node = tokio::spawn(async move {
+            node.run(async {
+                let _ = shutdown_rx.await;
+            })
+            .await
+        });
+
+        let prover = NetworkProverV1::try_new(PRIVATE_KEY, &network.rpc_url()).unwrap();
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof_id = prover.request_proof(ELF, stdin, ProofMode::Compressed).await.unwrap();
+        let proof = prover.wait_proof(&proof_id, None).await.unwrap();
+        assert!(matches!(proof.proof, SP1Proof::Compressed(_)));
+        assert_eq!(network.request(&proof_id).unwrap().status, MockRequestStatus::Fulfilled);
+
+        shutdown.send(()).unwrap();
+        node.await.unwrap().unwrap();
+        network.shutdown().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prover_node_max_cycles() {
+        utils::setup_logger();
+        let config = MockNetworkConfig { auto_fulfill: false, ..Default::default() };
+        let network = MockNetwork::start(MockProver::new(), config).await.unwrap();
+
+        let client = NetworkClient::try_new(PRIVATE_KEY, &network.rpc_url()).unwrap();
+        let config = ProverNodeConfig {
+            poll_interval: Duration::from_millis(100),
+            max_cycles: 1,
+            ..Default::default()
+        };
+        let node = ProverNode::new(client, MockProver::new(), config);
+        let (shutdown, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let node = tokio::spawn(async move {
+            node.run(async {
+                let _ = shutdown_rx.await;
+            })
+            .await
+        });
+
+        let prover = NetworkProverV1::try_new(PRIVATE_KEY, &network.rpc_url()).unwrap();
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof_id = prover.request_proof(ELF, stdin, ProofMode::Compressed).await.unwrap();
+        assert!(prover.wait_proof(&proof_id, None).await.is_err());
+        assert_eq!(network.request(&proof_id).unwrap().status, MockRequestStatus::Failed);
+
+        shutdown.send(()).unwrap();
+        node.await.unwrap().unwrap();
+        network.shutdown().await;
+    }
+}
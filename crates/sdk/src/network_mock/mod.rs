@@ -0,0 +1,504 @@
+//! An in-process stand-in for the prover network, for testing the network provers offline.
+//!
+//! [MockNetwork] serves the twirp API used by [crate::NetworkProverV1], the `ProverNetwork` and
+//! `ArtifactStore` gRPC services used by [crate::NetworkProverV2], and an HTTP artifact store
+//! that replaces the presigned URLs of the hosted network. Requests are proven with a local
+//! [Prover], usually a [crate::MockProver], and the store is kept in memory.
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use sp1_sdk::{
+//!     network_mock::{MockNetwork, MockNetworkConfig},
+//!     MockProver, NetworkProverV1,
+//! };
+//!
+//! let network = MockNetwork::start(MockProver::new(), MockNetworkConfig::default()).await?;
+//! let prover = NetworkProverV1::try_new(
+//!     "0x0000000000000000000000000000000000000000000000000000000000000001",
+//!     &network.rpc_url(),
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "network")]
+mod v1;
+#[cfg(feature = "network-v2")]
+mod v2;
+
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use sp1_core_executor::SP1Context;
+use sp1_core_machine::io::SP1Stdin;
+use sp1_prover::components::DefaultProverComponents;
+use tokio::{net::TcpListener, sync::watch, task::JoinHandle};
+use twirp::axum::{
+    self,
+    body::Bytes,
+    extract::{Path, State as AxumState},
+    http::StatusCode,
+    routing::get,
+};
+
+use crate::{provers::ProofOpts, Prover, SP1ProofKind};
+
+/// Configuration of a [MockNetwork].
+#[derive(Debug, Clone)]
+pub struct MockNetworkConfig {
+    /// The latency added to every RPC.
+    pub latency: Duration,
+    /// If set, every `n`-th RPC fails with an `unavailable` error, starting with the `n`-th.
+    pub fail_every: Option<u64>,
+    /// Whether proof requests fail instead of being proven.
+    ///
+    /// Failed requests are unclaimed in the v1 API. The v2 API has no failed status, so they are
+    /// reported with an unspecified status and an error description.
+    pub fail_proofs: bool,
+    /// Whether submitted requests are proven by the network itself. Otherwise they wait for a
+    /// prover to claim and fulfill them.
+    pub auto_fulfill: bool,
+}
+
+impl Default for MockNetworkConfig {
+    fn default() -> Self {
+        Self { latency: Duration::ZERO, fail_every: None, fail_proofs: false, auto_fulfill: true }
+    }
+}
+
+/// The status of a [MockProofRequest].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockRequestStatus {
+    /// Created, but the program and stdin have not been submitted yet.
+    Preparing,
+    /// Waiting for a prover.
+    Requested,
+    /// Claimed by a prover.
+    Claimed,
+    /// The proof is available in the artifact store.
+    Fulfilled,
+    /// The request could not be proven.
+    Failed,
+}
+
+/// A proof request known to a [MockNetwork].
+#[derive(Debug, Clone)]
+pub struct MockProofRequest {
+    /// The requested proof kind.
+    pub mode: SP1ProofKind,
+    /// The circuit version of the request.
+    pub version: String,
+    /// The address of the requester. Signatures are not checked, so this is always empty.
+    pub requester: Vec<u8>,
+    /// The artifact holding the program.
+    pub program_artifact: String,
+    /// The artifact holding the stdin.
+    pub stdin_artifact: String,
+    /// The artifact the proof is stored in once fulfilled.
+    pub proof_artifact: String,
+    /// The status of the request.
+    pub status: MockRequestStatus,
+    /// Why the request failed or was unclaimed.
+    pub error: Option<String>,
+}
+
+/// The state shared by the services of a [MockNetwork].
+pub(crate) struct MockState {
+    config: MockNetworkConfig,
+    prover: Arc<dyn Prover<DefaultProverComponents>>,
+    http_url: String,
+    calls: AtomicU64,
+    next_id: AtomicU64,
+    artifacts: Mutex<HashMap<String, Vec<u8>>>,
+    nonce: AtomicU64,
+    /// The proof requests by identifier, oldest first.
+    requests: Mutex<Vec<(String, MockProofRequest)>>,
+}
+
+/// An RPC failure injected by [MockNetworkConfig::fail_every].
+pub(crate) struct InjectedFailure;
+
+impl MockState {
+    /// Waits for the configured latency and decides whether the RPC should fail.
+    pub(crate) async fn rpc(&self) -> Result<(), InjectedFailure> {
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        match self.config.fail_every {
+            Some(n) if n > 0 && call % n == 0 => Err(InjectedFailure),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a new unique identifier with the given prefix.
+    pub(crate) fn new_id(&self, prefix: &str) -> String {
+        format!("{prefix}_{}", self.next_id.fetch_add(1, Ordering::SeqCst))
+    }
+
+    /// The URL an artifact is uploaded to and downloaded from.
+    pub(crate) fn artifact_url(&self, artifact: &str) -> String {
+        format!("{}/artifacts/{artifact}", self.http_url)
+    }
+
+    /// Returns the current nonce. Signatures are not checked, so a single nonce is shared by all
+    /// accounts.
+    pub(crate) fn nonce(&self) -> u64 {
+        self.nonce.load(Ordering::SeqCst)
+    }
+
+    /// Increments the nonce, as every signed message does.
+    pub(crate) fn use_nonce(&self) {
+        self.nonce.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn put_artifact(&self, artifact: &str, bytes: Vec<u8>) {
+        self.artifacts.lock().unwrap().insert(artifact.to_string(), bytes);
+    }
+
+    pub(crate) fn get_artifact(&self, artifact: &str) -> Option<Vec<u8>> {
+        self.artifacts.lock().unwrap().get(artifact).cloned()
+    }
+
+    /// Creates a request in the [MockRequestStatus::Preparing] status, with a new artifact for its
+    /// proof.
+    pub(crate) fn create_request(
+        &self,
+        id: &str,
+        mode: SP1ProofKind,
+        version: &str,
+        program_artifact: String,
+        stdin_artifact: String,
+    ) -> MockProofRequest {
+        let request = MockProofRequest {
+            mode,
+            version: version.to_string(),
+            requester: Vec::new(),
+            program_artifact,
+            stdin_artifact,
+            proof_artifact: self.new_id("proof"),
+            status: MockRequestStatus::Preparing,
+            error: None,
+        };
+        self.requests.lock().unwrap().push((id.to_string(), request.clone()));
+        request
+    }
+
+    pub(crate) fn request(&self, id: &str) -> Option<MockProofRequest> {
+        let requests = self.requests.lock().unwrap();
+        requests.iter().find(|(other, _)| other == id).map(|(_, request)| request.clone())
+    }
+
+    /// Updates the request `id` if it is in the `from` status, returning the updated request.
+    pub(crate) fn transition(
+        &self,
+        id: &str,
+        from: &[MockRequestStatus],
+        update: impl FnOnce(&mut MockProofRequest),
+    ) -> Result<MockProofRequest, String> {
+        let mut requests = self.requests.lock().unwrap();
+        let (_, request) = requests
+            .iter_mut()
+            .find(|(other, _)| other == id)
+            .ok_or_else(|| format!("unknown request {id}"))?;
+        if !from.contains(&request.status) {
+            return Err(format!("request {id} is {:?}", request.status));
+        }
+        update(request);
+        Ok(request.clone())
+    }
+
+    /// Returns the requests in the given status, oldest first.
+    pub(crate) fn requests_with_status(
+        &self,
+        status: MockRequestStatus,
+        version: Option<&str>,
+    ) -> Vec<(String, MockProofRequest)> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, r)| r.status == status && version.map_or(true, |v| r.version == v))
+            .cloned()
+            .collect()
+    }
+
+    /// Marks the request as requested and, if the network fulfills requests itself, proves it in
+    /// the background.
+    pub(crate) fn submit(self: &Arc<Self>, id: &str) -> Result<(), String> {
+        self.transition(id, &[MockRequestStatus::Preparing], |r| {
+            r.status = MockRequestStatus::Requested
+        })?;
+        if self.config.auto_fulfill {
+            let state = self.clone();
+            let id = id.to_string();
+            tokio::spawn(async move { state.fulfill(&id).await });
+        }
+        Ok(())
+    }
+
+    /// Proves the request `id` with the local prover and stores the proof.
+    async fn fulfill(self: Arc<Self>, id: &str) {
+        let Ok(request) = self.transition(id, &[MockRequestStatus::Requested], |r| {
+            r.status = MockRequestStatus::Claimed
+        }) else {
+            return;
+        };
+
+        let result = if self.config.fail_proofs {
+            Err(anyhow::anyhow!("proof failure injected by the mock network"))
+        } else {
+            let state = self.clone();
+            let request = request.clone();
+            tokio::task::spawn_blocking(move || state.prove(&request))
+                .await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("prover panicked: {e}")))
+        };
+
+        let _ = self.transition(id, &[MockRequestStatus::Claimed], |r| match result {
+            Ok(proof) => {
+                self.put_artifact(&request.proof_artifact, proof);
+                r.status = MockRequestStatus::Fulfilled;
+            }
+            Err(e) => {
+                tracing::warn!("mock network failed to prove {id}: {e}");
+                r.status = MockRequestStatus::Failed;
+                r.error = Some(e.to_string());
+            }
+        });
+    }
+
+    /// Proves a request, returning the serialized [crate::SP1ProofWithPublicValues].
+    fn prove(&self, request: &MockProofRequest) -> Result<Vec<u8>> {
+        let program = self.get_artifact(&request.program_artifact).context("missing program")?;
+        let stdin = self.get_artifact(&request.stdin_artifact).context("missing stdin")?;
+        let elf: Vec<u8> = bincode::deserialize(&program)?;
+        let stdin: SP1Stdin = bincode::deserialize(&stdin)?;
+
+        let (pk, _) = self.prover.setup(&elf);
+        let proof = self.prover.prove(
+            &pk,
+            stdin,
+            ProofOpts::default(),
+            SP1Context::default(),
+            request.mode,
+        )?;
+        Ok(bincode::serialize(&proof)?)
+    }
+}
+
+/// A running mock of the prover network. The servers are stopped when it is dropped.
+pub struct MockNetwork {
+    state: Arc<MockState>,
+    http_addr: SocketAddr,
+    #[cfg(feature = "network-v2")]
+    grpc_addr: SocketAddr,
+    shutdown: watch::Sender<bool>,
+    servers: Vec<JoinHandle<()>>,
+}
+
+impl MockNetwork {
+    /// Starts the mock network on local ports, proving requests with `prover`.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub async fn start(
+        prover: impl Prover<DefaultProverComponents> + 'static,
+        config: MockNetworkConfig,
+    ) -> io::Result<Self> {
+        let http_listener = TcpListener::bind("127.0.0.1:0").await?;
+        let http_addr = http_listener.local_addr()?;
+        let state = Arc::new(MockState {
+            config,
+            prover: Arc::new(prover),
+            http_url: format!("http://{http_addr}"),
+            calls: AtomicU64::new(0),
+            next_id: AtomicU64::new(0),
+            artifacts: Mutex::default(),
+            nonce: AtomicU64::new(0),
+            requests: Mutex::default(),
+        });
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let mut servers = Vec::new();
+
+        // The artifact store and the v1 twirp API share an HTTP server.
+        let router = axum::Router::new()
+            .route("/artifacts/:artifact", get(download_artifact).put(upload_artifact))
+            .with_state(state.clone());
+        #[cfg(feature = "network")]
+        let router = router.merge(v1::router(state.clone()));
+        let mut rx = shutdown_rx.clone();
+        servers.push(tokio::spawn(async move {
+            let shutdown = async move {
+                let _ = rx.wait_for(|stop| *stop).await;
+            };
+            if let Err(e) =
+                axum::serve(http_listener, router).with_graceful_shutdown(shutdown).await
+            {
+                tracing::error!("mock network http server failed: {e}");
+            }
+        }));
+
+        #[cfg(feature = "network-v2")]
+        let grpc_addr = {
+            let grpc_listener = TcpListener::bind("127.0.0.1:0").await?;
+            let grpc_addr = grpc_listener.local_addr()?;
+            servers.push(tokio::spawn(v2::serve(state.clone(), grpc_listener, shutdown_rx)));
+            grpc_addr
+        };
+
+        Ok(Self {
+            state,
+            http_addr,
+            #[cfg(feature = "network-v2")]
+            grpc_addr,
+            shutdown,
+            servers,
+        })
+    }
+
+    /// The RPC URL to use with [crate::NetworkProverV1].
+    pub fn rpc_url(&self) -> String {
+        format!("http://{}/", self.http_addr)
+    }
+
+    /// The RPC URL to use with [crate::NetworkProverV2].
+    #[cfg(feature = "network-v2")]
+    pub fn grpc_url(&self) -> String {
+        format!("http://{}", self.grpc_addr)
+    }
+
+    /// The URL of an artifact in the store.
+    pub fn artifact_url(&self, artifact: &str) -> String {
+        self.state.artifact_url(artifact)
+    }
+
+    /// Returns the proof request with the given identifier. v2 identifiers are hex encoded.
+    pub fn request(&self, id: &str) -> Option<MockProofRequest> {
+        self.state.request(id)
+    }
+
+    /// The number of RPCs received so far, including the failed ones.
+    pub fn rpc_count(&self) -> u64 {
+        self.state.calls.load(Ordering::SeqCst)
+    }
+
+    /// Stops the servers and waits for them to finish.
+    pub async fn shutdown(mut self) {
+        let _ = self.shutdown.send(true);
+        for server in self.servers.drain(..) {
+            let _ = server.await;
+        }
+    }
+}
+
+impl Drop for MockNetwork {
+    fn drop(&mut self) {
+        let _ = self.shutdown.send(true);
+    }
+}
+
+async fn download_artifact(
+    AxumState(state): AxumState<Arc<MockState>>,
+    Path(artifact): Path<String>,
+) -> Result<Vec<u8>, StatusCode> {
+    state.get_artifact(&artifact).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn upload_artifact(
+    AxumState(state): AxumState<Arc<MockState>>,
+    Path(artifact): Path<String>,
+    body: Bytes,
+) -> StatusCode {
+    state.put_artifact(&artifact, body.to_vec());
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockNetwork, MockNetworkConfig, MockRequestStatus};
+    use crate::{utils, MockProver, SP1Proof, SP1Stdin};
+
+    const ELF: &[u8] =
+        include_bytes!("../../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    const PRIVATE_KEY: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    fn stdin() -> SP1Stdin {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        stdin
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mock_network_v1() {
+        use crate::{network::proto::network::ProofMode, NetworkProverV1, SP1ProofKind};
+
+        utils::setup_logger();
+        let network =
+            MockNetwork::start(MockProver::new(), MockNetworkConfig::default()).await.unwrap();
+        let prover = NetworkProverV1::try_new(PRIVATE_KEY, &network.rpc_url()).unwrap();
+
+        let proof_id = prover.request_proof(ELF, stdin(), ProofMode::Compressed).await.unwrap();
+        let proof = prover.wait_proof(&proof_id, None).await.unwrap();
+        let request = network.request(&proof_id).unwrap();
+        assert_eq!(request.status, MockRequestStatus::Fulfilled);
+        assert_eq!(request.mode, SP1ProofKind::Compressed);
+        assert!(matches!(proof.proof, SP1Proof::Compressed(_)));
+
+        network.shutdown().await;
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mock_network_v1_failures() {
+        use crate::{network::proto::network::ProofMode, NetworkProverV1};
+
+        utils::setup_logger();
+
+        // A failed proof is reported as unclaimed, with the reason of the failure.
+        let config = MockNetworkConfig { fail_proofs: true, ..Default::default() };
+        let network = MockNetwork::start(MockProver::new(), config).await.unwrap();
+        let prover = NetworkProverV1::try_new(PRIVATE_KEY, &network.rpc_url()).unwrap();
+        let err = prover.prove(ELF, stdin(), ProofMode::Core, None).await.unwrap_err();
+        assert!(err.to_string().contains("failure injected"), "{err}");
+        network.shutdown().await;
+
+        // Every RPC fails.
+        let config = MockNetworkConfig { fail_every: Some(1), ..Default::default() };
+        let network = MockNetwork::start(MockProver::new(), config).await.unwrap();
+        let prover = NetworkProverV1::try_new(PRIVATE_KEY, &network.rpc_url()).unwrap();
+        assert!(prover.request_proof(ELF, stdin(), ProofMode::Core).await.is_err());
+        assert!(network.rpc_count() > 0);
+        network.shutdown().await;
+    }
+
+    #[cfg(feature = "network-v2")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mock_network_v2() {
+        use crate::{
+            network_v2::proto::network::ProofMode, NetworkProverV2, SP1ProofWithPublicValues,
+        };
+
+        utils::setup_logger();
+        let network =
+            MockNetwork::start(MockProver::new(), MockNetworkConfig::default()).await.unwrap();
+        let prover = NetworkProverV2::try_new(PRIVATE_KEY, &network.grpc_url()).unwrap();
+
+        let request_id = prover.request_proof(ELF, stdin(), ProofMode::Core, None).await.unwrap();
+        let proof: SP1ProofWithPublicValues = prover.wait_proof(&request_id, None).await.unwrap();
+        let request = network.request(&hex::encode(&request_id)).unwrap();
+        assert_eq!(request.status, MockRequestStatus::Fulfilled);
+        assert!(matches!(proof.proof, SP1Proof::Core(_)));
+
+        network.shutdown().await;
+    }
+}
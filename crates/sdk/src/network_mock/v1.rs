@@ -0,0 +1,241 @@
+//! The twirp API used by [crate::NetworkProverV1].
+
+use std::sync::Arc;
+
+use twirp::{async_trait::async_trait, axum, Context, TwirpErrorResponse};
+
+use super::{InjectedFailure, MockRequestStatus, MockState};
+use crate::{
+    network::proto::network::{
+        router as network_router, ClaimProofRequest, ClaimProofResponse, CreateProofRequest,
+        CreateProofResponse, FulfillProofRequest, FulfillProofResponse, GetNonceRequest,
+        GetNonceResponse, GetProofRequestsRequest, GetProofRequestsResponse, GetProofStatusRequest,
+        GetProofStatusResponse, GetRelayStatusRequest, GetRelayStatusResponse,
+        ModifyCpuCyclesRequest, ModifyCpuCyclesResponse, NetworkService, ProofMode, ProofStatus,
+        RelayProofRequest, RelayProofResponse, RequestedProof, SubmitProofRequest,
+        SubmitProofResponse, UnclaimProofRequest, UnclaimProofResponse, UnclaimReason, SERVICE_FQN,
+    },
+    SP1ProofKind,
+};
+
+/// The maximum number of requests returned by `GetProofRequests`.
+const MAX_PROOF_REQUESTS: usize = 10;
+
+/// The routes of the v1 API, served at the root of the HTTP server.
+pub(super) fn router(state: Arc<MockState>) -> axum::Router {
+    axum::Router::new().nest(SERVICE_FQN, network_router(MockNetworkV1(state)))
+}
+
+#[derive(Clone)]
+struct MockNetworkV1(Arc<MockState>);
+
+impl From<InjectedFailure> for TwirpErrorResponse {
+    fn from(_: InjectedFailure) -> Self {
+        twirp::unavailable("failure injected by the mock network")
+    }
+}
+
+fn proof_kind(mode: i32) -> Result<SP1ProofKind, TwirpErrorResponse> {
+    match ProofMode::try_from(mode) {
+        Ok(ProofMode::Core) => Ok(SP1ProofKind::Core),
+        Ok(ProofMode::Compressed) => Ok(SP1ProofKind::Compressed),
+        Ok(ProofMode::Plonk) => Ok(SP1ProofKind::Plonk),
+        Ok(ProofMode::Groth16) => Ok(SP1ProofKind::Groth16),
+        _ => Err(twirp::invalid_argument(format!("invalid proof mode {mode}"))),
+    }
+}
+
+fn proof_status(status: MockRequestStatus) -> ProofStatus {
+    match status {
+        MockRequestStatus::Preparing => ProofStatus::ProofPreparing,
+        MockRequestStatus::Requested => ProofStatus::ProofRequested,
+        MockRequestStatus::Claimed => ProofStatus::ProofClaimed,
+        MockRequestStatus::Fulfilled => ProofStatus::ProofFulfilled,
+        MockRequestStatus::Failed => ProofStatus::ProofUnclaimed,
+    }
+}
+
+#[async_trait]
+impl NetworkService for MockNetworkV1 {
+    async fn create_proof(
+        &self,
+        _ctx: Context,
+        req: CreateProofRequest,
+    ) -> Result<CreateProofResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        let mode = proof_kind(req.mode)?;
+        self.0.use_nonce();
+        let proof_id = self.0.new_id("proofreq");
+        let request = self.0.create_request(
+            &proof_id,
+            mode,
+            &req.circuit_version,
+            self.0.new_id("program"),
+            self.0.new_id("stdin"),
+        );
+        Ok(CreateProofResponse {
+            proof_id,
+            program_url: self.0.artifact_url(&request.program_artifact),
+            stdin_url: self.0.artifact_url(&request.stdin_artifact),
+        })
+    }
+
+    async fn submit_proof(
+        &self,
+        _ctx: Context,
+        req: SubmitProofRequest,
+    ) -> Result<SubmitProofResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        self.0.use_nonce();
+        self.0.submit(&req.proof_id).map_err(twirp::failed_precondition)?;
+        Ok(SubmitProofResponse {})
+    }
+
+    /// Claims a request. The artifact identifiers are the URLs of the artifacts in the mock
+    /// store, so that provers can download the program and stdin and upload the proof.
+    async fn claim_proof(
+        &self,
+        _ctx: Context,
+        req: ClaimProofRequest,
+    ) -> Result<ClaimProofResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        self.0.use_nonce();
+        let request = self
+            .0
+            .transition(&req.proof_id, &[MockRequestStatus::Requested], |r| {
+                r.status = MockRequestStatus::Claimed
+            })
+            .map_err(twirp::failed_precondition)?;
+        Ok(ClaimProofResponse {
+            program_artifact_id: self.0.artifact_url(&request.program_artifact),
+            stdin_artifact_id: self.0.artifact_url(&request.stdin_artifact),
+            proof_artifact_id: self.0.artifact_url(&request.proof_artifact),
+        })
+    }
+
+    async fn unclaim_proof(
+        &self,
+        _ctx: Context,
+        req: UnclaimProofRequest,
+    ) -> Result<UnclaimProofResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        self.0.use_nonce();
+        let reason = UnclaimReason::try_from(req.reason).unwrap_or(UnclaimReason::Unspecified);
+        self.0
+            .transition(&req.proof_id, &[MockRequestStatus::Claimed], |r| {
+                r.status = MockRequestStatus::Failed;
+                r.error = Some(format!("{}: {}", reason.as_str_name(), req.description));
+            })
+            .map_err(twirp::failed_precondition)?;
+        Ok(UnclaimProofResponse {})
+    }
+
+    async fn modify_cpu_cycles(
+        &self,
+        _ctx: Context,
+        req: ModifyCpuCyclesRequest,
+    ) -> Result<ModifyCpuCyclesResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        self.0.use_nonce();
+        self.0
+            .transition(&req.proof_id, &[MockRequestStatus::Claimed], |_| {})
+            .map_err(twirp::failed_precondition)?;
+        Ok(ModifyCpuCyclesResponse {})
+    }
+
+    async fn fulfill_proof(
+        &self,
+        _ctx: Context,
+        req: FulfillProofRequest,
+    ) -> Result<FulfillProofResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        self.0.use_nonce();
+        let request = self
+            .0
+            .request(&req.proof_id)
+            .ok_or_else(|| twirp::not_found(format!("unknown request {}", req.proof_id)))?;
+        if self.0.get_artifact(&request.proof_artifact).is_none() {
+            return Err(twirp::failed_precondition("the proof has not been uploaded"));
+        }
+        self.0
+            .transition(&req.proof_id, &[MockRequestStatus::Claimed], |r| {
+                r.status = MockRequestStatus::Fulfilled
+            })
+            .map_err(twirp::failed_precondition)?;
+        Ok(FulfillProofResponse { proving_seconds: 0 })
+    }
+
+    async fn relay_proof(
+        &self,
+        _ctx: Context,
+        _req: RelayProofRequest,
+    ) -> Result<RelayProofResponse, TwirpErrorResponse> {
+        Err(twirp::unimplemented("the mock network does not relay proofs"))
+    }
+
+    async fn get_nonce(
+        &self,
+        _ctx: Context,
+        _req: GetNonceRequest,
+    ) -> Result<GetNonceResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        Ok(GetNonceResponse { nonce: self.0.nonce() })
+    }
+
+    async fn get_proof_status(
+        &self,
+        _ctx: Context,
+        req: GetProofStatusRequest,
+    ) -> Result<GetProofStatusResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        let request = self
+            .0
+            .request(&req.proof_id)
+            .ok_or_else(|| twirp::not_found(format!("unknown request {}", req.proof_id)))?;
+        let failed = request.status == MockRequestStatus::Failed;
+        Ok(GetProofStatusResponse {
+            status: proof_status(request.status).into(),
+            proof_url: (request.status == MockRequestStatus::Fulfilled)
+                .then(|| self.0.artifact_url(&request.proof_artifact)),
+            unclaim_reason: failed.then_some(UnclaimReason::Abandoned.into()),
+            unclaim_description: if failed { request.error } else { None },
+        })
+    }
+
+    async fn get_proof_requests(
+        &self,
+        _ctx: Context,
+        req: GetProofRequestsRequest,
+    ) -> Result<GetProofRequestsResponse, TwirpErrorResponse> {
+        self.0.rpc().await?;
+        let status = match ProofStatus::try_from(req.status) {
+            Ok(ProofStatus::ProofPreparing) => MockRequestStatus::Preparing,
+            Ok(ProofStatus::ProofRequested) => MockRequestStatus::Requested,
+            Ok(ProofStatus::ProofClaimed) => MockRequestStatus::Claimed,
+            Ok(ProofStatus::ProofUnclaimed) => MockRequestStatus::Failed,
+            Ok(ProofStatus::ProofFulfilled) => MockRequestStatus::Fulfilled,
+            _ => return Err(twirp::invalid_argument(format!("invalid status {}", req.status))),
+        };
+        let requests = self.0.requests_with_status(status, req.circuit_version.as_deref());
+        let proofs = requests
+            .into_iter()
+            .rev()
+            .take(MAX_PROOF_REQUESTS)
+            .map(|(proof_id, request)| RequestedProof {
+                proof_id,
+                mode: ProofMode::from(request.mode).into(),
+                requester: request.requester,
+                circuit_version: request.version,
+            })
+            .collect();
+        Ok(GetProofRequestsResponse { proofs })
+    }
+
+    async fn get_relay_status(
+        &self,
+        _ctx: Context,
+        _req: GetRelayStatusRequest,
+    ) -> Result<GetRelayStatusResponse, TwirpErrorResponse> {
+        Err(twirp::unimplemented("the mock network does not relay proofs"))
+    }
+}
@@ -0,0 +1,234 @@
+//! The gRPC API used by [crate::NetworkProverV2].
+
+use std::sync::Arc;
+
+use tokio::{net::TcpListener, sync::watch};
+use tonic::{
+    transport::{server::TcpIncoming, Server},
+    Request, Response, Status,
+};
+
+use super::{InjectedFailure, MockRequestStatus, MockState};
+use crate::{
+    network_v2::proto::{
+        artifact::{
+            artifact_store_server::{ArtifactStore, ArtifactStoreServer},
+            CreateArtifactRequest, CreateArtifactResponse,
+        },
+        network::{
+            prover_network_server::{ProverNetwork, ProverNetworkServer},
+            FulfillProofRequest, FulfillProofResponse, FulfillProofResponseBody, GetBalanceRequest,
+            GetBalanceResponse, GetFilteredProofRequestsRequest, GetFilteredProofRequestsResponse,
+            GetNonceRequest, GetNonceResponse, GetProofRequestStatusRequest,
+            GetProofRequestStatusResponse, ProofMode, ProofRequest, ProofStatus, ProofStrategy,
+            RequestProofRequest, RequestProofResponse, RequestProofResponseBody,
+        },
+    },
+    SP1ProofKind,
+};
+
+/// The balance reported for every account.
+const BALANCE: &str = "1000000000000000000";
+
+/// Serves the v2 API on `listener` until `shutdown` is set.
+pub(super) async fn serve(
+    state: Arc<MockState>,
+    listener: TcpListener,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let incoming = match TcpIncoming::from_listener(listener, true, None) {
+        Ok(incoming) => incoming,
+        Err(e) => {
+            tracing::error!("mock network grpc server failed: {e}");
+            return;
+        }
+    };
+    let service = MockNetworkV2(state);
+    let result = Server::builder()
+        .add_service(ProverNetworkServer::new(service.clone()))
+        .add_service(ArtifactStoreServer::new(service))
+        .serve_with_incoming_shutdown(incoming, async move {
+            let _ = shutdown.wait_for(|stop| *stop).await;
+        })
+        .await;
+    if let Err(e) = result {
+        tracing::error!("mock network grpc server failed: {e}");
+    }
+}
+
+#[derive(Clone)]
+struct MockNetworkV2(Arc<MockState>);
+
+impl From<InjectedFailure> for Status {
+    fn from(_: InjectedFailure) -> Self {
+        Status::unavailable("failure injected by the mock network")
+    }
+}
+
+fn proof_kind(mode: i32) -> Result<SP1ProofKind, Status> {
+    match ProofMode::try_from(mode) {
+        Ok(ProofMode::Core) => Ok(SP1ProofKind::Core),
+        Ok(ProofMode::Compressed) => Ok(SP1ProofKind::Compressed),
+        Ok(ProofMode::Plonk) => Ok(SP1ProofKind::Plonk),
+        Ok(ProofMode::Groth16) => Ok(SP1ProofKind::Groth16),
+        _ => Err(Status::invalid_argument(format!("invalid proof mode {mode}"))),
+    }
+}
+
+fn proof_status(status: MockRequestStatus) -> ProofStatus {
+    match status {
+        MockRequestStatus::Requested => ProofStatus::Requested,
+        MockRequestStatus::Claimed => ProofStatus::Assigned,
+        MockRequestStatus::Fulfilled => ProofStatus::Fulfilled,
+        MockRequestStatus::Preparing | MockRequestStatus::Failed => ProofStatus::UnspecifiedStatus,
+    }
+}
+
+/// Returns the name of the artifact stored at `uri`, which must have been returned by
+/// `CreateArtifact`.
+fn artifact_name(uri: &str) -> Result<String, Status> {
+    uri.rsplit_once("/artifacts/")
+        .map(|(_, artifact)| artifact.to_string())
+        .ok_or_else(|| Status::invalid_argument(format!("unknown artifact {uri}")))
+}
+
+#[tonic::async_trait]
+impl ProverNetwork for MockNetworkV2 {
+    async fn request_proof(
+        &self,
+        request: Request<RequestProofRequest>,
+    ) -> Result<Response<RequestProofResponse>, Status> {
+        self.0.rpc().await?;
+        let body = request
+            .into_inner()
+            .body
+            .ok_or_else(|| Status::invalid_argument("missing request body"))?;
+        let mode = proof_kind(body.mode)?;
+        let program_artifact = artifact_name(&body.program_uri)?;
+        let stdin_artifact = artifact_name(&body.stdin_uri)?;
+        self.0.use_nonce();
+
+        let request_id = self.0.new_id("proofreq").into_bytes();
+        let id = hex::encode(&request_id);
+        self.0.create_request(&id, mode, &body.version, program_artifact, stdin_artifact);
+        self.0.submit(&id).map_err(Status::failed_precondition)?;
+        Ok(Response::new(RequestProofResponse {
+            tx_hash: request_id.clone(),
+            body: Some(RequestProofResponseBody { request_id }),
+        }))
+    }
+
+    async fn fulfill_proof(
+        &self,
+        request: Request<FulfillProofRequest>,
+    ) -> Result<Response<FulfillProofResponse>, Status> {
+        self.0.rpc().await?;
+        let body = request
+            .into_inner()
+            .body
+            .ok_or_else(|| Status::invalid_argument("missing request body"))?;
+        self.0.use_nonce();
+        let id = hex::encode(&body.request_id);
+        self.0
+            .transition(&id, &[MockRequestStatus::Requested, MockRequestStatus::Claimed], |r| {
+                self.0.put_artifact(&r.proof_artifact, body.proof);
+                r.status = MockRequestStatus::Fulfilled;
+            })
+            .map_err(Status::failed_precondition)?;
+        Ok(Response::new(FulfillProofResponse {
+            tx_hash: body.request_id,
+            body: Some(FulfillProofResponseBody {}),
+        }))
+    }
+
+    async fn get_filtered_proof_requests(
+        &self,
+        request: Request<GetFilteredProofRequestsRequest>,
+    ) -> Result<Response<GetFilteredProofRequestsResponse>, Status> {
+        self.0.rpc().await?;
+        let request = request.into_inner();
+        let status = match ProofStatus::try_from(request.status) {
+            Ok(ProofStatus::Requested) => MockRequestStatus::Requested,
+            Ok(ProofStatus::Assigned) => MockRequestStatus::Claimed,
+            Ok(ProofStatus::Fulfilled) => MockRequestStatus::Fulfilled,
+            _ => {
+                return Err(Status::invalid_argument(format!("invalid status {}", request.status)))
+            }
+        };
+        let version = (!request.version.is_empty()).then_some(request.version.as_str());
+        let requests = self
+            .0
+            .requests_with_status(status, version)
+            .into_iter()
+            .map(|(request_id, r)| ProofRequest {
+                request_id,
+                version: r.version,
+                mode: ProofMode::from(r.mode).into(),
+                strategy: ProofStrategy::Hosted.into(),
+                program_uri: self.0.artifact_url(&r.program_artifact),
+                stdin_uri: self.0.artifact_url(&r.stdin_artifact),
+                deadline: 0,
+                cycle_limit: 0,
+                status: proof_status(r.status).into(),
+                requester: r.requester,
+            })
+            .collect();
+        Ok(Response::new(GetFilteredProofRequestsResponse { requests }))
+    }
+
+    async fn get_proof_request_status(
+        &self,
+        request: Request<GetProofRequestStatusRequest>,
+    ) -> Result<Response<GetProofRequestStatusResponse>, Status> {
+        self.0.rpc().await?;
+        let request_id = request.into_inner().request_id;
+        let id = hex::encode(&request_id);
+        let request = self
+            .0
+            .request(&id)
+            .ok_or_else(|| Status::not_found(format!("unknown request {id}")))?;
+        let fulfilled = request.status == MockRequestStatus::Fulfilled;
+        let failed = request.status == MockRequestStatus::Failed;
+        Ok(Response::new(GetProofRequestStatusResponse {
+            status: proof_status(request.status).into(),
+            request_tx_hash: request_id.clone(),
+            fulfill_tx_hash: fulfilled.then_some(request_id),
+            proof_uri: fulfilled.then(|| self.0.artifact_url(&request.proof_artifact)),
+            error_code: failed.then_some(1),
+            error_description: if failed { request.error } else { None },
+        }))
+    }
+
+    async fn get_balance(
+        &self,
+        _request: Request<GetBalanceRequest>,
+    ) -> Result<Response<GetBalanceResponse>, Status> {
+        self.0.rpc().await?;
+        Ok(Response::new(GetBalanceResponse { amount: BALANCE.to_string() }))
+    }
+
+    async fn get_nonce(
+        &self,
+        _request: Request<GetNonceRequest>,
+    ) -> Result<Response<GetNonceResponse>, Status> {
+        self.0.rpc().await?;
+        Ok(Response::new(GetNonceResponse { nonce: self.0.nonce() }))
+    }
+}
+
+#[tonic::async_trait]
+impl ArtifactStore for MockNetworkV2 {
+    /// Creates an artifact. The URI and the presigned URL are both the URL of the artifact in the
+    /// HTTP store.
+    async fn create_artifact(
+        &self,
+        _request: Request<CreateArtifactRequest>,
+    ) -> Result<Response<CreateArtifactResponse>, Status> {
+        self.0.rpc().await?;
+        let url = self.0.artifact_url(&self.0.new_id("artifact"));
+        Ok(Response::new(CreateArtifactResponse {
+            artifact_uri: url.clone(),
+            artifact_presigned_url: url,
+        }))
+    }
+}
@@ -0,0 +1,475 @@
+//! A scheduler for graphs of proofs that verify each other.
+//!
+//! A [ProofPipeline] is a directed acyclic graph of programs. Every node has an ELF, the nodes
+//! whose proofs it verifies with `verify_sp1_proof`, and an input builder that writes its stdin
+//! from the proofs of those dependencies. [ProofPipeline::run] proves the nodes in dependency
+//! order, proving up to [ProofPipeline::max_concurrency] independent nodes in parallel, and
+//! writes the compressed proofs of the dependencies of a node to its stdin with
+//! [SP1Stdin::write_proof], in the order they were declared in. The root, the only node no other node depends on, is proven in the requested mode.
+//!
+//! Before a node with dependencies is proven, it is executed to check that it verifies exactly
+//! the proofs of its dependencies, with their verifying key hashes and public values digests.
+//! Mistakes in the input builders are thus reported before any recursion proving is spent on
+//! them.
+//!
+//! ```no_run
+//! use sp1_sdk::{pipeline::ProofPipeline, HashableKey, ProverClient, SP1ProofKind, SP1Stdin};
+//!
+//! # const LEAF_ELF: &[u8] = &[];
+//! # const AGGREGATION_ELF: &[u8] = &[];
+//! let client = ProverClient::new();
+//! let mut pipeline = ProofPipeline::new();
+//! let a = pipeline.add("a", LEAF_ELF, &[], |_| {
+//!     let mut stdin = SP1Stdin::new();
+//!     stdin.write(&10u32);
+//!     Ok(stdin)
+//! });
+//! let b = pipeline.add("b", LEAF_ELF, &[], |_| {
+//!     let mut stdin = SP1Stdin::new();
+//!     stdin.write(&20u32);
+//!     Ok(stdin)
+//! });
+//! let root = pipeline.add("root", AGGREGATION_ELF, &[a, b], |deps| {
+//!     let mut stdin = SP1Stdin::new();
+//!     stdin.write(&deps.iter().map(|(_, vk)| vk.hash_u32()).collect::<Vec<_>>());
+//!     stdin.write(&deps.iter().map(|(p, _)| p.public_values.to_vec()).collect::<Vec<_>>());
+//!     Ok(stdin)
+//! });
+//! let proofs = pipeline.run(&client, SP1ProofKind::Plonk).unwrap();
+//! let (root_proof, root_vk) = proofs.get(root);
+//! client.verify(root_proof, root_vk).unwrap();
+//! ```
+
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use sha2::{Digest, Sha256};
+use sp1_core_executor::{
+    subproof::SubproofVerifier, Executor, Program, SP1Context, SP1ReduceProof,
+};
+use sp1_prover::HashableKey;
+use sp1_stark::{
+    baby_bear_poseidon2::BabyBearPoseidon2, MachineVerificationError, SP1CoreOpts,
+    StarkVerifyingKey,
+};
+
+use crate::{
+    provers::ProverType, ProverClient, SP1Proof, SP1ProofKind, SP1ProofWithPublicValues, SP1Stdin,
+    SP1VerifyingKey,
+};
+
+/// The identifier of a node of a [ProofPipeline].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// A proof of a node together with the verifying key of its program.
+pub type NodeProof = (SP1ProofWithPublicValues, SP1VerifyingKey);
+
+/// Builds the stdin of a node from the proofs of its dependencies, in the order they were declared
+/// in. The proofs themselves are written to the stdin by the pipeline.
+type InputBuilder<'a> = Box<dyn Fn(&[&NodeProof]) -> Result<SP1Stdin> + Send + Sync + 'a>;
+
+struct Node<'a> {
+    name: String,
+    elf: &'a [u8],
+    deps: Vec<NodeId>,
+    input: InputBuilder<'a>,
+}
+
+/// A directed acyclic graph of programs whose proofs verify each other.
+#[derive(Default)]
+pub struct ProofPipeline<'a> {
+    nodes: Vec<Node<'a>>,
+    max_concurrency: Option<usize>,
+}
+
+impl<'a> ProofPipeline<'a> {
+    /// Creates an empty [ProofPipeline].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of nodes proven at the same time.
+    ///
+    /// Defaults to 1 for the provers running on this machine, whose proofs already use all of its
+    /// cores and memory, and to no limit for the network and remote provers.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        assert!(max_concurrency > 0, "max_concurrency must be positive");
+        self.max_concurrency = Some(max_concurrency);
+        self
+    }
+
+    /// Adds a node proving `elf` on the stdin returned by `input`, which is given the proofs of
+    /// `deps`. The program must verify the proofs of `deps` with `verify_sp1_proof`, in order.
+    ///
+    /// The dependencies must have been added before, so the graph is always acyclic.
+    pub fn add(
+        &mut self,
+        name: impl Into<String>,
+        elf: &'a [u8],
+        deps: &[NodeId],
+        input: impl Fn(&[&NodeProof]) -> Result<SP1Stdin> + Send + Sync + 'a,
+    ) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        assert!(deps.iter().all(|dep| *dep < id), "dependencies must be added before the node");
+        self.nodes.push(Node {
+            name: name.into(),
+            elf,
+            deps: deps.to_vec(),
+            input: Box::new(input),
+        });
+        id
+    }
+
+    /// Returns the root of the graph, the only node no other node depends on.
+    pub fn root(&self) -> Result<NodeId> {
+        let mut is_dep = vec![false; self.nodes.len()];
+        for node in &self.nodes {
+            for dep in &node.deps {
+                is_dep[dep.0] = true;
+            }
+        }
+        let roots = (0..self.nodes.len()).filter(|&i| !is_dep[i]).collect::<Vec<_>>();
+        match roots.as_slice() {
+            [] => bail!("the pipeline is empty"),
+            [root] => Ok(NodeId(*root)),
+            _ => {
+                let names = roots.iter().map(|&i| self.nodes[i].name.as_str()).collect::<Vec<_>>();
+                bail!("the pipeline has several roots: {}", names.join(", "))
+            }
+        }
+    }
+
+    /// Proves every node with `client`, the root in mode `root_kind` and the other nodes in the
+    /// compressed mode.
+    ///
+    /// The nodes are proven in waves: a wave holds the nodes whose dependencies have all been
+    /// proven, and up to [Self::max_concurrency] of its nodes are proven in parallel.
+    pub fn run(self, client: &ProverClient, root_kind: SP1ProofKind) -> Result<PipelineProofs> {
+        let root = self.root()?;
+        let max_concurrency = self.max_concurrency.unwrap_or(match client.prover.id() {
+            ProverType::Cpu | ProverType::Cuda | ProverType::Mock => 1,
+            ProverType::Network | ProverType::Remote => usize::MAX,
+        });
+
+        // The keys are set up once per distinct program.
+        let mut keys: Vec<(&[u8], _)> = Vec::new();
+        let mut node_keys = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let index = match keys.iter().position(|(elf, _)| *elf == node.elf) {
+                Some(index) => index,
+                None => {
+                    keys.push((node.elf, client.setup(node.elf)));
+                    keys.len() - 1
+                }
+            };
+            node_keys.push(index);
+        }
+
+        // The wave of a node is one more than the last wave of its dependencies.
+        let mut waves = vec![0usize; self.nodes.len()];
+        for (i, node) in self.nodes.iter().enumerate() {
+            waves[i] = node.deps.iter().map(|dep| waves[dep.0] + 1).max().unwrap_or(0);
+        }
+
+        let mut proofs: Vec<Option<NodeProof>> = (0..self.nodes.len()).map(|_| None).collect();
+        for wave in 0..=waves[root.0] {
+            let ids = (0..self.nodes.len()).filter(|&i| waves[i] == wave).collect::<Vec<_>>();
+
+            // Build and check the inputs of the wave before proving any of them.
+            let mut stdins = Vec::with_capacity(ids.len());
+            for &i in &ids {
+                let node = &self.nodes[i];
+                let deps = node
+                    .deps
+                    .iter()
+                    .map(|dep| proofs[dep.0].as_ref().expect("dependency was proven"))
+                    .collect::<Vec<_>>();
+                let stdin = node_stdin(node, &deps)
+                    .with_context(|| format!("failed to build the input of {}", node.name))?;
+                stdins.push(stdin);
+            }
+
+            // The wave is proven in batches of at most `max_concurrency` nodes.
+            let mut jobs = ids.into_iter().zip(stdins).peekable();
+            while jobs.peek().is_some() {
+                let batch = jobs.by_ref().take(max_concurrency).collect::<Vec<_>>();
+                let results = thread::scope(|s| {
+                    let handles = batch
+                        .into_iter()
+                        .map(|(i, stdin)| {
+                            let (pk, vk) = &keys[node_keys[i]].1;
+                            let kind =
+                                if i == root.0 { root_kind } else { SP1ProofKind::Compressed };
+                            let handle = s.spawn(move || {
+                                let proof = client.prove(pk, stdin);
+                                let proof = match kind {
+                                    SP1ProofKind::Core => proof.core(),
+                                    SP1ProofKind::Compressed => proof.compressed(),
+                                    SP1ProofKind::Plonk => proof.plonk(),
+                                    SP1ProofKind::Groth16 => proof.groth16(),
+                                };
+                                proof.run().map(|proof| (proof, vk.clone()))
+                            });
+                            (i, handle)
+                        })
+                        .collect::<Vec<_>>();
+                    handles
+                        .into_iter()
+                        .map(|(i, handle)| {
+                            let result =
+                                handle.join().unwrap_or_else(|_| Err(anyhow!("proving panicked")));
+                            (i, result)
+                        })
+                        .collect::<Vec<_>>()
+                });
+                for (i, result) in results {
+                    let proof = result
+                        .with_context(|| format!("failed to prove {}", self.nodes[i].name))?;
+                    proofs[i] = Some(proof);
+                }
+            }
+        }
+
+        let proofs =
+            proofs.into_iter().map(|proof| proof.expect("every node was proven")).collect();
+        Ok(PipelineProofs { proofs, root })
+    }
+}
+
+/// The proofs of the nodes of a [ProofPipeline].
+pub struct PipelineProofs {
+    proofs: Vec<NodeProof>,
+    root: NodeId,
+}
+
+impl PipelineProofs {
+    /// Returns the proof of a node and the verifying key of its program.
+    pub fn get(&self, id: NodeId) -> &NodeProof {
+        &self.proofs[id.0]
+    }
+
+    /// Returns the proof of the root and the verifying key of its program.
+    pub fn root(&self) -> &NodeProof {
+        self.get(self.root)
+    }
+
+    /// Consumes the proofs, returning them in the order the nodes were added in.
+    pub fn into_proofs(self) -> Vec<NodeProof> {
+        self.proofs
+    }
+}
+
+/// Builds the stdin of `node`, writes the proofs of its dependencies and checks that the program
+/// verifies exactly these proofs.
+fn node_stdin(node: &Node<'_>, deps: &[&NodeProof]) -> Result<SP1Stdin> {
+    let mut stdin = (node.input)(deps)?;
+    ensure!(stdin.proofs.is_empty(), "the input builder must not write proofs");
+    if deps.is_empty() {
+        return Ok(stdin);
+    }
+
+    let mut expected = Vec::with_capacity(deps.len());
+    for (proof, vk) in deps {
+        let SP1Proof::Compressed(reduce_proof) = &proof.proof else {
+            bail!("dependencies must be proven in the compressed mode");
+        };
+        stdin.write_proof(*reduce_proof.clone(), vk.vk.clone());
+        expected.push((vk.hash_u32(), public_values_digest(proof)));
+    }
+    check_linkage(node.elf, &stdin, expected)?;
+    Ok(stdin)
+}
+
+/// The words of the SHA-256 digest of the public values of a proof, as passed to
+/// `verify_sp1_proof`.
+fn public_values_digest(proof: &SP1ProofWithPublicValues) -> [u32; 8] {
+    let digest = Sha256::digest(proof.public_values.as_slice());
+    let mut words = [0u32; 8];
+    for (word, chunk) in words.iter_mut().zip(digest.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+/// Executes `elf` on `stdin`, checking that its calls to `verify_sp1_proof` match the expected
+/// verifying key hashes and public values digests, in order, and that every proof is verified.
+fn check_linkage(elf: &[u8], stdin: &SP1Stdin, expected: Vec<([u32; 8], [u32; 8])>) -> Result<()> {
+    let num_proofs = expected.len();
+    let verifier = Arc::new(LinkageVerifier { expected, next: Mutex::new(0) });
+    let program = Program::from(elf).map_err(|e| anyhow!("invalid program: {e}"))?;
+    let context = SP1Context::builder().subproof_verifier(verifier.clone()).build();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut runtime = Executor::with_context(program, SP1CoreOpts::default(), context);
+        runtime.write_vecs(&stdin.buffer);
+        for (proof, vk) in &stdin.proofs {
+            runtime.write_proof(proof.clone(), vk.clone());
+        }
+        runtime.run_fast()
+    }));
+    let verified = *verifier.next.lock().unwrap();
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => bail!("execution failed: {e}"),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_default();
+            bail!("execution panicked: {message}");
+        }
+    }
+    ensure!(
+        verified == num_proofs,
+        "the program verified {verified} proofs, but has {num_proofs} dependencies"
+    );
+    Ok(())
+}
+
+/// A [SubproofVerifier] that only checks that the verified proofs are the expected ones. The
+/// proofs themselves are verified when the node is proven.
+struct LinkageVerifier {
+    expected: Vec<([u32; 8], [u32; 8])>,
+    next: Mutex<usize>,
+}
+
+impl SubproofVerifier for LinkageVerifier {
+    fn verify_deferred_proof(
+        &self,
+        _proof: &SP1ReduceProof<BabyBearPoseidon2>,
+        _vk: &StarkVerifyingKey<BabyBearPoseidon2>,
+        vk_hash: [u32; 8],
+        committed_value_digest: [u32; 8],
+    ) -> Result<(), MachineVerificationError<BabyBearPoseidon2>> {
+        let mut next = self.next.lock().unwrap();
+        let Some(&(expected_vk_hash, expected_digest)) = self.expected.get(*next) else {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "more proofs verified than dependencies",
+            ));
+        };
+        *next += 1;
+        if vk_hash != expected_vk_hash {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "the vkey hash does not match the dependency",
+            ));
+        }
+        if committed_value_digest != expected_digest {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "the public values digest does not match the dependency",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{node_stdin, NodeProof, ProofPipeline};
+    use crate::{
+        aggregation::AGGREGATION_ELF, utils, HashableKey, ProverClient, SP1ProofKind, SP1Stdin,
+    };
+
+    const FIBONACCI_ELF: &[u8] =
+        include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+
+    fn fibonacci_stdin(n: u32) -> SP1Stdin {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&n);
+        stdin
+    }
+
+    #[test]
+    fn test_pipeline_roots() {
+        assert!(ProofPipeline::new().root().is_err());
+
+        let mut pipeline = ProofPipeline::new();
+        let a = pipeline.add("a", FIBONACCI_ELF, &[], |_| Ok(fibonacci_stdin(10)));
+        pipeline.add("b", FIBONACCI_ELF, &[], |_| Ok(fibonacci_stdin(20)));
+        let err = pipeline.root().unwrap_err();
+        assert_eq!(err.to_string(), "the pipeline has several roots: a, b");
+
+        pipeline.add("root", AGGREGATION_ELF, &[a], |_| Ok(SP1Stdin::new()));
+        let err = pipeline.root().unwrap_err();
+        assert_eq!(err.to_string(), "the pipeline has several roots: b, root");
+
+        let mut pipeline = ProofPipeline::new();
+        let a = pipeline.add("a", FIBONACCI_ELF, &[], |_| Ok(fibonacci_stdin(10)));
+        let b = pipeline.add("b", FIBONACCI_ELF, &[], |_| Ok(fibonacci_stdin(20)));
+        let root = pipeline.add("root", AGGREGATION_ELF, &[a, b], |_| Ok(SP1Stdin::new()));
+        assert_eq!(pipeline.root().unwrap(), root);
+    }
+
+    #[test]
+    fn test_pipeline_run_mock() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let mut pipeline = ProofPipeline::new().max_concurrency(2);
+        let leaves = [10, 20, 30].map(|n| {
+            pipeline.add(format!("fib-{n}"), FIBONACCI_ELF, &[], move |_| Ok(fibonacci_stdin(n)))
+        });
+        pipeline.add("root", AGGREGATION_ELF, &leaves, |deps| {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(&deps.iter().map(|(_, vk)| vk.hash_u32()).collect::<Vec<_>>());
+            stdin.write(&deps.iter().map(|(p, _)| p.public_values.to_vec()).collect::<Vec<_>>());
+            Ok(stdin)
+        });
+
+        let proofs = pipeline.run(&client, SP1ProofKind::Plonk).unwrap();
+        assert!(matches!(SP1ProofKind::from(&proofs.root().0.proof), SP1ProofKind::Plonk));
+        for leaf in leaves {
+            let kind = SP1ProofKind::from(&proofs.get(leaf).0.proof);
+            assert!(matches!(kind, SP1ProofKind::Compressed));
+        }
+    }
+
+    #[test]
+    fn test_pipeline_linkage() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let (pk, vk) = client.setup(FIBONACCI_ELF);
+        let deps = [10, 20].map(|n| {
+            (client.prove(&pk, fibonacci_stdin(n)).compressed().run().unwrap(), vk.clone())
+        });
+        let deps = deps.iter().collect::<Vec<_>>();
+
+        // The aggregation program verifies the proofs of the vkeys and public values it is given.
+        let mut pipeline = ProofPipeline::new();
+        let aggregate = |swap: bool, len: usize| {
+            move |deps: &[&NodeProof]| {
+                let deps = &deps[..len];
+                let mut stdin = SP1Stdin::new();
+                let vkeys = deps.iter().map(|(_, vk)| vk.hash_u32()).collect::<Vec<_>>();
+                let mut public_values =
+                    deps.iter().map(|(proof, _)| proof.public_values.to_vec()).collect::<Vec<_>>();
+                if swap {
+                    public_values.reverse();
+                }
+                stdin.write(&vkeys);
+                stdin.write(&public_values);
+                Ok(stdin)
+            }
+        };
+        pipeline.add("linked", AGGREGATION_ELF, &[], aggregate(false, 2));
+        pipeline.add("swapped", AGGREGATION_ELF, &[], aggregate(true, 2));
+        pipeline.add("partial", AGGREGATION_ELF, &[], aggregate(false, 1));
+
+        let stdin = node_stdin(&pipeline.nodes[0], &deps).unwrap();
+        assert_eq!(stdin.proofs.len(), 2);
+        let err = node_stdin(&pipeline.nodes[1], &deps).unwrap_err();
+        assert!(format!("{err:#}").contains("the public values digest does not match"));
+        let err = node_stdin(&pipeline.nodes[2], &deps).unwrap_err();
+        assert_eq!(err.to_string(), "the program verified 1 proofs, but has 2 dependencies");
+
+        // Proofs that cannot be verified inside the zkVM are rejected.
+        let core = (client.prove(&pk, fibonacci_stdin(10)).run().unwrap(), vk.clone());
+        let err = node_stdin(&pipeline.nodes[0], &[&core, deps[1]]).unwrap_err();
+        assert!(err.to_string().contains("compressed"));
+        assert!(matches!(SP1ProofKind::from(&core.0.proof), SP1ProofKind::Core));
+    }
+}
@@ -1,39 +1,71 @@
-use anyhow::Result;
-use sp1_core_executor::SP1Context;
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{mpsc, Arc},
+    thread,
+};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sp1_core_executor::{ExecutionReport, ProofPhase, SP1Context, SP1ReduceProof};
 use sp1_core_machine::io::SP1Stdin;
-use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+use sp1_primitives::io::SP1PublicValues;
+use sp1_prover::{
+    components::DefaultProverComponents,
+    job::{ProofJob, ProofStage},
+    OuterSC, SP1CoreProof, SP1Prover,
+};
+use sp1_stark::SP1ProverOpts;
 
-use crate::install::try_install_circuit_artifacts;
+use crate::install::{
+    ark_groth16_circuit_artifacts_dir, default_circuits_dir, try_install_circuit_artifacts_in,
+};
 use crate::{
     provers::ProofOpts, Prover, SP1Proof, SP1ProofKind, SP1ProofWithPublicValues, SP1ProvingKey,
     SP1VerifyingKey,
 };
 
-use super::ProverType;
+use super::{pool, ProverType};
 
 /// An implementation of [crate::ProverClient] that can generate end-to-end proofs locally.
+///
+/// Cloning a [CpuProver] is cheap and shares the underlying [SP1Prover].
+#[derive(Clone)]
 pub struct CpuProver {
-    prover: SP1Prover<DefaultProverComponents>,
+    prover: Arc<SP1Prover<DefaultProverComponents>>,
+    circuits_dir: Option<PathBuf>,
 }
 
 impl CpuProver {
     /// Creates a new [LocalProver].
     pub fn new() -> Self {
-        let prover = SP1Prover::new();
-        Self { prover }
+        Self::from_prover(SP1Prover::new())
     }
 
     /// Creates a new [LocalProver] from an existing [SP1Prover].
     pub fn from_prover(prover: SP1Prover<DefaultProverComponents>) -> Self {
-        Self { prover }
+        Self { prover: Arc::new(prover), circuits_dir: None }
+    }
+
+    /// Sets the directory under which the Plonk and Groth16 circuit artifacts are installed.
+    ///
+    /// Defaults to `~/.sp1/circuits`.
+    pub fn with_circuits_dir(mut self, circuits_dir: impl Into<PathBuf>) -> Self {
+        self.circuits_dir = Some(circuits_dir.into());
+        self
     }
 }
 
+#[async_trait]
 impl Prover<DefaultProverComponents> for CpuProver {
     fn id(&self) -> ProverType {
         ProverType::Cpu
     }
 
+    fn circuits_dir(&self) -> PathBuf {
+        self.circuits_dir.clone().unwrap_or_else(default_circuits_dir)
+    }
+
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
         self.prover.setup(elf)
     }
@@ -50,10 +82,43 @@ impl Prover<DefaultProverComponents> for CpuProver {
         context: SP1Context<'a>,
         kind: SP1ProofKind,
     ) -> Result<SP1ProofWithPublicValues> {
+        let progress = context.progress.clone();
+
+        // Open the job directory, if any, and find the stage to resume from. Stages after the
+        // one needed for `kind` are ignored.
+        let mut job = opts
+            .job_dir
+            .as_ref()
+            .map(|dir| ProofJob::open(dir, &pk.elf, &stdin, opts.sp1_prover_opts))
+            .transpose()?;
+        let target = match kind {
+            SP1ProofKind::Core => ProofStage::Core,
+            SP1ProofKind::Compressed => ProofStage::Compress,
+            SP1ProofKind::Plonk | SP1ProofKind::Groth16 => ProofStage::Wrap,
+        };
+        let resumed = job.as_ref().and_then(|job| job.manifest().stage).map(|s| s.min(target));
+
         // Generate the core proof.
-        let proof: sp1_prover::SP1ProofWithMetadata<sp1_prover::SP1CoreProofData> =
-            self.prover.prove_core(pk, &stdin, opts.sp1_prover_opts, context)?;
+        let (proof, public_values) = match (&job, resumed) {
+            (_, None) => {
+                let proof = self.prover.prove_core(pk, &stdin, opts.sp1_prover_opts, context)?;
+                if let Some(job) = &mut job {
+                    job.save_public_values(&proof.public_values)?;
+                    job.save(ProofStage::Core, &proof)?;
+                }
+                let public_values = proof.public_values.clone();
+                (Some(proof), public_values)
+            }
+            (Some(job), Some(ProofStage::Core)) => {
+                let proof: SP1CoreProof = job.load(ProofStage::Core)?;
+                let public_values = proof.public_values.clone();
+                (Some(proof), public_values)
+            }
+            (Some(job), Some(_)) => (None, job.load_public_values()?),
+            (None, Some(_)) => unreachable!(),
+        };
         if kind == SP1ProofKind::Core {
+            let proof = proof.unwrap();
             return Ok(SP1ProofWithPublicValues {
                 proof: SP1Proof::Core(proof.proof.0),
                 stdin: proof.stdin,
@@ -62,16 +127,31 @@ impl Prover<DefaultProverComponents> for CpuProver {
             });
         }
 
-        let deferred_proofs =
-            stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
-        let public_values = proof.public_values.clone();
-
         // Generate the compressed proof.
-        let reduce_proof =
-            self.prover.compress(&pk.vk, proof, deferred_proofs, opts.sp1_prover_opts)?;
+        let reduce_proof = match (proof, &job) {
+            (Some(proof), _) => {
+                let deferred_proofs =
+                    stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
+                let reduce_proof = self.prover.compress_with_progress(
+                    &pk.vk,
+                    proof,
+                    deferred_proofs,
+                    opts.sp1_prover_opts,
+                    &progress,
+                )?;
+                if let Some(job) = &mut job {
+                    job.save(ProofStage::Compress, &reduce_proof)?;
+                }
+                Some(reduce_proof)
+            }
+            (None, Some(job)) if resumed == Some(ProofStage::Compress) => {
+                Some(job.load(ProofStage::Compress)?)
+            }
+            (None, _) => None,
+        };
         if kind == SP1ProofKind::Compressed {
             return Ok(SP1ProofWithPublicValues {
-                proof: SP1Proof::Compressed(Box::new(reduce_proof)),
+                proof: SP1Proof::Compressed(Box::new(reduce_proof.unwrap())),
                 stdin,
                 public_values,
                 sp1_version: self.version().to_string(),
@@ -79,49 +159,195 @@ impl Prover<DefaultProverComponents> for CpuProver {
         }
 
         // Generate the shrink proof.
-        let compress_proof = self.prover.shrink(reduce_proof, opts.sp1_prover_opts)?;
+        let compress_proof = match (reduce_proof, &job) {
+            (Some(reduce_proof), _) => {
+                let compress_proof = self.prover.shrink_with_progress(
+                    reduce_proof,
+                    opts.sp1_prover_opts,
+                    &progress,
+                )?;
+                if let Some(job) = &mut job {
+                    job.save(ProofStage::Shrink, &compress_proof)?;
+                }
+                Some(compress_proof)
+            }
+            (None, Some(job)) if resumed == Some(ProofStage::Shrink) => {
+                Some(job.load(ProofStage::Shrink)?)
+            }
+            (None, _) => None,
+        };
 
         // Genenerate the wrap proof.
-        let outer_proof = self.prover.wrap_bn254(compress_proof, opts.sp1_prover_opts)?;
+        let outer_proof = match (compress_proof, &job) {
+            (Some(compress_proof), _) => {
+                let outer_proof = self.prover.wrap_bn254_with_progress(
+                    compress_proof,
+                    opts.sp1_prover_opts,
+                    &progress,
+                )?;
+                if let Some(job) = &mut job {
+                    job.save(ProofStage::Wrap, &outer_proof)?;
+                }
+                outer_proof
+            }
+            (None, Some(job)) => job.load(ProofStage::Wrap)?,
+            (None, None) => unreachable!(),
+        };
 
-        if kind == SP1ProofKind::Plonk {
-            let plonk_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
-                sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
-                    &outer_proof.vk,
-                    &outer_proof.proof,
-                )
-            } else {
-                try_install_circuit_artifacts("plonk")
-            };
-            let proof = self.prover.wrap_plonk_bn254(outer_proof, &plonk_bn254_artifacts);
+        // The gnark prover cannot be interrupted, so this is the last point at which the proof
+        // can be cancelled.
+        progress.check_cancelled()?;
+        progress.report(ProofPhase::Gnark);
+
+        Ok(SP1ProofWithPublicValues {
+            proof: self.wrap_gnark(outer_proof, kind),
+            stdin,
+            public_values,
+            sp1_version: self.version().to_string(),
+        })
+    }
 
+    /// Proves the inputs in a two-stage pipeline: the core proof of an input is generated while
+    /// the previous input is compressed and wrapped. Both stages share the [SP1Prover], so the
+    /// recursion programs compiled for the first input are reused from its caches.
+    ///
+    /// The job directory of `opts` is ignored.
+    fn prove_batch(
+        &self,
+        pk: &SP1ProvingKey,
+        stdins: Vec<SP1Stdin>,
+        opts: ProofOpts,
+        kind: SP1ProofKind,
+    ) -> Vec<Result<SP1ProofWithPublicValues>> {
+        let opts = opts.sp1_prover_opts;
+        // The bound keeps at most one core proof waiting for the second stage.
+        let (tx, rx) = mpsc::sync_channel(1);
+        thread::scope(|s| {
+            s.spawn(move || {
+                for stdin in stdins {
+                    let proof = catch_panic(|| {
+                        Ok(self.prover.prove_core(pk, &stdin, opts, SP1Context::default())?)
+                    });
+                    if tx.send((stdin, proof)).is_err() {
+                        break;
+                    }
+                }
+            });
+            rx.into_iter()
+                .map(|(stdin, proof)| {
+                    catch_panic(|| self.prove_from_core(pk, stdin, proof?, opts, kind))
+                })
+                .collect()
+        })
+    }
+
+    async fn prove_async<'a>(
+        &'a self,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+        opts: ProofOpts,
+        context: SP1Context<'a>,
+        kind: SP1ProofKind,
+    ) -> Result<SP1ProofWithPublicValues> {
+        pool::prove(self, pk, stdin, opts, context, kind).await
+    }
+
+    async fn execute_async<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        context: SP1Context<'a>,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        pool::execute(self.prover.clone(), elf, stdin, context).await
+    }
+}
+
+impl CpuProver {
+    /// Generates a proof of the given kind from the core proof of `stdin`.
+    fn prove_from_core(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        proof: SP1CoreProof,
+        opts: SP1ProverOpts,
+        kind: SP1ProofKind,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let public_values = proof.public_values.clone();
+        if kind == SP1ProofKind::Core {
             return Ok(SP1ProofWithPublicValues {
-                proof: SP1Proof::Plonk(proof),
-                stdin,
+                proof: SP1Proof::Core(proof.proof.0),
+                stdin: proof.stdin,
                 public_values,
                 sp1_version: self.version().to_string(),
             });
-        } else if kind == SP1ProofKind::Groth16 {
-            let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
-                sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
-                    &outer_proof.vk,
-                    &outer_proof.proof,
-                )
-            } else {
-                try_install_circuit_artifacts("groth16")
-            };
+        }
 
-            let proof = self.prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
+        let deferred_proofs =
+            stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
+        let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs, opts)?;
+        if kind == SP1ProofKind::Compressed {
             return Ok(SP1ProofWithPublicValues {
-                proof: SP1Proof::Groth16(proof),
+                proof: SP1Proof::Compressed(Box::new(reduce_proof)),
                 stdin,
                 public_values,
                 sp1_version: self.version().to_string(),
             });
         }
 
-        unreachable!()
+        let compress_proof = self.prover.shrink(reduce_proof, opts)?;
+        let outer_proof = self.prover.wrap_bn254(compress_proof, opts)?;
+        Ok(SP1ProofWithPublicValues {
+            proof: self.wrap_gnark(outer_proof, kind),
+            stdin,
+            public_values,
+            sp1_version: self.version().to_string(),
+        })
     }
+
+    /// Wraps the outer proof into a Plonk or Groth16 proof with gnark.
+    fn wrap_gnark(&self, outer_proof: SP1ReduceProof<OuterSC>, kind: SP1ProofKind) -> SP1Proof {
+        match kind {
+            SP1ProofKind::Plonk => {
+                let plonk_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                    sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
+                        &outer_proof.vk,
+                        &outer_proof.proof,
+                    )
+                } else {
+                    try_install_circuit_artifacts_in(&self.circuits_dir(), "plonk")
+                };
+                SP1Proof::Plonk(self.prover.wrap_plonk_bn254(outer_proof, &plonk_bn254_artifacts))
+            }
+            SP1ProofKind::Groth16 => {
+                let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                    sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
+                        &outer_proof.vk,
+                        &outer_proof.proof,
+                    )
+                } else if cfg!(feature = "ark-groth16") {
+                    // There are no released artifacts for the arkworks backend, so they are built
+                    // locally the first time, using the proof being wrapped as the template.
+                    sp1_prover::build::try_build_groth16_bn254_artifacts_in(
+                        &outer_proof.vk,
+                        &outer_proof.proof,
+                        ark_groth16_circuit_artifacts_dir(&self.circuits_dir()),
+                    )
+                } else {
+                    try_install_circuit_artifacts_in(&self.circuits_dir(), "groth16")
+                };
+                SP1Proof::Groth16(
+                    self.prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts),
+                )
+            }
+            SP1ProofKind::Core | SP1ProofKind::Compressed => unreachable!(),
+        }
+    }
+}
+
+/// Runs `f`, turning a panic into an error so that it only fails one input of a batch. The
+/// executor panics on some invalid inputs.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| Err(anyhow!("proving panicked")))
 }
 
 impl Default for CpuProver {
@@ -1,8 +1,14 @@
 #![allow(unused_variables)]
+use std::sync::Arc;
+
+use async_trait::async_trait;
 use hashbrown::HashMap;
-use sp1_core_executor::{SP1Context, SP1ReduceProof};
+use sp1_core_executor::{
+    subproof::NoOpSubproofVerifier, ExecutionReport, SP1Context, SP1ReduceProof,
+};
 use sp1_core_machine::io::SP1Stdin;
-use sp1_stark::{ShardCommitment, ShardOpenedValues, ShardProof, StarkVerifyingKey};
+use sp1_primitives::io::SP1PublicValues;
+use sp1_stark::{FriParams, ShardCommitment, ShardOpenedValues, ShardProof, StarkVerifyingKey};
 
 use crate::{
     Prover, SP1Proof, SP1ProofKind, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerificationError,
@@ -18,21 +24,37 @@ use sp1_prover::{
     Groth16Bn254Proof, HashableKey, PlonkBn254Proof, SP1Prover,
 };
 
-use super::{ProofOpts, ProverType};
+use super::{pool, ProofOpts, ProverType};
 
 /// An implementation of [crate::ProverClient] that can generate mock proofs.
+#[derive(Clone)]
 pub struct MockProver {
-    pub(crate) prover: SP1Prover,
+    pub(crate) prover: Arc<SP1Prover>,
 }
 
 impl MockProver {
     /// Creates a new [MockProver].
     pub fn new() -> Self {
         let prover = SP1Prover::new();
-        Self { prover }
+        Self { prover: Arc::new(prover) }
+    }
+
+    /// Executes `elf` without verifying the proofs it verifies with `verify_sp1_proof`, since
+    /// these are mock proofs as well.
+    fn execute<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        mut context: SP1Context<'a>,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        if context.subproof_verifier.is_none() {
+            context.subproof_verifier = Some(Arc::new(NoOpSubproofVerifier));
+        }
+        Ok(self.prover.execute(elf, stdin, context)?)
     }
 }
 
+#[async_trait]
 impl Prover<DefaultProverComponents> for MockProver {
     fn id(&self) -> ProverType {
         ProverType::Mock
@@ -56,7 +78,7 @@ impl Prover<DefaultProverComponents> for MockProver {
     ) -> Result<SP1ProofWithPublicValues> {
         match kind {
             SP1ProofKind::Core => {
-                let (public_values, _) = self.prover.execute(&pk.elf, &stdin, context)?;
+                let (public_values, _) = self.execute(&pk.elf, &stdin, context)?;
                 Ok(SP1ProofWithPublicValues {
                     proof: SP1Proof::Core(vec![]),
                     stdin,
@@ -65,7 +87,7 @@ impl Prover<DefaultProverComponents> for MockProver {
                 })
             }
             SP1ProofKind::Compressed => {
-                let (public_values, _) = self.prover.execute(&pk.elf, &stdin, context)?;
+                let (public_values, _) = self.execute(&pk.elf, &stdin, context)?;
 
                 let shard_proof = ShardProof {
                     commitment: ShardCommitment {
@@ -93,6 +115,7 @@ impl Prover<DefaultProverComponents> for MockProver {
                     pc_start: BabyBear::zero(),
                     chip_information: vec![],
                     chip_ordering: HashMap::new(),
+                    fri_params: FriParams::default(),
                 };
 
                 let proof = SP1Proof::Compressed(Box::new(SP1ReduceProof {
@@ -108,7 +131,7 @@ impl Prover<DefaultProverComponents> for MockProver {
                 })
             }
             SP1ProofKind::Plonk => {
-                let (public_values, _) = self.prover.execute(&pk.elf, &stdin, context)?;
+                let (public_values, _) = self.execute(&pk.elf, &stdin, context)?;
                 Ok(SP1ProofWithPublicValues {
                     proof: SP1Proof::Plonk(PlonkBn254Proof {
                         public_inputs: [
@@ -125,7 +148,7 @@ impl Prover<DefaultProverComponents> for MockProver {
                 })
             }
             SP1ProofKind::Groth16 => {
-                let (public_values, _) = self.prover.execute(&pk.elf, &stdin, context)?;
+                let (public_values, _) = self.execute(&pk.elf, &stdin, context)?;
                 Ok(SP1ProofWithPublicValues {
                     proof: SP1Proof::Groth16(Groth16Bn254Proof {
                         public_inputs: [
@@ -144,6 +167,26 @@ impl Prover<DefaultProverComponents> for MockProver {
         }
     }
 
+    async fn prove_async<'a>(
+        &'a self,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+        opts: ProofOpts,
+        context: SP1Context<'a>,
+        kind: SP1ProofKind,
+    ) -> Result<SP1ProofWithPublicValues> {
+        pool::prove(self, pk, stdin, opts, context, kind).await
+    }
+
+    async fn execute_async<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        context: SP1Context<'a>,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        pool::execute(self.prover.clone(), elf, stdin, context).await
+    }
+
     fn verify(
         &self,
         bundle: &SP1ProofWithPublicValues,
@@ -2,20 +2,29 @@ mod cpu;
 #[cfg(feature = "cuda")]
 mod cuda;
 mod mock;
+pub(crate) mod pool;
+#[cfg(feature = "remote")]
+mod remote;
 
 pub use cpu::CpuProver;
 #[cfg(feature = "cuda")]
 pub use cuda::CudaProver;
 pub use mock::MockProver;
+#[cfg(feature = "remote")]
+pub use remote::RemoteProver;
 
 use itertools::Itertools;
 use p3_field::PrimeField32;
 use std::borrow::Borrow;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
-use sp1_core_executor::SP1Context;
+use async_trait::async_trait;
+use sp1_core_executor::{ExecutionReport, SP1Context};
 use sp1_core_machine::{io::SP1Stdin, SP1_CIRCUIT_VERSION};
+use sp1_primitives::io::SP1PublicValues;
 use sp1_prover::{
     components::SP1ProverComponents, CoreSC, InnerSC, SP1CoreProofData, SP1Prover, SP1ProvingKey,
     SP1VerifyingKey,
@@ -24,7 +33,9 @@ use sp1_stark::{air::PublicValues, MachineVerificationError, SP1ProverOpts, Word
 use strum_macros::EnumString;
 use thiserror::Error;
 
-use crate::install::try_install_circuit_artifacts;
+use crate::install::{
+    ark_groth16_circuit_artifacts_dir, default_circuits_dir, try_install_circuit_artifacts_in,
+};
 use crate::{SP1Proof, SP1ProofKind, SP1ProofWithPublicValues};
 
 /// The type of prover.
@@ -34,6 +45,7 @@ pub enum ProverType {
     Cuda,
     Mock,
     Network,
+    Remote,
 }
 
 /// Options to configure proof generation.
@@ -43,6 +55,11 @@ pub struct ProofOpts {
     pub sp1_prover_opts: SP1ProverOpts,
     /// Optional timeout duration for proof generation.
     pub timeout: Option<Duration>,
+    /// The directory to persist intermediate artifacts to, so that proving can resume from the
+    /// last completed stage.
+    ///
+    /// This is only used by the local prover.
+    pub job_dir: Option<PathBuf>,
 }
 
 #[derive(Error, Debug)]
@@ -62,6 +79,11 @@ pub enum SP1VerificationError {
 }
 
 /// An implementation of [crate::ProverClient].
+///
+/// The provers in this crate implement the async methods without blocking the caller's async
+/// runtime: local proving runs on tokio's blocking thread pool and the network provers await their
+/// requests natively.
+#[async_trait]
 pub trait Prover<C: SP1ProverComponents>: Send + Sync {
     fn id(&self) -> ProverType;
 
@@ -71,6 +93,11 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
         SP1_CIRCUIT_VERSION
     }
 
+    /// The directory under which the Plonk and Groth16 circuit artifacts are installed.
+    fn circuits_dir(&self) -> PathBuf {
+        default_circuits_dir()
+    }
+
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey);
 
     /// Prove the execution of a RISCV ELF with the given inputs, according to the given proof mode.
@@ -83,6 +110,59 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
         kind: SP1ProofKind,
     ) -> Result<SP1ProofWithPublicValues>;
 
+    /// Prove the execution of a RISCV ELF on each of the given inputs, according to the given
+    /// proof mode. Returns one result per input, in the order of the inputs.
+    ///
+    /// The default implementation proves the inputs one after the other with [Self::prove].
+    fn prove_batch(
+        &self,
+        pk: &SP1ProvingKey,
+        stdins: Vec<SP1Stdin>,
+        opts: ProofOpts,
+        kind: SP1ProofKind,
+    ) -> Vec<Result<SP1ProofWithPublicValues>> {
+        stdins
+            .into_iter()
+            .map(|stdin| self.prove(pk, stdin, opts.clone(), SP1Context::default(), kind))
+            .collect()
+    }
+
+    /// Asynchronously prove the execution of a RISCV ELF with the given inputs, according to the
+    /// given proof mode.
+    ///
+    /// The proving key is shared rather than borrowed, so that implementations can move the job to
+    /// another thread without cloning it. The local provers run [Self::prove] on the blocking
+    /// thread pool, which does not support hooks or subproof verifiers.
+    ///
+    /// The default implementation calls [Self::prove] directly, which blocks the caller's async
+    /// runtime until the proof is done.
+    async fn prove_async<'a>(
+        &'a self,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+        opts: ProofOpts,
+        context: SP1Context<'a>,
+        kind: SP1ProofKind,
+    ) -> Result<SP1ProofWithPublicValues> {
+        self.prove(&pk, stdin, opts, context, kind)
+    }
+
+    /// Asynchronously execute a RISCV ELF with the given inputs, without generating a proof.
+    ///
+    /// The default implementation executes the program with [Self::sp1_prover] directly, which
+    /// blocks the caller's async runtime until the execution is done.
+    async fn execute_async<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        context: SP1Context<'a>,
+    ) -> Result<(SP1PublicValues, ExecutionReport)>
+    where
+        C: 'a,
+    {
+        Ok(self.sp1_prover().execute(elf, stdin, context)?)
+    }
+
     /// Verify that an SP1 proof is valid given its vkey and metadata.
     /// For Plonk proofs, verifies that the public inputs of the PlonkBn254 proof match
     /// the hash of the VK and the committed public values of the SP1ProofWithPublicValues.
@@ -153,7 +233,7 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
                     &if sp1_prover::build::sp1_dev_mode() {
                         sp1_prover::build::plonk_bn254_artifacts_dev_dir()
                     } else {
-                        try_install_circuit_artifacts("plonk")
+                        try_install_circuit_artifacts_in(&self.circuits_dir(), "plonk")
                     },
                 )
                 .map_err(SP1VerificationError::Plonk),
@@ -165,8 +245,10 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
                     &bundle.public_values,
                     &if sp1_prover::build::sp1_dev_mode() {
                         sp1_prover::build::groth16_bn254_artifacts_dev_dir()
+                    } else if cfg!(feature = "ark-groth16") {
+                        ark_groth16_circuit_artifacts_dir(&self.circuits_dir())
                     } else {
-                        try_install_circuit_artifacts("groth16")
+                        try_install_circuit_artifacts_in(&self.circuits_dir(), "groth16")
                     },
                 )
                 .map_err(SP1VerificationError::Groth16),
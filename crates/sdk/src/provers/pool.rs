@@ -0,0 +1,118 @@
+//! Runs blocking proving work from async code.
+//!
+//! Proving and execution are CPU-bound and can take minutes, so the async prover methods run them
+//! on tokio's blocking thread pool instead of on the worker threads of the caller's async runtime.
+//! A semaphore bounds the number of jobs running at once, so that a burst of requests queues up
+//! instead of spawning a blocking thread each.
+
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{anyhow, ensure, Result};
+use sp1_core_executor::{ExecutionReport, ProgressMonitor, SP1Context};
+use sp1_core_machine::io::SP1Stdin;
+use sp1_primitives::io::SP1PublicValues;
+use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+use tokio::sync::Semaphore;
+
+use crate::{Prover, SP1ProofKind, SP1ProofWithPublicValues, SP1ProvingKey};
+
+use super::ProofOpts;
+
+/// The default maximum number of jobs run at once.
+///
+/// A single proof can use most of the memory of the machine, so jobs run one at a time unless
+/// configured otherwise with [set_max_async_jobs].
+pub const DEFAULT_MAX_ASYNC_JOBS: usize = 1;
+
+/// The maximum number of concurrent jobs and the semaphore that enforces it.
+static PERMITS: OnceLock<(usize, Arc<Semaphore>)> = OnceLock::new();
+
+/// Sets the maximum number of proofs and executions the async methods of the local provers run at
+/// once, across the process.
+///
+/// The limit is fixed by the first call or the first async job, whichever comes first, so this
+/// returns an error if a different limit is already in effect.
+pub fn set_max_async_jobs(max_jobs: usize) -> Result<()> {
+    ensure!(max_jobs > 0, "the maximum number of async jobs must be positive");
+    let (current, _) = PERMITS.get_or_init(|| (max_jobs, Arc::new(Semaphore::new(max_jobs))));
+    ensure!(*current == max_jobs, "the maximum number of async jobs is already {current}");
+    Ok(())
+}
+
+/// Returns the semaphore that bounds the number of concurrent jobs.
+fn permits() -> Arc<Semaphore> {
+    let (_, permits) = PERMITS
+        .get_or_init(|| (DEFAULT_MAX_ASYNC_JOBS, Arc::new(Semaphore::new(DEFAULT_MAX_ASYNC_JOBS))));
+    permits.clone()
+}
+
+/// Runs `f` on the blocking thread pool and waits for its result without blocking the caller.
+///
+/// Must be called from within a tokio runtime.
+pub(crate) async fn spawn<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Result<T> {
+    let permit =
+        permits().acquire_owned().await.map_err(|_| anyhow!("prover pool has shut down"))?;
+    tokio::task::spawn_blocking(move || {
+        // The permit is held by the job, so it is only released once the job finishes even if
+        // the caller stops waiting for it.
+        let _permit = permit;
+        f()
+    })
+    .await
+    .map_err(|e| anyhow!("proving task panicked: {e}"))
+}
+
+/// Runs [Prover::prove] with a clone of `prover` on the blocking thread pool.
+///
+/// The proving key is shared with the job rather than cloned, since it holds the whole program.
+pub(crate) async fn prove<P: Prover<DefaultProverComponents> + Clone + 'static>(
+    prover: &P,
+    pk: Arc<SP1ProvingKey>,
+    stdin: SP1Stdin,
+    opts: ProofOpts,
+    context: SP1Context<'_>,
+    kind: SP1ProofKind,
+) -> Result<SP1ProofWithPublicValues> {
+    let context = DetachedContext::try_from_context(context)?;
+    let prover = prover.clone();
+    spawn(move || prover.prove(&pk, stdin, opts, context.build(), kind)).await?
+}
+
+/// Runs [SP1Prover::execute] on the blocking thread pool.
+pub(crate) async fn execute(
+    prover: Arc<SP1Prover<DefaultProverComponents>>,
+    elf: &[u8],
+    stdin: &SP1Stdin,
+    context: SP1Context<'_>,
+) -> Result<(SP1PublicValues, ExecutionReport)> {
+    let context = DetachedContext::try_from_context(context)?;
+    let (elf, stdin) = (elf.to_vec(), stdin.clone());
+    spawn(move || Ok(prover.execute(&elf, &stdin, context.build())?)).await?
+}
+
+/// The parts of an [SP1Context] that can be moved to the blocking thread pool.
+///
+/// Hooks and subproof verifiers may borrow from the caller, so they cannot outlive the async call
+/// and are only supported by the blocking prover methods.
+#[derive(Clone, Debug)]
+pub(crate) struct DetachedContext {
+    max_cycles: Option<u64>,
+    progress: ProgressMonitor,
+}
+
+impl DetachedContext {
+    /// Detaches `context`, or returns an error if it sets hooks or a subproof verifier.
+    pub(crate) fn try_from_context(context: SP1Context<'_>) -> Result<Self> {
+        if context.hook_registry.is_some() || context.subproof_verifier.is_some() {
+            return Err(anyhow!(
+                "hooks and subproof verifiers are not supported by async proving, use `run` instead"
+            ));
+        }
+        Ok(Self { max_cycles: context.max_cycles, progress: context.progress })
+    }
+
+    /// Rebuilds the context on the prover thread.
+    pub(crate) fn build<'a>(self) -> SP1Context<'a> {
+        SP1Context { max_cycles: self.max_cycles, progress: self.progress, ..Default::default() }
+    }
+}
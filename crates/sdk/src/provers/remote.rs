@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sp1_core_executor::ExecutionReport;
+use sp1_core_machine::{io::SP1Stdin, SP1_CIRCUIT_VERSION};
+use sp1_cuda::SP1CudaProver;
+use sp1_primitives::io::SP1PublicValues;
+use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+use sp1_stark::SP1ProverOpts;
+
+use super::{pool, ProverType};
+use crate::install::try_install_circuit_artifacts;
+use crate::{
+    provers::ProofOpts, Prover, SP1Context, SP1Proof, SP1ProofKind, SP1ProofWithPublicValues,
+    SP1ProvingKey, SP1VerifyingKey,
+};
+
+/// An implementation of [crate::ProverClient] that generates proofs with a remote proving
+/// service, such as the `sp1-cpu-server` binary of `sp1-cuda`.
+///
+/// Keys are generated and the Plonk and Groth16 proofs are wrapped locally, and the STARK proofs
+/// are requested from the service.
+///
+/// Cloning a [RemoteProver] is cheap and shares the connection to the service.
+#[derive(Clone)]
+pub struct RemoteProver {
+    prover: Arc<SP1Prover<DefaultProverComponents>>,
+    client: Arc<SP1CudaProver>,
+}
+
+impl RemoteProver {
+    /// Creates a new [RemoteProver] connected to the proving service at `endpoint`, e.g.
+    /// `http://localhost:3000`.
+    ///
+    /// Returns an error if the service does not become ready within a minute.
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let client = SP1CudaProver::connect(endpoint).map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(Self { prover: Arc::new(SP1Prover::new()), client: Arc::new(client) })
+    }
+}
+
+#[async_trait]
+impl Prover<DefaultProverComponents> for RemoteProver {
+    fn id(&self) -> ProverType {
+        ProverType::Remote
+    }
+
+    fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        self.prover.setup(elf)
+    }
+
+    fn sp1_prover(&self) -> &SP1Prover<DefaultProverComponents> {
+        &self.prover
+    }
+
+    fn prove<'a>(
+        &'a self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        opts: ProofOpts,
+        context: SP1Context<'a>,
+        kind: SP1ProofKind,
+    ) -> Result<SP1ProofWithPublicValues> {
+        check_service_context(&context)?;
+        prove_with_service(&self.prover, &self.client, pk, stdin, opts.sp1_prover_opts, kind)
+    }
+
+    async fn prove_async<'a>(
+        &'a self,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+        opts: ProofOpts,
+        context: SP1Context<'a>,
+        kind: SP1ProofKind,
+    ) -> Result<SP1ProofWithPublicValues> {
+        pool::prove(self, pk, stdin, opts, context, kind).await
+    }
+
+    async fn execute_async<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        context: SP1Context<'a>,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        pool::execute(self.prover.clone(), elf, stdin, context).await
+    }
+}
+
+/// Returns an error if `context` uses a feature that cannot be forwarded to a proving service.
+///
+/// The program is executed by the service, so hooks, subproof verifiers, cycle limits,
+/// cancellation and progress listeners set on the caller's side would silently have no effect.
+pub(crate) fn check_service_context(context: &SP1Context<'_>) -> Result<()> {
+    let unsupported = if context.hook_registry.is_some() {
+        "hooks"
+    } else if context.subproof_verifier.is_some() {
+        "subproof verifiers"
+    } else if context.max_cycles.is_some() {
+        "cycle limits"
+    } else if context.progress.cancellation_token.is_some() {
+        "cancellation"
+    } else if context.progress.listener.is_some() {
+        "progress listeners"
+    } else {
+        return Ok(());
+    };
+    Err(anyhow::anyhow!("{unsupported} are not supported by the proving service"))
+}
+
+/// Generates a proof of the given kind by requesting the STARK proofs from `service` and
+/// wrapping them into Plonk or Groth16 proofs with `prover`.
+pub(crate) fn prove_with_service(
+    prover: &SP1Prover<DefaultProverComponents>,
+    service: &SP1CudaProver,
+    pk: &SP1ProvingKey,
+    stdin: SP1Stdin,
+    opts: SP1ProverOpts,
+    kind: SP1ProofKind,
+) -> Result<SP1ProofWithPublicValues> {
+    // Generate the core proof.
+    let proof = service.prove_core(pk, &stdin, opts)?;
+    if kind == SP1ProofKind::Core {
+        return Ok(SP1ProofWithPublicValues {
+            proof: SP1Proof::Core(proof.proof.0),
+            stdin: proof.stdin,
+            public_values: proof.public_values,
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        });
+    }
+
+    let deferred_proofs =
+        stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
+    let public_values = proof.public_values.clone();
+
+    // Generate the compressed proof.
+    let reduce_proof = service.compress(&pk.vk, proof, deferred_proofs, opts)?;
+    if kind == SP1ProofKind::Compressed {
+        return Ok(SP1ProofWithPublicValues {
+            proof: SP1Proof::Compressed(Box::new(reduce_proof)),
+            stdin,
+            public_values,
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        });
+    }
+
+    // Generate the shrink proof.
+    let compress_proof = service.shrink(reduce_proof, opts)?;
+
+    // Genenerate the wrap proof.
+    let outer_proof = service.wrap_bn254(compress_proof, opts)?;
+
+    if kind == SP1ProofKind::Plonk {
+        let plonk_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+            sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
+                &outer_proof.vk,
+                &outer_proof.proof,
+            )
+        } else {
+            try_install_circuit_artifacts("plonk")
+        };
+        let proof = prover.wrap_plonk_bn254(outer_proof, &plonk_bn254_artifacts);
+        return Ok(SP1ProofWithPublicValues {
+            proof: SP1Proof::Plonk(proof),
+            stdin,
+            public_values,
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        });
+    } else if kind == SP1ProofKind::Groth16 {
+        let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+            sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
+                &outer_proof.vk,
+                &outer_proof.proof,
+            )
+        } else {
+            try_install_circuit_artifacts("groth16")
+        };
+
+        let proof = prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
+        return Ok(SP1ProofWithPublicValues {
+            proof: SP1Proof::Groth16(proof),
+            stdin,
+            public_values,
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        });
+    }
+
+    unreachable!()
+}
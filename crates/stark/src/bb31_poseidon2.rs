@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
 
-use crate::{Com, StarkGenericConfig, ZeroCommitment};
+use crate::{Com, FriParams, StarkGenericConfig, ZeroCommitment};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
@@ -142,6 +142,10 @@ impl StarkGenericConfig for BabyBearPoseidon2Inner {
     fn challenger(&self) -> Self::Challenger {
         InnerChallenger::new(self.perm.clone())
     }
+
+    fn fri_params(&self) -> FriParams {
+        self.pcs.fri_config().into()
+    }
 }
 
 impl ZeroCommitment<BabyBearPoseidon2Inner> for InnerPcs {
@@ -164,7 +168,7 @@ pub mod baby_bear_poseidon2 {
     use serde::{Deserialize, Serialize};
     use sp1_primitives::RC_16_30;
 
-    use crate::{Com, StarkGenericConfig, ZeroCommitment, DIGEST_SIZE};
+    use crate::{Com, FriParams, StarkGenericConfig, ZeroCommitment, DIGEST_SIZE};
 
     pub type Val = BabyBear;
     pub type Challenge = BinomialExtensionField<Val, 4>;
@@ -207,48 +211,34 @@ pub mod baby_bear_poseidon2 {
         )
     }
 
+    /// The FRI config with the given parameters.
     #[must_use]
-    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+    pub fn fri_config(params: FriParams) -> FriConfig<ChallengeMmcs> {
         let perm = my_perm();
         let hash = MyHash::new(perm.clone());
         let compress = MyCompress::new(perm.clone());
         let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 100,
-        };
-        FriConfig { log_blowup: 1, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+        FriConfig {
+            log_blowup: params.log_blowup,
+            num_queries: params.num_queries,
+            proof_of_work_bits: params.proof_of_work_bits,
+            mmcs: challenge_mmcs,
+        }
     }
 
     #[must_use]
-    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 50,
-        };
-        FriConfig { log_blowup: 2, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(FriParams::standard())
     }
 
     #[must_use]
-    pub fn ultra_compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 33,
-        };
-        FriConfig { log_blowup: 3, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(FriParams::compressed())
     }
 
-    enum BabyBearPoseidon2Type {
-        Default,
-        Compressed,
+    #[must_use]
+    pub fn ultra_compressed_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(FriParams::ultra_compressed())
     }
 
     #[derive(Deserialize)]
@@ -256,53 +246,41 @@ pub mod baby_bear_poseidon2 {
     pub struct BabyBearPoseidon2 {
         pub perm: Perm,
         pcs: Pcs,
-        config_type: BabyBearPoseidon2Type,
+        fri_params: FriParams,
     }
 
     impl BabyBearPoseidon2 {
         #[must_use]
         pub fn new() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft {};
-            let fri_config = default_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: BabyBearPoseidon2Type::Default }
+            Self::with_fri_params(FriParams::standard())
         }
 
         #[must_use]
         pub fn compressed() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft {};
-            let fri_config = compressed_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: BabyBearPoseidon2Type::Compressed }
+            Self::with_fri_params(FriParams::compressed())
         }
 
         #[must_use]
         pub fn ultra_compressed() -> Self {
+            Self::with_fri_params(FriParams::ultra_compressed())
+        }
+
+        /// Creates a config whose PCS uses the given FRI parameters.
+        #[must_use]
+        pub fn with_fri_params(fri_params: FriParams) -> Self {
             let perm = my_perm();
             let hash = MyHash::new(perm.clone());
             let compress = MyCompress::new(perm.clone());
             let val_mmcs = ValMmcs::new(hash, compress);
             let dft = Dft {};
-            let fri_config = ultra_compressed_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: BabyBearPoseidon2Type::Compressed }
+            let pcs = Pcs::new(27, dft, val_mmcs, fri_config(fri_params));
+            Self { pcs, perm, fri_params }
         }
     }
 
     impl Clone for BabyBearPoseidon2 {
         fn clone(&self) -> Self {
-            match self.config_type {
-                BabyBearPoseidon2Type::Default => Self::new(),
-                BabyBearPoseidon2Type::Compressed => Self::compressed(),
-            }
+            Self::with_fri_params(self.fri_params)
         }
     }
 
@@ -342,6 +320,10 @@ pub mod baby_bear_poseidon2 {
         fn challenger(&self) -> Self::Challenger {
             Challenger::new(self.perm.clone())
         }
+
+        fn fri_params(&self) -> FriParams {
+            self.fri_params
+        }
     }
 
     impl ZeroCommitment<BabyBearPoseidon2> for Pcs {
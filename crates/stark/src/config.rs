@@ -5,6 +5,8 @@ use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{ExtensionField, Field, PrimeField};
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::FriParams;
+
 pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
@@ -73,6 +75,14 @@ pub trait StarkGenericConfig: 'static + Send + Sync + Serialize + DeserializeOwn
 
     /// Initialize a new challenger.
     fn challenger(&self) -> Self::Challenger;
+
+    /// Get the FRI parameters of the PCS.
+    ///
+    /// Defaults to [`FriParams::standard`], so configurations using other parameters must override
+    /// it.
+    fn fri_params(&self) -> FriParams {
+        FriParams::standard()
+    }
 }
 
 pub trait ZeroCommitment<SC: StarkGenericConfig> {
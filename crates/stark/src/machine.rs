@@ -1,12 +1,14 @@
 use hashbrown::HashMap;
 use itertools::Itertools;
 use p3_air::Air;
+use p3_baby_bear::BabyBear;
 use p3_challenger::{CanObserve, FieldChallenger};
-use p3_commit::Pcs;
-use p3_field::{AbstractExtensionField, AbstractField, Field, PrimeField32};
+use p3_commit::{Pcs, TwoAdicMultiplicativeCoset};
+use p3_field::{AbstractExtensionField, AbstractField, Field, PrimeField32, TwoAdicField};
 use p3_matrix::{dense::RowMajorMatrix, Dimensions, Matrix};
 use p3_maybe_rayon::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_primitives::poseidon2_hash;
 use std::{array, cmp::Reverse, env, fmt::Debug, time::Instant};
 use tracing::instrument;
 
@@ -15,7 +17,7 @@ use crate::{
     air::{InteractionScope, MachineAir, MachineProgram},
     lookup::{debug_interactions_with_all_chips, InteractionKind},
     record::MachineRecord,
-    DebugConstraintBuilder, ShardProof, VerifierConstraintFolder,
+    DebugConstraintBuilder, FriParams, ShardProof, VerifierConstraintFolder, DIGEST_SIZE,
 };
 
 use super::{
@@ -92,6 +94,12 @@ pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
     pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
     /// The chip ordering.
     pub chip_ordering: HashMap<String, usize>,
+    /// The FRI parameters the proofs are generated with.
+    ///
+    /// Keys serialized in a self-describing format before the parameters were configurable use
+    /// the standard ones. Earlier bincode-encoded keys can't be read, and must be set up again.
+    #[serde(default)]
+    pub fri_params: FriParams,
 }
 
 impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
@@ -105,6 +113,41 @@ impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
     }
 }
 
+impl<SC> StarkVerifyingKey<SC>
+where
+    SC: StarkGenericConfig<Val = BabyBear, Domain = TwoAdicMultiplicativeCoset<BabyBear>>,
+    Com<SC>: AsRef<[BabyBear; DIGEST_SIZE]>,
+{
+    /// Hashes the verifying key into a digest of BabyBear elements.
+    ///
+    /// This is the vkey hash of a program passed to `verify_sp1_proof`, and the digest of a
+    /// recursion program in the tree of allowed recursion vkeys. Parameters other than the presets
+    /// are hashed as well, so keys of the same program with different parameters have different
+    /// hashes.
+    pub fn hash_babybear(&self) -> [BabyBear; DIGEST_SIZE] {
+        let prep_domains = self.chip_information.iter().map(|(_, domain, _)| domain);
+        let num_inputs = DIGEST_SIZE + 1 + (4 * prep_domains.len()) + 3;
+        let mut inputs = Vec::with_capacity(num_inputs);
+        inputs.extend(self.commit.as_ref());
+        inputs.push(self.pc_start);
+        for domain in prep_domains {
+            inputs.push(BabyBear::from_canonical_usize(domain.log_n));
+            let size = 1 << domain.log_n;
+            inputs.push(BabyBear::from_canonical_usize(size));
+            let g = BabyBear::two_adic_generator(domain.log_n);
+            inputs.push(domain.shift);
+            inputs.push(g);
+        }
+        if !self.fri_params.is_preset() {
+            inputs.push(BabyBear::from_canonical_usize(self.fri_params.log_blowup));
+            inputs.push(BabyBear::from_canonical_usize(self.fri_params.num_queries));
+            inputs.push(BabyBear::from_canonical_usize(self.fri_params.proof_of_work_bits));
+        }
+
+        poseidon2_hash(inputs)
+    }
+}
+
 impl<SC: StarkGenericConfig> Debug for StarkVerifyingKey<SC> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("VerifyingKey").finish()
@@ -239,7 +282,13 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                 data,
                 chip_ordering: chip_ordering.clone(),
             },
-            StarkVerifyingKey { commit, pc_start, chip_information, chip_ordering },
+            StarkVerifyingKey {
+                commit,
+                pc_start,
+                chip_information,
+                chip_ordering,
+                fri_params: self.config.fri_params(),
+            },
         )
     }
 
@@ -293,6 +342,12 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         SC::Challenger: Clone,
         A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
     {
+        // The proofs must be generated with the FRI parameters of the verifying key.
+        let fri_params = self.config.fri_params();
+        if vk.fri_params != fri_params {
+            return Err(MachineVerificationError::FriParamsMismatch(vk.fri_params, fri_params));
+        }
+
         let contains_global_bus = self.contains_global_bus();
 
         // Observe the preprocessed commitment.
@@ -518,6 +573,8 @@ pub enum MachineVerificationError<SC: StarkGenericConfig> {
     CpuLogDegreeTooLarge(usize),
     /// The verification key is not allowed.
     InvalidVerificationKey,
+    /// The FRI parameters of the verifying key differ from the ones of the verifier.
+    FriParamsMismatch(FriParams, FriParams),
 }
 
 impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
@@ -560,6 +617,9 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidVerificationKey => {
                 write!(f, "Invalid verification key")
             }
+            MachineVerificationError::FriParamsMismatch(vk, verifier) => {
+                write!(f, "FRI params mismatch: vk {:?}, verifier {:?}", vk, verifier)
+            }
         }
     }
 }
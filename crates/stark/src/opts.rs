@@ -1,5 +1,6 @@
 use std::env;
 
+use p3_fri::FriConfig;
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
 
@@ -10,10 +11,16 @@ const DEFAULT_TRACE_GEN_WORKERS: usize = 1;
 const DEFAULT_CHECKPOINTS_CHANNEL_CAPACITY: usize = 128;
 const DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY: usize = 1;
 
+/// The bits of security of the challenges, sampled from the degree four extension of BabyBear.
+const EXTENSION_FIELD_SECURITY_BITS: usize = 124;
+
 /// The threshold for splitting deferred events.
 pub const MAX_DEFERRED_SPLIT_THRESHOLD: usize = 1 << 18;
 
 /// Options to configure the SP1 prover for core and recursive proofs.
+///
+/// The FRI parameters of `core_opts` and `recursion_opts` apply to the core and compress proofs,
+/// and must match the ones the prover was built with.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SP1ProverOpts {
     /// Options for the core prover.
@@ -45,6 +52,112 @@ pub struct SP1CoreOpts {
     pub checkpoints_channel_capacity: usize,
     /// The capacity of the channel for records and traces.
     pub records_and_traces_channel_capacity: usize,
+    /// The FRI parameters of the proofs.
+    pub fri_params: FriParams,
+}
+
+/// The parameters of the FRI low-degree test, which trade proof size against prover time.
+///
+/// The conjectured security level of a proof is [`FriParams::conjectured_security_bits`]: each
+/// query contributes `log_blowup` bits and the proof-of-work grinding `proof_of_work_bits` bits.
+/// The conjecture assumes the proximity gaps of Reed-Solomon codes up to capacity, and the level
+/// is further bounded by the ~124 bits of the degree four extension of BabyBear used for the
+/// challenges.
+///
+/// | preset                                | blowup | queries | pow bits | security bits |
+/// |---------------------------------------|--------|---------|----------|---------------|
+/// | [`FriParams::standard`]               | 2      | 100     | 16       | 116           |
+/// | [`FriParams::compressed`]             | 4      | 50      | 16       | 116           |
+/// | [`FriParams::ultra_compressed`]       | 8      | 33      | 16       | 115           |
+///
+/// For example, 64 queries at blowup 2 with 16 bits of grinding give fast 80-bit proofs.
+///
+/// The parameters are part of the verifying key, and verification fails for proofs generated
+/// with other parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FriParams {
+    /// The log2 of the blowup factor of the Reed-Solomon code.
+    pub log_blowup: usize,
+    /// The number of FRI queries.
+    pub num_queries: usize,
+    /// The number of proof-of-work bits ground before sampling the queries.
+    pub proof_of_work_bits: usize,
+}
+
+impl FriParams {
+    /// Creates new FRI parameters.
+    #[must_use]
+    pub const fn new(log_blowup: usize, num_queries: usize, proof_of_work_bits: usize) -> Self {
+        Self { log_blowup, num_queries, proof_of_work_bits }
+    }
+
+    /// The parameters of core and compress proofs.
+    ///
+    /// The number of queries can be overridden with the `FRI_QUERIES` environment variable.
+    #[must_use]
+    pub fn standard() -> Self {
+        Self::new(1, fri_queries(100), 16)
+    }
+
+    /// The parameters of shrink proofs, which trade prover time for smaller proofs.
+    ///
+    /// The number of queries can be overridden with the `FRI_QUERIES` environment variable.
+    #[must_use]
+    pub fn compressed() -> Self {
+        Self::new(2, fri_queries(50), 16)
+    }
+
+    /// The parameters of the smallest proofs.
+    ///
+    /// The number of queries can be overridden with the `FRI_QUERIES` environment variable.
+    #[must_use]
+    pub fn ultra_compressed() -> Self {
+        Self::new(3, fri_queries(33), 16)
+    }
+
+    /// Whether these are the parameters of one of the presets.
+    ///
+    /// The presets have distinct blowups, and so distinct preprocessed commitments, so the vkey
+    /// hash only includes the parameters that are not a preset. This keeps the hashes of the
+    /// released verifying keys unchanged.
+    #[must_use]
+    pub fn is_preset(&self) -> bool {
+        [Self::standard(), Self::compressed(), Self::ultra_compressed()].contains(self)
+    }
+
+    /// The conjectured security level in bits, `log_blowup * num_queries + proof_of_work_bits`,
+    /// bounded by the size of the extension field the challenges are sampled from.
+    #[must_use]
+    pub const fn conjectured_security_bits(&self) -> usize {
+        let bits = self.log_blowup * self.num_queries + self.proof_of_work_bits;
+        if bits < EXTENSION_FIELD_SECURITY_BITS {
+            bits
+        } else {
+            EXTENSION_FIELD_SECURITY_BITS
+        }
+    }
+}
+
+impl<M> From<&FriConfig<M>> for FriParams {
+    fn from(config: &FriConfig<M>) -> Self {
+        Self::new(config.log_blowup, config.num_queries, config.proof_of_work_bits)
+    }
+}
+
+impl Default for FriParams {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+fn fri_queries(default: usize) -> usize {
+    match env::var("FRI_QUERIES") {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            tracing::warn!("invalid FRI_QUERIES value {value:?}, using {default} queries");
+            default
+        }),
+        Err(_) => default,
+    }
 }
 
 /// Calculate the default shard size using an empirically determined formula.
@@ -114,6 +227,7 @@ impl Default for SP1CoreOpts {
                     |_| DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY,
                     |s| s.parse::<usize>().unwrap_or(DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY),
                 ),
+            fri_params: FriParams::standard(),
         }
     }
 }
@@ -159,3 +273,25 @@ impl SplitOpts {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+
+    #[test]
+    fn test_fri_params() {
+        assert_eq!(FriParams::new(1, 100, 16).conjectured_security_bits(), 116);
+        assert_eq!(FriParams::new(1, 64, 16).conjectured_security_bits(), 80);
+        assert_eq!(FriParams::new(1, 200, 16).conjectured_security_bits(), 124);
+        assert!(FriParams::standard().is_preset());
+        assert!(FriParams::ultra_compressed().is_preset());
+        assert!(!FriParams::new(1, 64, 16).is_preset());
+
+        let params = FriParams::new(2, 40, 8);
+        let config = BabyBearPoseidon2::with_fri_params(params);
+        assert_eq!(config.fri_params(), params);
+        assert_eq!(config.clone().fri_params(), params);
+        assert_eq!(FriParams::from(config.pcs().fri_config()), params);
+    }
+}
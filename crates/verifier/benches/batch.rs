@@ -0,0 +1,79 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_verifier::{
+    Groth16BatchEntry, Groth16Verifier, PlonkBatchEntry, PlonkVerifier, GROTH16_VK_BYTES,
+    PLONK_VK_BYTES,
+};
+
+fn bench_groth16_batch(c: &mut Criterion) {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("test_binaries/fibonacci-groth16.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    let vkey_hash = "0x00e60860c07bfc6e4c480286c0ddbb879674eb47f84b4ef041cf858b17aa0ed1";
+    let entry = Groth16BatchEntry {
+        proof: &proof,
+        sp1_public_inputs: &public_inputs,
+        sp1_vkey_hash: vkey_hash,
+    };
+
+    let mut group = c.benchmark_group("groth16");
+    for n in [1, 4, 16, 64] {
+        let entries = vec![entry; n];
+        group.bench_with_input(BenchmarkId::new("verify_loop", n), &entries, |b, entries| {
+            b.iter(|| {
+                for entry in entries {
+                    Groth16Verifier::verify(
+                        entry.proof,
+                        entry.sp1_public_inputs,
+                        entry.sp1_vkey_hash,
+                        &GROTH16_VK_BYTES,
+                    )
+                    .unwrap();
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("verify_batch", n), &entries, |b, entries| {
+            b.iter(|| Groth16Verifier::verify_batch(entries, &GROTH16_VK_BYTES).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_plonk_batch(c: &mut Criterion) {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("test_binaries/fibonacci-plonk.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    let vkey_hash = "0x00e60860c07bfc6e4c480286c0ddbb879674eb47f84b4ef041cf858b17aa0ed1";
+    let entry = PlonkBatchEntry {
+        proof: &proof,
+        sp1_public_inputs: &public_inputs,
+        sp1_vkey_hash: vkey_hash,
+    };
+
+    let mut group = c.benchmark_group("plonk");
+    for n in [1, 4, 16, 64] {
+        let entries = vec![entry; n];
+        group.bench_with_input(BenchmarkId::new("verify_loop", n), &entries, |b, entries| {
+            b.iter(|| {
+                for entry in entries {
+                    PlonkVerifier::verify(
+                        entry.proof,
+                        entry.sp1_public_inputs,
+                        entry.sp1_vkey_hash,
+                        &PLONK_VK_BYTES,
+                    )
+                    .unwrap();
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("verify_batch", n), &entries, |b, entries| {
+            b.iter(|| PlonkVerifier::verify_batch(entries, &PLONK_VK_BYTES).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_groth16_batch, bench_plonk_batch);
+criterion_main!(benches);
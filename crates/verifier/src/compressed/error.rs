@@ -0,0 +1,22 @@
+use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, FriParams, MachineVerificationError};
+use thiserror_no_std::Error;
+
+#[derive(Debug, Error)]
+pub enum CompressedError {
+    #[error("Proof verification failed: {0:?}")]
+    ProofVerificationFailed(MachineVerificationError<BabyBearPoseidon2>),
+    #[error("Compress vkey not allowed")]
+    VkeyNotAllowed,
+    #[error("FRI parameters mismatch: the proof uses {0:?}, the verifier {1:?}")]
+    FriParamsMismatch(FriParams, FriParams),
+    #[error("Recursion vkey root mismatch")]
+    VkRootMismatch,
+    #[error("Recursion public values digest mismatch")]
+    PublicValuesDigestMismatch,
+    #[error("Proof is not complete")]
+    IncompleteProof,
+    #[error("SP1 vkey hash mismatch")]
+    Sp1VkeyHashMismatch,
+    #[error("Committed values digest mismatch")]
+    CommittedValuesDigestMismatch,
+}
@@ -0,0 +1,146 @@
+pub mod error;
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::borrow::Borrow;
+
+use p3_baby_bear::BabyBear;
+use p3_field::{AbstractField, PrimeField32};
+use sha2::{Digest, Sha256};
+use sp1_core_executor::SP1ReduceProof;
+use sp1_primitives::poseidon2_hash;
+use sp1_recursion_core::{
+    air::{RecursionPublicValues, NUM_PV_ELMS_TO_HASH},
+    machine::RecursionAir,
+    DIGEST_SIZE,
+};
+use sp1_stark::{
+    baby_bear_poseidon2::BabyBearPoseidon2, FriParams, MachineProof, StarkGenericConfig,
+    StarkMachine,
+};
+
+use error::CompressedError;
+
+/// The degree of the compress machine, matching the one used by the SP1 prover.
+const COMPRESS_DEGREE: usize = 3;
+
+/// A verifier for compressed SP1 STARK proofs.
+///
+/// Compressed proofs are the proofs that can be verified inside the zkVM with `verify_sp1_proof`.
+/// Verifying them directly avoids wrapping them into a Groth16 or Plonk BN254 proof.
+pub struct CompressedVerifier {
+    machine: StarkMachine<BabyBearPoseidon2, RecursionAir<BabyBear, COMPRESS_DEGREE>>,
+    fri_params: FriParams,
+    vk_root: [BabyBear; DIGEST_SIZE],
+    allowed_vks: BTreeSet<[BabyBear; DIGEST_SIZE]>,
+}
+
+impl CompressedVerifier {
+    /// Creates a verifier for the recursion programs of an SP1 version.
+    ///
+    /// # Arguments
+    ///
+    /// * `vk_root` - The root of the Merkle tree of the recursion program vkeys.
+    /// * `allowed_vks` - The digests of the recursion program vkeys, the leaves of that tree.
+    ///
+    /// Both are held by the SP1 prover of the version that generated the proofs, as its `vk_root`
+    /// and the keys of its `allowed_vk_map`.
+    ///
+    /// The proofs must use the standard FRI parameters of compressed proofs, see
+    /// [Self::with_fri_params] for other parameters.
+    pub fn new(
+        vk_root: [BabyBear; DIGEST_SIZE],
+        allowed_vks: impl IntoIterator<Item = [BabyBear; DIGEST_SIZE]>,
+    ) -> Self {
+        Self::with_fri_params(vk_root, allowed_vks, FriParams::standard())
+    }
+
+    /// Creates a verifier for the recursion programs of an SP1 version, whose compressed proofs
+    /// are generated with the FRI parameters `fri_params`.
+    pub fn with_fri_params(
+        vk_root: [BabyBear; DIGEST_SIZE],
+        allowed_vks: impl IntoIterator<Item = [BabyBear; DIGEST_SIZE]>,
+        fri_params: FriParams,
+    ) -> Self {
+        Self {
+            machine: RecursionAir::compress_machine(BabyBearPoseidon2::with_fri_params(fri_params)),
+            fri_params,
+            vk_root,
+            allowed_vks: allowed_vks.into_iter().collect(),
+        }
+    }
+
+    /// Verifies a compressed proof.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - The compressed proof.
+    /// * `sp1_public_inputs` - The SP1 public inputs.
+    /// * `sp1_vkey_hash` - The SP1 vkey hash, as passed to `verify_sp1_proof`.
+    ///   This is generated in the following manner:
+    ///
+    /// ```ignore
+    /// use sp1_sdk::{HashableKey, ProverClient};
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(ELF);
+    /// let sp1_vkey_hash = vk.hash_u32();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A success [`Result`] if verification succeeds, or a [`CompressedError`] if verification
+    /// fails.
+    pub fn verify(
+        &self,
+        proof: &SP1ReduceProof<BabyBearPoseidon2>,
+        sp1_public_inputs: &[u8],
+        sp1_vkey_hash: &[u32; DIGEST_SIZE],
+    ) -> Result<(), CompressedError> {
+        let SP1ReduceProof { vk, proof } = proof;
+
+        // The proof must be generated by one of the recursion programs of this SP1 version, with
+        // the FRI parameters of the verifier.
+        if !self.allowed_vks.contains(&vk.hash_babybear()) {
+            return Err(CompressedError::VkeyNotAllowed);
+        }
+        if vk.fri_params != self.fri_params {
+            return Err(CompressedError::FriParamsMismatch(vk.fri_params, self.fri_params));
+        }
+
+        let mut challenger = self.machine.config().challenger();
+        let machine_proof = MachineProof { shard_proofs: alloc::vec![proof.clone()] };
+        self.machine
+            .verify(vk, &machine_proof, &mut challenger)
+            .map_err(CompressedError::ProofVerificationFailed)?;
+
+        let public_values: &RecursionPublicValues<BabyBear> =
+            proof.public_values.as_slice().borrow();
+        let pv_array = public_values.as_array();
+        if poseidon2_hash(pv_array[..NUM_PV_ELMS_TO_HASH].to_vec()) != public_values.digest {
+            return Err(CompressedError::PublicValuesDigestMismatch);
+        }
+        if public_values.vk_root != self.vk_root {
+            return Err(CompressedError::VkRootMismatch);
+        }
+
+        // The proof must be fully reduced.
+        if public_values.is_complete != BabyBear::one() {
+            return Err(CompressedError::IncompleteProof);
+        }
+
+        let sp1_vk_digest = public_values.sp1_vk_digest.map(|x| x.as_canonical_u32());
+        if sp1_vk_digest != *sp1_vkey_hash {
+            return Err(CompressedError::Sp1VkeyHashMismatch);
+        }
+
+        let committed_value_digest = public_values
+            .committed_value_digest
+            .iter()
+            .flat_map(|w| w.0.iter().map(|x| x.as_canonical_u32() as u8))
+            .collect::<Vec<_>>();
+        if committed_value_digest != Sha256::digest(sp1_public_inputs).as_slice() {
+            return Err(CompressedError::CommittedValuesDigestMismatch);
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::boxed::Box;
+
 use thiserror_no_std::Error;
 
 #[derive(Debug, Error)]
@@ -8,8 +10,12 @@ pub enum Groth16Error {
     ProcessVerifyingKeyFailed,
     #[error("Prepare inputs failed")]
     PrepareInputsFailed,
+    #[error("Invalid proof: shorter than its vkey hash prefix")]
+    InvalidProof,
     #[error("General error")]
     GeneralError(#[from] crate::error::Error),
     #[error("Groth16 vkey hash mismatch")]
     Groth16VkeyHashMismatch,
+    #[error("Proof {index} of the batch is invalid: {error}")]
+    InvalidBatchProof { index: usize, error: Box<Groth16Error> },
 }
@@ -3,12 +3,15 @@ pub mod error;
 mod verify;
 
 pub(crate) use converter::{load_groth16_proof_from_bytes, load_groth16_verifying_key_from_bytes};
-use sha2::{Digest, Sha256};
 pub(crate) use verify::*;
 
+use alloc::{boxed::Box, vec::Vec};
+use bn::Fr;
 use error::Groth16Error;
 
-use crate::{bn254_public_values, decode_sp1_vkey_hash, error::Error};
+use crate::{
+    bn254_public_values, decode_sp1_vkey_hash, derive_batch_randomness, vk_hash_prefix, BatchEntry,
+};
 
 /// A verifier for Groth16 zero-knowledge proofs.
 #[derive(Debug)]
@@ -42,26 +45,95 @@ impl Groth16Verifier {
         sp1_vkey_hash: &str,
         groth16_vk: &[u8],
     ) -> Result<(), Groth16Error> {
-        // Hash the vk and get the first 4 bytes.
-        let groth16_vk_hash: [u8; 4] = Sha256::digest(groth16_vk)[..4]
-            .try_into()
-            .map_err(|_| Groth16Error::GeneralError(Error::InvalidData))?;
-
-        // Check to make sure that this proof was generated by the groth16 proving key corresponding to
-        // the given groth16_vk.
-        //
-        // SP1 prepends the raw Groth16 proof with the first 4 bytes of the groth16 vkey to
-        // facilitate this check.
-        if groth16_vk_hash != proof[..4] {
-            return Err(Groth16Error::Groth16VkeyHashMismatch);
-        }
+        let groth16_vk_hash = vk_hash_prefix(groth16_vk);
+        let (proof, public_inputs) =
+            decode_proof(proof, sp1_public_inputs, sp1_vkey_hash, &groth16_vk_hash)?;
+        let groth16_vk = load_groth16_verifying_key_from_bytes(groth16_vk)?;
+
+        verify_groth16_raw(&groth16_vk, &proof, &public_inputs)
+    }
 
-        let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
-        let public_inputs = bn254_public_values(&sp1_vkey_hash, sp1_public_inputs);
+    /// Verifies a batch of Groth16 proofs generated under the same Groth16 verifying key.
+    ///
+    /// The proofs are combined with random coefficients into a single multi-pairing, which is
+    /// much cheaper than verifying each proof on its own. The coefficients are derived by hashing
+    /// the whole batch, so they can't be chosen before the proofs are fixed. If the batch check
+    /// fails, the proofs are verified one by one to find the invalid one.
+    ///
+    /// # Arguments
+    ///
+    /// * `proofs` - The proofs, each with its SP1 public inputs and SP1 vkey hash, in the same
+    ///   format as the arguments of [`Groth16Verifier::verify`].
+    /// * `groth16_vk` - The Groth16 verifying key bytes.
+    ///
+    /// # Returns
+    ///
+    /// A success [`Result`] if every proof is valid, or a [`Groth16Error::InvalidBatchProof`] with
+    /// the index of the first invalid proof.
+    pub fn verify_batch(
+        proofs: &[Groth16BatchEntry<'_>],
+        groth16_vk: &[u8],
+    ) -> Result<(), Groth16Error> {
+        if proofs.is_empty() {
+            return Ok(());
+        }
 
-        let proof = load_groth16_proof_from_bytes(&proof[4..])?;
+        let groth16_vk_hash = vk_hash_prefix(groth16_vk);
         let groth16_vk = load_groth16_verifying_key_from_bytes(groth16_vk)?;
 
-        verify_groth16_raw(&groth16_vk, &proof, &public_inputs)
+        let mut decoded = Vec::with_capacity(proofs.len());
+        for (index, entry) in proofs.iter().enumerate() {
+            let proof = decode_proof(
+                entry.proof,
+                entry.sp1_public_inputs,
+                entry.sp1_vkey_hash,
+                &groth16_vk_hash,
+            )
+            .map_err(|error| Groth16Error::InvalidBatchProof { index, error: Box::new(error) })?;
+            decoded.push(proof);
+        }
+        let randomness = derive_batch_randomness(&groth16_vk_hash, proofs)?;
+
+        if verify_groth16_batch_raw(&groth16_vk, &decoded, &randomness).is_ok() {
+            return Ok(());
+        }
+
+        // Fall back to verifying each proof on its own to find which one is invalid.
+        for (index, (proof, public_inputs)) in decoded.iter().enumerate() {
+            verify_groth16_raw(&groth16_vk, proof, public_inputs).map_err(|error| {
+                Groth16Error::InvalidBatchProof { index, error: Box::new(error) }
+            })?;
+        }
+
+        Err(Groth16Error::ProofVerificationFailed)
     }
 }
+
+/// A proof to verify with [`Groth16Verifier::verify_batch`].
+pub type Groth16BatchEntry<'a> = BatchEntry<'a>;
+
+/// Decodes a proof and its public inputs, checking that it was generated by the Groth16 proving
+/// key with the given vk hash.
+fn decode_proof(
+    proof: &[u8],
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &str,
+    groth16_vk_hash: &[u8; 4],
+) -> Result<(Groth16Proof, [Fr; 2]), Groth16Error> {
+    // Check to make sure that this proof was generated by the groth16 proving key corresponding to
+    // the given groth16_vk.
+    //
+    // SP1 prepends the raw Groth16 proof with the first 4 bytes of the groth16 vkey to
+    // facilitate this check.
+    let (prefix, proof) = proof.split_at_checked(4).ok_or(Groth16Error::InvalidProof)?;
+    if groth16_vk_hash != prefix {
+        return Err(Groth16Error::Groth16VkeyHashMismatch);
+    }
+
+    let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+    let public_inputs = bn254_public_values(&sp1_vkey_hash, sp1_public_inputs);
+
+    let proof = load_groth16_proof_from_bytes(proof)?;
+
+    Ok((proof, public_inputs))
+}
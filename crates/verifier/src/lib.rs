@@ -1,11 +1,18 @@
 //! This crate provides verifiers for SP1 Groth16 and Plonk BN254 proofs in a no-std environment.
 //! It is patched for efficient verification within the SP1 ZKVM context.
+//!
+//! With the `compressed` feature, it also provides a verifier for compressed SP1 STARK proofs. This
+//! verifier is not `no_std` yet: it depends on the `std`-only SP1 machine crates, and the feature
+//! enables `std`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 extern crate alloc;
 
 use lazy_static::lazy_static;
 
+/// The SP1 circuit version of the embedded verifying keys.
+pub const SP1_CIRCUIT_VERSION: &str = "v3.0.0";
+
 lazy_static! {
     /// The PLONK verifying key for this SP1 version.
     pub static ref PLONK_VK_BYTES: &'static [u8] = include_bytes!("../bn254-vk/plonk_vk.bin");
@@ -24,12 +31,20 @@ mod utils;
 pub use utils::*;
 
 pub use groth16::error::Groth16Error;
-pub use groth16::Groth16Verifier;
+pub use groth16::{Groth16BatchEntry, Groth16Verifier};
 mod groth16;
 
 pub use plonk::error::PlonkError;
-pub use plonk::PlonkVerifier;
+pub use plonk::{PlonkBatchEntry, PlonkVerifier};
 mod plonk;
 
+pub use registry::{error::RegistryError, ProofSystem, RegisteredVerifyingKey, VerifierRegistry};
+mod registry;
+
+#[cfg(feature = "compressed")]
+pub use compressed::{error::CompressedError, CompressedVerifier};
+#[cfg(feature = "compressed")]
+mod compressed;
+
 #[cfg(test)]
 mod tests;
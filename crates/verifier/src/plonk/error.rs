@@ -1,3 +1,5 @@
+use alloc::boxed::Box;
+
 use thiserror_no_std::Error;
 
 #[derive(Error, Debug)]
@@ -28,6 +30,10 @@ pub enum PlonkError {
     TranscriptError,
     #[error("Plonk vkey hash mismatch")]
     PlonkVkeyHashMismatch,
+    #[error("Invalid proof: shorter than its vkey hash prefix")]
+    InvalidProof,
     #[error("General error")]
     GeneralError(#[from] crate::error::Error),
+    #[error("Proof {index} of the batch is invalid: {error}")]
+    InvalidBatchProof { index: usize, error: Box<PlonkError> },
 }
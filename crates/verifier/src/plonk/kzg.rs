@@ -129,13 +129,21 @@ pub(crate) fn fold_proof(
     Ok((open_proof, folded_digests))
 }
 
-pub(crate) fn batch_verify_multi_points(
+/// The G1 points of a KZG pairing check `e(digest, G₂) · e(quotient, [α]G₂) = 1`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PairingInput {
+    pub(crate) digest: AffineG1,
+    pub(crate) quotient: AffineG1,
+}
+
+/// Folds opening proofs at multiple points into the inputs of a single pairing check.
+pub(crate) fn fold_multi_points(
     digests: Vec<Digest>,
     proofs: Vec<OpeningProof>,
     points: Vec<Fr>,
     u: Fr,
     vk: &KZGVerifyingKey,
-) -> Result<(), PlonkError> {
+) -> Result<PairingInput, PlonkError> {
     let nb_digests = digests.len();
     let nb_proofs = proofs.len();
     let nb_points = points.len();
@@ -182,8 +190,13 @@ pub(crate) fn batch_verify_multi_points(
     folded_digests = folded_digests + folded_points_quotients;
     folded_quotients = -folded_quotients;
 
+    Ok(PairingInput { digest: folded_digests, quotient: folded_quotients })
+}
+
+/// Checks the pairing `e(digest, G₂) · e(quotient, [α]G₂) = 1`.
+pub(crate) fn verify_pairing(input: &PairingInput, vk: &KZGVerifyingKey) -> Result<(), PlonkError> {
     let pairing_result =
-        pairing_batch(&[(folded_digests.into(), vk.g2[0]), (folded_quotients.into(), vk.g2[1])]);
+        pairing_batch(&[(input.digest.into(), vk.g2[0]), (input.quotient.into(), vk.g2[1])]);
 
     if !pairing_result.is_one() {
         return Err(PlonkError::PairingCheckFailed);
@@ -191,3 +204,26 @@ pub(crate) fn batch_verify_multi_points(
 
     Ok(())
 }
+
+/// Checks the pairings of several proofs at once.
+///
+/// The pairing inputs are folded with the given random coefficients, so that a single pairing
+/// check holds if and only if all of them hold, except with negligible probability.
+pub(crate) fn batch_verify_pairings(
+    inputs: &[PairingInput],
+    randomness: &[Fr],
+    vk: &KZGVerifyingKey,
+) -> Result<(), PlonkError> {
+    if inputs.len() != randomness.len() {
+        return Err(PlonkError::InvalidNumberOfDigests);
+    }
+
+    let digests = inputs.iter().map(|input| input.digest).collect::<Vec<_>>();
+    let quotients = inputs.iter().map(|input| input.quotient).collect::<Vec<_>>();
+    let folded = PairingInput {
+        digest: AffineG1::msm(&digests, randomness),
+        quotient: AffineG1::msm(&quotients, randomness),
+    };
+
+    verify_pairing(&folded, vk)
+}
@@ -15,12 +15,16 @@ pub(crate) mod error;
 
 pub(crate) use converter::{load_plonk_proof_from_bytes, load_plonk_verifying_key_from_bytes};
 pub(crate) use proof::PlonkProof;
-pub(crate) use verify::verify_plonk_raw;
+pub(crate) use verify::{prepare_plonk_pairing, verify_plonk_raw, PlonkVerifyingKey};
 
+use alloc::{boxed::Box, vec::Vec};
+use bn::Fr;
 use error::PlonkError;
-use sha2::{Digest, Sha256};
 
-use crate::{bn254_public_values, decode_sp1_vkey_hash, error::Error};
+use crate::{
+    bn254_public_values, decode_sp1_vkey_hash, derive_batch_randomness, vk_hash_prefix, BatchEntry,
+};
+
 /// A verifier for Plonk zero-knowledge proofs.
 #[derive(Debug)]
 pub struct PlonkVerifier;
@@ -51,26 +55,95 @@ impl PlonkVerifier {
         sp1_vkey_hash: &str,
         plonk_vk: &[u8],
     ) -> Result<(), PlonkError> {
-        // Hash the vk and get the first 4 bytes.
-        let plonk_vk_hash: [u8; 4] = Sha256::digest(plonk_vk)[..4]
-            .try_into()
-            .map_err(|_| PlonkError::GeneralError(Error::InvalidData))?;
-
-        // Check to make sure that this proof was generated by the plonk proving key corresponding to
-        // the given plonk vk.
-        //
-        // SP1 prepends the raw Plonk proof with the first 4 bytes of the plonk vkey to
-        // facilitate this check.
-        if plonk_vk_hash != proof[..4] {
-            return Err(PlonkError::PlonkVkeyHashMismatch);
-        }
+        let plonk_vk_hash = vk_hash_prefix(plonk_vk);
+        let plonk_vk = load_plonk_verifying_key_from_bytes(plonk_vk)?;
+        let (proof, public_inputs) =
+            decode_proof(proof, sp1_public_inputs, sp1_vkey_hash, &plonk_vk_hash, &plonk_vk)?;
+
+        verify_plonk_raw(&plonk_vk, &proof, &public_inputs)
+    }
 
-        let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
-        let public_inputs = bn254_public_values(&sp1_vkey_hash, sp1_public_inputs);
+    /// Verifies a batch of Plonk proofs generated under the same Plonk verifying key.
+    ///
+    /// Each proof is checked up to its final KZG pairing check, and the pairing checks of all proofs
+    /// are then folded with random coefficients into a single multi-pairing. If the batch check
+    /// fails, the pairing checks are run one by one to find the invalid proof.
+    ///
+    /// # Arguments
+    ///
+    /// * `proofs` - The proofs, each with its SP1 public inputs and SP1 vkey hash, in the same
+    ///   format as the arguments of [`PlonkVerifier::verify`].
+    /// * `plonk_vk` - The Plonk verifying key bytes.
+    ///
+    /// # Returns
+    ///
+    /// A success [`Result`] if every proof is valid, or a [`PlonkError::InvalidBatchProof`] with
+    /// the index of the first invalid proof.
+    pub fn verify_batch(proofs: &[PlonkBatchEntry<'_>], plonk_vk: &[u8]) -> Result<(), PlonkError> {
+        if proofs.is_empty() {
+            return Ok(());
+        }
 
+        let plonk_vk_hash = vk_hash_prefix(plonk_vk);
         let plonk_vk = load_plonk_verifying_key_from_bytes(plonk_vk)?;
-        let proof = load_plonk_proof_from_bytes(&proof[4..], plonk_vk.qcp.len())?;
 
-        verify_plonk_raw(&plonk_vk, &proof, &public_inputs)
+        let mut pairing_inputs = Vec::with_capacity(proofs.len());
+        for (index, entry) in proofs.iter().enumerate() {
+            let pairing_input = decode_proof(
+                entry.proof,
+                entry.sp1_public_inputs,
+                entry.sp1_vkey_hash,
+                &plonk_vk_hash,
+                &plonk_vk,
+            )
+            .and_then(|(proof, public_inputs)| {
+                prepare_plonk_pairing(&plonk_vk, &proof, &public_inputs)
+            })
+            .map_err(|error| PlonkError::InvalidBatchProof { index, error: Box::new(error) })?;
+            pairing_inputs.push(pairing_input);
+        }
+        let randomness = derive_batch_randomness(&plonk_vk_hash, proofs)?;
+
+        if kzg::batch_verify_pairings(&pairing_inputs, &randomness, &plonk_vk.kzg).is_ok() {
+            return Ok(());
+        }
+
+        // Fall back to checking each pairing on its own to find which proof is invalid.
+        for (index, pairing_input) in pairing_inputs.iter().enumerate() {
+            kzg::verify_pairing(pairing_input, &plonk_vk.kzg)
+                .map_err(|error| PlonkError::InvalidBatchProof { index, error: Box::new(error) })?;
+        }
+
+        Err(PlonkError::PairingCheckFailed)
     }
 }
+
+/// A proof to verify with [`PlonkVerifier::verify_batch`].
+pub type PlonkBatchEntry<'a> = BatchEntry<'a>;
+
+/// Decodes a proof and its public inputs, checking that it was generated by the Plonk proving key
+/// with the given vk hash.
+fn decode_proof(
+    proof: &[u8],
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &str,
+    plonk_vk_hash: &[u8; 4],
+    plonk_vk: &PlonkVerifyingKey,
+) -> Result<(PlonkProof, [Fr; 2]), PlonkError> {
+    // Check to make sure that this proof was generated by the plonk proving key corresponding to
+    // the given plonk vk.
+    //
+    // SP1 prepends the raw Plonk proof with the first 4 bytes of the plonk vkey to
+    // facilitate this check.
+    let (prefix, proof) = proof.split_at_checked(4).ok_or(PlonkError::InvalidProof)?;
+    if plonk_vk_hash != prefix {
+        return Err(PlonkError::PlonkVkeyHashMismatch);
+    }
+
+    let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+    let public_inputs = bn254_public_values(&sp1_vkey_hash, sp1_public_inputs);
+
+    let proof = load_plonk_proof_from_bytes(proof, plonk_vk.qcp.len())?;
+
+    Ok((proof, public_inputs))
+}
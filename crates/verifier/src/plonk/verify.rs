@@ -49,6 +49,19 @@ pub(crate) fn verify_plonk_raw(
     proof: &PlonkProof,
     public_inputs: &[Fr],
 ) -> Result<(), PlonkError> {
+    let pairing_input = prepare_plonk_pairing(vk, proof, public_inputs)?;
+    kzg::verify_pairing(&pairing_input, &vk.kzg)
+}
+
+/// Runs every check of [`verify_plonk_raw`] except the final KZG pairing check, and returns the
+/// inputs of that pairing check instead.
+///
+/// This lets the pairing checks of several proofs under the same verifying key be batched.
+pub(crate) fn prepare_plonk_pairing(
+    vk: &PlonkVerifyingKey,
+    proof: &PlonkProof,
+    public_inputs: &[Fr],
+) -> Result<kzg::PairingInput, PlonkError> {
     // Check if the number of BSB22 commitments matches the number of Qcp in the verifying key
     if proof.bsb22_commitments.len() != vk.qcp.len() {
         return Err(PlonkError::Bsb22CommitmentMismatch);
@@ -305,16 +318,14 @@ pub(crate) fn verify_plonk_raw(
 
     let folded_digest: AffineG1 = folded_digest;
 
-    // Perform batch verification
-    kzg::batch_verify_multi_points(
+    // Fold the openings into the inputs of the final pairing check
+    kzg::fold_multi_points(
         [folded_digest, proof.z].to_vec(),
         [folded_proof, proof.z_shifted_opening].to_vec(),
         [zeta, shifted_zeta].to_vec(),
         u,
         &vk.kzg,
-    )?;
-
-    Ok(())
+    )
 }
 
 /// Binds all plonk public data to the transcript.
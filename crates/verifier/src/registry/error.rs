@@ -0,0 +1,15 @@
+use thiserror_no_std::Error;
+
+use crate::{Groth16Error, PlonkError};
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("Proof is too short to contain a vkey hash prefix")]
+    ProofTooShort,
+    #[error("No verifying key registered for vkey hash prefix {0:?}")]
+    UnknownVkeyHash([u8; 4]),
+    #[error("Groth16 verification failed: {0}")]
+    Groth16(#[from] Groth16Error),
+    #[error("Plonk verification failed: {0}")]
+    Plonk(#[from] PlonkError),
+}
@@ -0,0 +1,114 @@
+pub mod error;
+
+use alloc::collections::BTreeMap;
+
+use error::RegistryError;
+
+use crate::{utils::vk_hash_prefix, Groth16Verifier, PlonkVerifier};
+
+/// The proof system a verifying key belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofSystem {
+    Groth16,
+    Plonk,
+}
+
+/// A verifying key registered in a [`VerifierRegistry`].
+#[derive(Debug, Clone, Copy)]
+pub struct RegisteredVerifyingKey<'a> {
+    /// The SP1 circuit version the key belongs to, e.g. `v3.0.0`.
+    pub version: &'a str,
+    /// The proof system of the key.
+    pub system: ProofSystem,
+    /// The verifying key bytes.
+    pub vk: &'a [u8],
+}
+
+/// A registry of Groth16 and Plonk verifying keys across SP1 versions.
+///
+/// Keys are indexed by the first 4 bytes of their SHA-256 hash, which SP1 prepends to the proof
+/// bytes returned by `SP1ProofWithPublicValues::bytes`. This lets [`VerifierRegistry::verify_any`]
+/// pick the right key for a proof without knowing which SP1 version generated it.
+#[derive(Debug, Clone, Default)]
+pub struct VerifierRegistry<'a> {
+    keys: BTreeMap<[u8; 4], RegisteredVerifyingKey<'a>>,
+}
+
+impl<'a> VerifierRegistry<'a> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry containing the verifying keys embedded in this crate.
+    pub fn with_embedded_keys() -> VerifierRegistry<'static> {
+        let mut registry = VerifierRegistry::new();
+        registry.register(
+            crate::SP1_CIRCUIT_VERSION,
+            ProofSystem::Groth16,
+            *crate::GROTH16_VK_BYTES,
+        );
+        registry.register(crate::SP1_CIRCUIT_VERSION, ProofSystem::Plonk, *crate::PLONK_VK_BYTES);
+        registry
+    }
+
+    /// Registers a verifying key.
+    ///
+    /// Returns the key previously registered under the same vkey hash prefix, if any.
+    pub fn register(
+        &mut self,
+        version: &'a str,
+        system: ProofSystem,
+        vk: &'a [u8],
+    ) -> Option<RegisteredVerifyingKey<'a>> {
+        let prefix = vk_hash_prefix(vk);
+        self.keys.insert(prefix, RegisteredVerifyingKey { version, system, vk })
+    }
+
+    /// Returns the verifying key registered for the given proof, if any.
+    pub fn get(&self, proof: &[u8]) -> Option<&RegisteredVerifyingKey<'a>> {
+        let prefix: [u8; 4] = proof.get(..4)?.try_into().ok()?;
+        self.keys.get(&prefix)
+    }
+
+    /// Returns an iterator over the registered verifying keys.
+    pub fn keys(&self) -> impl Iterator<Item = &RegisteredVerifyingKey<'a>> {
+        self.keys.values()
+    }
+
+    /// Verifies a Groth16 or Plonk proof with the registered verifying key it was generated for.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - The proof bytes, as returned by `SP1ProofWithPublicValues::bytes`.
+    /// * `sp1_public_inputs` - The SP1 public inputs.
+    /// * `sp1_vkey_hash` - The SP1 vkey hash, as returned by `vk.bytes32()`.
+    ///
+    /// # Returns
+    ///
+    /// The verifying key that matched if verification succeeds, or a [`RegistryError`] if no key
+    /// is registered for the proof or verification fails.
+    pub fn verify_any(
+        &self,
+        proof: &[u8],
+        sp1_public_inputs: &[u8],
+        sp1_vkey_hash: &str,
+    ) -> Result<&RegisteredVerifyingKey<'a>, RegistryError> {
+        let prefix: [u8; 4] = proof
+            .get(..4)
+            .and_then(|prefix| prefix.try_into().ok())
+            .ok_or(RegistryError::ProofTooShort)?;
+        let key = self.keys.get(&prefix).ok_or(RegistryError::UnknownVkeyHash(prefix))?;
+
+        match key.system {
+            ProofSystem::Groth16 => {
+                Groth16Verifier::verify(proof, sp1_public_inputs, sp1_vkey_hash, key.vk)?
+            }
+            ProofSystem::Plonk => {
+                PlonkVerifier::verify(proof, sp1_public_inputs, sp1_vkey_hash, key.vk)?
+            }
+        }
+
+        Ok(key)
+    }
+}
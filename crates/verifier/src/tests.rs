@@ -18,6 +18,47 @@ fn test_verify_groth16() {
 
     crate::Groth16Verifier::verify(&proof, &public_inputs, vkey_hash, &crate::GROTH16_VK_BYTES)
         .expect("Groth16 proof is invalid");
+
+    // A proof shorter than its vkey hash prefix is rejected without panicking.
+    assert!(matches!(
+        crate::Groth16Verifier::verify(
+            &proof[..3],
+            &public_inputs,
+            vkey_hash,
+            &crate::GROTH16_VK_BYTES
+        ),
+        Err(crate::Groth16Error::InvalidProof)
+    ));
+}
+
+#[test]
+fn test_verify_groth16_batch() {
+    use crate::{Groth16BatchEntry, Groth16Error};
+
+    let proof_file = "test_binaries/fibonacci-groth16.bin";
+    let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(proof_file).unwrap();
+
+    let proof = sp1_proof_with_public_values.bytes();
+    let public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    let vkey_hash = "0x00e60860c07bfc6e4c480286c0ddbb879674eb47f84b4ef041cf858b17aa0ed1";
+
+    let entry = Groth16BatchEntry {
+        proof: &proof,
+        sp1_public_inputs: &public_inputs,
+        sp1_vkey_hash: vkey_hash,
+    };
+    crate::Groth16Verifier::verify_batch(&[entry; 4], &crate::GROTH16_VK_BYTES)
+        .expect("Groth16 batch is invalid");
+
+    // Tamper with the public inputs of one proof and check that it is identified.
+    let mut other_public_inputs = public_inputs.clone();
+    other_public_inputs[0] ^= 1;
+    let mut entries = [entry; 4];
+    entries[2].sp1_public_inputs = &other_public_inputs;
+    assert!(matches!(
+        crate::Groth16Verifier::verify_batch(&entries, &crate::GROTH16_VK_BYTES),
+        Err(Groth16Error::InvalidBatchProof { index: 2, .. })
+    ));
 }
 
 #[test]
@@ -36,6 +77,47 @@ fn test_verify_plonk() {
 
     crate::PlonkVerifier::verify(&proof, &public_inputs, vkey_hash, &crate::PLONK_VK_BYTES)
         .expect("Plonk proof is invalid");
+
+    // A proof shorter than its vkey hash prefix is rejected without panicking.
+    assert!(matches!(
+        crate::PlonkVerifier::verify(
+            &proof[..3],
+            &public_inputs,
+            vkey_hash,
+            &crate::PLONK_VK_BYTES
+        ),
+        Err(crate::PlonkError::InvalidProof)
+    ));
+}
+
+#[test]
+fn test_verify_plonk_batch() {
+    use crate::{PlonkBatchEntry, PlonkError};
+
+    let proof_file = "test_binaries/fibonacci-plonk.bin";
+    let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(proof_file).unwrap();
+
+    let proof = sp1_proof_with_public_values.bytes();
+    let public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    let vkey_hash = "0x00e60860c07bfc6e4c480286c0ddbb879674eb47f84b4ef041cf858b17aa0ed1";
+
+    let entry = PlonkBatchEntry {
+        proof: &proof,
+        sp1_public_inputs: &public_inputs,
+        sp1_vkey_hash: vkey_hash,
+    };
+    crate::PlonkVerifier::verify_batch(&[entry; 4], &crate::PLONK_VK_BYTES)
+        .expect("Plonk batch is invalid");
+
+    // Tamper with the public inputs of one proof and check that it is identified.
+    let mut other_public_inputs = public_inputs.clone();
+    other_public_inputs[0] ^= 1;
+    let mut entries = [entry; 4];
+    entries[1].sp1_public_inputs = &other_public_inputs;
+    assert!(matches!(
+        crate::PlonkVerifier::verify_batch(&entries, &crate::PLONK_VK_BYTES),
+        Err(PlonkError::InvalidBatchProof { index: 1, .. })
+    ));
 }
 
 #[test]
@@ -50,3 +132,84 @@ fn test_vkeys() {
     let s3_vkey_bytes = std::fs::read(s3_vkey_path).unwrap();
     assert_eq!(s3_vkey_bytes, *crate::PLONK_VK_BYTES);
 }
+
+#[test]
+fn test_verify_any() {
+    use crate::{ProofSystem, RegistryError, VerifierRegistry};
+
+    // This vkey hash was derived by calling `vk.bytes32()` on the verifying key.
+    let vkey_hash = "0x00e60860c07bfc6e4c480286c0ddbb879674eb47f84b4ef041cf858b17aa0ed1";
+    let registry = VerifierRegistry::with_embedded_keys();
+
+    for (proof_file, system) in [
+        ("test_binaries/fibonacci-groth16.bin", ProofSystem::Groth16),
+        ("test_binaries/fibonacci-plonk.bin", ProofSystem::Plonk),
+    ] {
+        let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(proof_file).unwrap();
+        let proof = sp1_proof_with_public_values.bytes();
+        let public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+
+        let key = registry.verify_any(&proof, &public_inputs, vkey_hash).expect("proof is invalid");
+        assert_eq!(key.system, system);
+        assert_eq!(key.version, crate::SP1_CIRCUIT_VERSION);
+
+        assert!(matches!(
+            VerifierRegistry::new().verify_any(&proof, &public_inputs, vkey_hash),
+            Err(RegistryError::UnknownVkeyHash(_))
+        ));
+    }
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+fn test_verify_compressed() {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use sp1_sdk::{HashableKey, ProverClient, SP1Proof, SP1Stdin};
+    use sp1_stark::FriParams;
+
+    use crate::{CompressedError, CompressedVerifier};
+
+    let elf = include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    let client = ProverClient::local();
+    let (pk, vk) = client.setup(elf);
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&10u32);
+    let proof = client.prove(&pk, stdin).compressed().run().unwrap();
+    let SP1Proof::Compressed(reduce_proof) = &proof.proof else { panic!("not a compressed proof") };
+    let public_inputs = proof.public_values.to_vec();
+    let vkey_hash = vk.hash_u32();
+
+    let prover = client.prover.sp1_prover();
+    let allowed_vks = prover.allowed_vk_map.keys().copied().collect::<Vec<_>>();
+    let verifier = CompressedVerifier::new(prover.vk_root, allowed_vks.clone());
+    verifier.verify(reduce_proof, &public_inputs, &vkey_hash).expect("proof is invalid");
+
+    // Tamper with the public inputs, the vkey hash and the proof.
+    let mut other_public_inputs = public_inputs.clone();
+    other_public_inputs[0] ^= 1;
+    assert!(matches!(
+        verifier.verify(reduce_proof, &other_public_inputs, &vkey_hash),
+        Err(CompressedError::CommittedValuesDigestMismatch)
+    ));
+    let mut other_vkey_hash = vkey_hash;
+    other_vkey_hash[0] ^= 1;
+    assert!(matches!(
+        verifier.verify(reduce_proof, &public_inputs, &other_vkey_hash),
+        Err(CompressedError::Sp1VkeyHashMismatch)
+    ));
+    let mut tampered = reduce_proof.clone();
+    tampered.proof.public_values[0] += BabyBear::one();
+    assert!(matches!(
+        verifier.verify(&tampered, &public_inputs, &vkey_hash),
+        Err(CompressedError::ProofVerificationFailed(_))
+    ));
+
+    // A verifier for other FRI parameters rejects the proof.
+    let verifier =
+        CompressedVerifier::with_fri_params(prover.vk_root, allowed_vks, FriParams::compressed());
+    assert!(matches!(
+        verifier.verify(reduce_proof, &public_inputs, &vkey_hash),
+        Err(CompressedError::FriParamsMismatch(..))
+    ));
+}
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use bn::Fr;
 use sha2::{Digest, Sha256};
 
@@ -27,3 +28,51 @@ pub fn decode_sp1_vkey_hash(sp1_vkey_hash: &str) -> Result<[u8; 32], Error> {
     let bytes = hex::decode(&sp1_vkey_hash[2..]).map_err(|_| Error::InvalidProgramVkeyHash)?;
     bytes.try_into().map_err(|_| Error::InvalidProgramVkeyHash)
 }
+
+/// Returns the first 4 bytes of the SHA-256 hash of a Groth16 or Plonk verifying key, which SP1
+/// prepends to the proofs generated with it.
+pub(crate) fn vk_hash_prefix(vk: &[u8]) -> [u8; 4] {
+    let mut prefix = [0u8; 4];
+    prefix.copy_from_slice(&Sha256::digest(vk)[..4]);
+    prefix
+}
+
+/// A proof to verify in a batch with [`crate::Groth16Verifier::verify_batch`] or
+/// [`crate::PlonkVerifier::verify_batch`].
+#[derive(Debug, Clone, Copy)]
+pub struct BatchEntry<'a> {
+    /// The proof bytes.
+    pub proof: &'a [u8],
+    /// The SP1 public inputs.
+    pub sp1_public_inputs: &'a [u8],
+    /// The SP1 vkey hash, as returned by `vk.bytes32()`.
+    pub sp1_vkey_hash: &'a str,
+}
+
+/// Derives the random coefficients used to batch the verification of several proofs.
+///
+/// The coefficients are derived by hashing the vk hash together with every proof, public inputs
+/// and vkey hash of the batch, so they can't be chosen before the proofs are fixed.
+pub(crate) fn derive_batch_randomness(
+    vk_hash: &[u8; 4],
+    entries: &[BatchEntry<'_>],
+) -> Result<Vec<Fr>, Error> {
+    let num_entries = entries.len() as u64;
+
+    let mut transcript = Sha256::new();
+    transcript.update(vk_hash);
+    for entry in entries {
+        for bytes in [entry.proof, entry.sp1_public_inputs, entry.sp1_vkey_hash.as_bytes()] {
+            transcript.update((bytes.len() as u64).to_be_bytes());
+            transcript.update(bytes);
+        }
+    }
+    let seed = transcript.finalize();
+
+    (0..num_entries)
+        .map(|i| {
+            let bytes = Sha256::new().chain_update(seed).chain_update(i.to_be_bytes()).finalize();
+            Fr::from_bytes_be_mod_order(&bytes).map_err(|_| Error::FailedToGetFrFromRandomBytes)
+        })
+        .collect()
+}
@@ -6,6 +6,27 @@ pub mod syscalls;
 #[cfg(feature = "lib")]
 pub mod io {
     pub use sp1_lib::io::*;
+
+    /// Seed the zkVM random number generator from the public values committed so far.
+    ///
+    /// The seed is the SHA-256 digest of the public values stream at the time of the call, so the
+    /// randomness is bound to the committed inputs. Returns the derived seed.
+    ///
+    /// ### Examples
+    /// ```ignore
+    /// sp1_zkvm::io::commit(&input);
+    /// let seed = sp1_zkvm::io::seed_rng_from_public_values();
+    /// ```
+    #[cfg(target_os = "zkvm")]
+    pub fn seed_rng_from_public_values() -> [u8; 32] {
+        use sha2::Digest;
+
+        let hasher = unsafe { (*core::ptr::addr_of!(crate::zkvm::PUBLIC_VALUES_HASHER)).clone() };
+        let seed: [u8; 32] =
+            hasher.expect("public values hasher not initialized").finalize().into();
+        set_rng_seed(&seed);
+        seed
+    }
 }
 
 #[cfg(feature = "lib")]
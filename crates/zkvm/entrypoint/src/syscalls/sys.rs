@@ -1,33 +1,48 @@
-use std::sync::Mutex;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
 
 use lazy_static::lazy_static;
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
 use crate::syscalls::{syscall_halt, syscall_write};
 
-/// The random number generator seed for the zkVM.
+/// The default random number generator seed for the zkVM.
 ///
-/// In the future, we can pass in this seed from the host or have the verifier generate it.
+/// This seed is used until the program seeds the generator itself with [`sys_rand_seed`].
 const PRNG_SEED: u64 = 0x123456789abcdef0;
 
 lazy_static! {
     /// A lazy static to generate a global random number generator.
-    static ref RNG: Mutex<StdRng> = Mutex::new(StdRng::seed_from_u64(PRNG_SEED));
+    ///
+    /// The generator is a ChaCha20-based DRBG, so the output stream is fully determined by its
+    /// seed and stable across versions of the `rand` crate.
+    static ref RNG: Mutex<ChaCha20Rng> = Mutex::new(ChaCha20Rng::seed_from_u64(PRNG_SEED));
 }
 
+/// Whether the global random number generator has been seeded by the program.
+static RNG_SEEDED: AtomicBool = AtomicBool::new(false);
+
 /// A lazy static to print a warning once for using the `sys_rand` system call.
 static SYS_RAND_WARNING: std::sync::Once = std::sync::Once::new();
 
 /// Generates random bytes.
 ///
+/// If the program has not seeded the generator with [`sys_rand_seed`], the bytes are derived from
+/// a fixed seed and a warning is printed.
+///
 /// # Safety
 ///
 /// Make sure that `buf` has at least `nwords` words.
 #[no_mangle]
 pub unsafe extern "C" fn sys_rand(recv_buf: *mut u8, words: usize) {
-    SYS_RAND_WARNING.call_once(|| {
-        println!("WARNING: Using insecure random number generator.");
-    });
+    if !RNG_SEEDED.load(Ordering::Acquire) {
+        SYS_RAND_WARNING.call_once(|| {
+            println!("WARNING: Using insecure random number generator.");
+        });
+    }
     let mut rng = RNG.lock().unwrap();
     for i in 0..words {
         let element = recv_buf.add(i);
@@ -35,6 +50,21 @@ pub unsafe extern "C" fn sys_rand(recv_buf: *mut u8, words: usize) {
     }
 }
 
+/// Seeds the random number generator used by [`sys_rand`].
+///
+/// All randomness produced afterwards (including through `getrandom` and `rand::thread_rng`) is
+/// a deterministic function of `seed`.
+///
+/// # Safety
+///
+/// Make sure that `seed` points to 32 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sys_rand_seed(seed: *const [u8; 32]) {
+    let mut rng = RNG.lock().unwrap();
+    *rng = ChaCha20Rng::from_seed(*seed);
+    RNG_SEEDED.store(true, Ordering::Release);
+}
+
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
 pub unsafe extern "C" fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
@@ -66,3 +96,32 @@ pub fn sys_write(fd: u32, write_buf: *const u8, nbytes: usize) {
         syscall_write(fd, write_buf, nbytes);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws `len` bytes from [`sys_rand`], the way `getrandom` does in the guest.
+    fn getrandom(len: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; len];
+        unsafe { sys_rand(buf.as_mut_ptr(), buf.len()) };
+        buf
+    }
+
+    #[test]
+    fn test_sys_rand_seed_is_deterministic() {
+        let seed = [7u8; 32];
+        unsafe { sys_rand_seed(&seed) };
+        let first = getrandom(64);
+        unsafe { sys_rand_seed(&seed) };
+        assert_eq!(getrandom(64), first);
+
+        // The output is the ChaCha20 stream of the seed, which does not depend on the version of
+        // the `rand` crate.
+        let mut expected = ChaCha20Rng::from_seed(seed);
+        assert_eq!(first, (0..64).map(|_| expected.gen::<u8>()).collect::<Vec<_>>());
+
+        unsafe { sys_rand_seed(&[8u8; 32]) };
+        assert_ne!(getrandom(64), first);
+    }
+}
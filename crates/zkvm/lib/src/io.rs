@@ -1,9 +1,10 @@
 #![allow(unused_unsafe)]
-use crate::{syscall_hint_len, syscall_hint_read, syscall_write};
+use crate::{codec::Codec, sys_rand_seed, syscall_hint_len, syscall_hint_read, syscall_write};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     alloc::Layout,
     io::{Result, Write},
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 /// The file descriptor for public values.
@@ -21,6 +22,12 @@ pub const FD_ECRECOVER_HOOK_2: u32 = 7;
 /// The file descriptor through which to access `hook_ed_decompress`.
 pub const FD_EDDECOMPRESS: u32 = 8;
 
+/// The file descriptor for leaves committed through a Merkle root.
+pub const FD_MERKLE_LEAVES: u32 = 9;
+
+/// Whether the program has already committed its Merkle leaves.
+static MERKLE_LEAVES_COMMITTED: AtomicBool = AtomicBool::new(false);
+
 /// A writer that writes to a file descriptor inside the zkVM.
 struct SyscallWriter {
     fd: u32,
@@ -73,6 +80,54 @@ pub fn read_vec() -> Vec<u8> {
     vec
 }
 
+/// The alignment of buffers read with [`read_archived`], matching rkyv's `AlignedVec`.
+#[cfg(feature = "rkyv")]
+const ARCHIVE_ALIGNMENT: usize = 16;
+
+/// Read an rkyv archived object from the input stream and access it in place.
+///
+/// The bytes are read directly into a buffer aligned for the archive and validated, so no copy or
+/// deserialization of the object takes place. The value should be written on the host with
+/// `SP1Stdin::write_archived`.
+///
+/// ### Examples
+/// ```ignore
+/// use rkyv::{Archive, Deserialize, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// #[archive(check_bytes)]
+/// struct MyStruct {
+///     a: u32,
+///     b: Vec<u8>,
+/// }
+///
+/// let data: &ArchivedMyStruct = sp1_zkvm::io::read_archived::<MyStruct>();
+/// ```
+#[cfg(feature = "rkyv")]
+pub fn read_archived<T>() -> &'static T::Archived
+where
+    T: rkyv::Archive,
+    T::Archived: for<'a> rkyv::CheckBytes<rkyv::validation::validators::DefaultValidator<'a>>,
+{
+    // Round up to the nearest multiple of 4 so that the memory allocated is in whole words
+    let len = unsafe { syscall_hint_len() };
+    let capacity = ((len + 3) / 4 * 4).max(4);
+
+    // Allocate a buffer of the required length that is aligned for the archive
+    let layout = Layout::from_size_align(capacity, ARCHIVE_ALIGNMENT).expect("vec is too large");
+    let ptr = unsafe { std::alloc::alloc(layout) };
+
+    // SAFETY:
+    // 1. `ptr` was freshly allocated with a valid layout, so the hint read writes to uninitialized
+    //    memory.
+    // 2. The buffer is never deallocated, so it lives for the rest of the program.
+    let bytes: &'static [u8] = unsafe {
+        syscall_hint_read(ptr, len);
+        std::slice::from_raw_parts(ptr, len)
+    };
+    rkyv::check_archived_root::<T>(bytes).expect("archive validation failed")
+}
+
 /// Read a deserializable object from the input stream.
 ///
 /// ### Examples
@@ -92,6 +147,56 @@ pub fn read<T: DeserializeOwned>() -> T {
     bincode::deserialize(&vec).expect("deserialization failed")
 }
 
+/// Read an object from the input stream, decoding it with the codec `C`.
+///
+/// The value should be written on the host with `SP1Stdin::write_with` and the same codec.
+///
+/// ### Examples
+/// ```ignore
+/// use sp1_zkvm::lib::codec::Postcard;
+///
+/// let data: MyStruct = sp1_zkvm::io::read_with::<Postcard, _>();
+/// ```
+pub fn read_with<C: Codec<T>, T>() -> T {
+    let vec = read_vec();
+    let (value, _) = C::decode(&vec).expect("deserialization failed");
+    value
+}
+
+/// Seed the zkVM random number generator.
+///
+/// After seeding, all randomness obtained through `getrandom` (e.g. `rand::thread_rng`) is a
+/// deterministic function of `seed`, so proofs of randomized programs are reproducible.
+///
+/// ### Examples
+/// ```ignore
+/// sp1_zkvm::io::set_rng_seed(&[0u8; 32]);
+/// ```
+pub fn set_rng_seed(seed: &[u8; 32]) {
+    unsafe {
+        sys_rand_seed(seed);
+    }
+}
+
+/// Read a random number generator seed from the input stream and use it to seed the zkVM
+/// random number generator.
+///
+/// The seed should be written on the host with `SP1Stdin::write_rng_seed`. If `commit` is true,
+/// the seed is also committed to the public values stream so that verifiers can audit it.
+///
+/// ### Examples
+/// ```ignore
+/// let seed = sp1_zkvm::io::read_rng_seed(true);
+/// ```
+pub fn read_rng_seed(commit: bool) -> [u8; 32] {
+    let seed: [u8; 32] = read_vec().try_into().expect("rng seed must be 32 bytes");
+    set_rng_seed(&seed);
+    if commit {
+        commit_slice(&seed);
+    }
+    seed
+}
+
 /// Commit a serializable object to the public values stream.
 ///
 /// ### Examples
@@ -115,6 +220,20 @@ pub fn commit<T: Serialize>(value: &T) {
     bincode::serialize_into(writer, value).expect("serialization failed");
 }
 
+/// Commit an object to the public values stream, encoding it with the codec `C`.
+///
+/// ### Examples
+/// ```ignore
+/// use sp1_zkvm::lib::codec::Abi;
+///
+/// let data: (u64, u64) = (1, 2);
+/// sp1_zkvm::io::commit_with::<Abi, _>(&data);
+/// ```
+pub fn commit_with<C: Codec<T>, T>(value: &T) {
+    let bytes = C::encode(value).expect("serialization failed");
+    commit_slice(&bytes);
+}
+
 /// Commit bytes to the public values stream.
 ///
 /// ### Examples
@@ -127,6 +246,32 @@ pub fn commit_slice(buf: &[u8]) {
     my_writer.write_all(buf).unwrap();
 }
 
+/// Commit the Merkle root of `leaves` to the public values stream.
+///
+/// Only the 32-byte root is committed, so the cost of verifying the proof does not grow with the
+/// number of leaves. The leaves themselves are returned to the host out-of-band in
+/// `SP1PublicValues::merkle_leaves`, where inclusion proofs for individual leaves can be generated
+/// against the root. See [`crate::merkle`] for the tree construction.
+///
+/// The host receives a single set of leaves per execution, so this function can only be called
+/// once. Subsequent calls panic.
+///
+/// ### Examples
+/// ```ignore
+/// let receipts: Vec<Vec<u8>> = vec![vec![1, 2], vec![3, 4]];
+/// let root = sp1_zkvm::io::commit_merkle_leaves(&receipts);
+/// ```
+pub fn commit_merkle_leaves<T: AsRef<[u8]>>(leaves: &[T]) -> [u8; 32] {
+    if MERKLE_LEAVES_COMMITTED.load(Ordering::Relaxed) {
+        panic!("Merkle leaves can only be committed once");
+    }
+    MERKLE_LEAVES_COMMITTED.store(true, Ordering::Relaxed);
+    let root = crate::merkle::merkle_root(leaves);
+    commit_slice(&root);
+    write(FD_MERKLE_LEAVES, &crate::merkle::encode_leaves(leaves));
+    root
+}
+
 /// Hint a serializable object to the hint stream.
 ///
 /// ### Examples
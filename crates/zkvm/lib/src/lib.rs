@@ -13,6 +13,8 @@ pub mod utils;
 #[cfg(feature = "verify")]
 pub mod verify;
 
+pub use sp1_codec::{self as codec, merkle};
+
 extern "C" {
     /// Halts the program with the given exit code.
     pub fn syscall_halt(exit_code: u8) -> !;
@@ -77,6 +79,9 @@ extern "C" {
     /// Reads the next element in the hint stream into the given buffer.
     pub fn syscall_hint_read(ptr: *mut u8, len: usize);
 
+    /// Seeds the random number generator backing `sys_rand`.
+    pub fn sys_rand_seed(seed: *const [u8; 32]);
+
     /// Allocates a buffer aligned to the given alignment.
     pub fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8;
 
@@ -1,4 +1,3 @@
 fn main() {
-    sp1_build::build_program("../program");
     sp1_build::build_program("../../fibonacci/program");
 }
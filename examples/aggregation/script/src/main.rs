@@ -1,24 +1,10 @@
 //! A simple example showing how to aggregate proofs of multiple programs with SP1.
 
-use sp1_sdk::{
-    include_elf, HashableKey, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1Stdin,
-    SP1VerifyingKey,
-};
-
-/// A program that aggregates the proofs of the simple program.
-const AGGREGATION_ELF: &[u8] = include_elf!("aggregation-program");
+use sp1_sdk::{aggregation::is_aggregated, include_elf, ProverClient, SP1Stdin};
 
 /// A program that just runs a simple computation.
 const FIBONACCI_ELF: &[u8] = include_elf!("fibonacci-program");
 
-/// An input to the aggregation program.
-///
-/// Consists of a proof and a verification key.
-struct AggregationInput {
-    pub proof: SP1ProofWithPublicValues,
-    pub vk: SP1VerifyingKey,
-}
-
 fn main() {
     // Setup the logger.
     sp1_sdk::utils::setup_logger();
@@ -27,7 +13,6 @@ fn main() {
     let client = ProverClient::new();
 
     // Setup the proving and verifying keys.
-    let (aggregation_pk, _) = client.setup(AGGREGATION_ELF);
     let (fibonacci_pk, fibonacci_vk) = client.setup(FIBONACCI_ELF);
 
     // Generate the fibonacci proofs.
@@ -47,35 +32,21 @@ fn main() {
         client.prove(&fibonacci_pk, stdin).compressed().run().expect("proving failed")
     });
 
-    // Setup the inputs to the aggregation program.
-    let input_1 = AggregationInput { proof: proof_1, vk: fibonacci_vk.clone() };
-    let input_2 = AggregationInput { proof: proof_2, vk: fibonacci_vk.clone() };
-    let input_3 = AggregationInput { proof: proof_3, vk: fibonacci_vk.clone() };
-    let inputs = vec![input_1, input_2, input_3];
-
-    // Aggregate the proofs.
-    tracing::info_span!("aggregate the proofs").in_scope(|| {
-        let mut stdin = SP1Stdin::new();
-
-        // Write the verification keys.
-        let vkeys = inputs.iter().map(|input| input.vk.hash_u32()).collect::<Vec<_>>();
-        stdin.write::<Vec<[u32; 8]>>(&vkeys);
-
-        // Write the public values.
-        let public_values =
-            inputs.iter().map(|input| input.proof.public_values.to_vec()).collect::<Vec<_>>();
-        stdin.write::<Vec<Vec<u8>>>(&public_values);
-
-        // Write the proofs.
-        //
-        // Note: this data will not actually be read by the aggregation program, instead it will be
-        // witnessed by the prover during the recursive aggregation process inside SP1 itself.
-        for input in inputs {
-            let SP1Proof::Compressed(proof) = input.proof.proof else { panic!() };
-            stdin.write_proof(*proof, input.vk.vk);
-        }
-
+    // Aggregate the proofs with the built-in aggregation program of the SDK, whose source is in
+    // `crates/sdk/programs/aggregation`.
+    let proofs = vec![
+        (proof_1, fibonacci_vk.clone()),
+        (proof_2, fibonacci_vk.clone()),
+        (proof_3, fibonacci_vk.clone()),
+    ];
+    let (aggregate, aggregation_vk) = tracing::info_span!("aggregate the proofs").in_scope(|| {
         // Generate the plonk bn254 proof.
-        client.prove(&aggregation_pk, stdin).plonk().run().expect("proving failed");
+        client.aggregate(&proofs).plonk().run().expect("proving failed")
     });
+    client.verify(&aggregate, &aggregation_vk).expect("verification failed");
+
+    // Check that each of the proofs was aggregated.
+    for (proof, vk) in &proofs {
+        assert!(is_aggregated(&aggregate, proof, vk).expect("invalid aggregate"));
+    }
 }
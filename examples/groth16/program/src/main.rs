@@ -3,13 +3,14 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use sp1_verifier::Groth16Verifier;
+use sp1_verifier::{Groth16BatchEntry, Groth16Verifier};
 
 pub fn main() {
     // Read the proof, public values, and vkey hash from the input stream.
     let proof = sp1_zkvm::io::read_vec();
     let sp1_public_values = sp1_zkvm::io::read_vec();
     let sp1_vkey_hash: String = sp1_zkvm::io::read();
+    let batch_size: usize = sp1_zkvm::io::read();
 
     // Verify the groth16 proof.
     let groth16_vk = *sp1_verifier::GROTH16_VK_BYTES;
@@ -25,4 +26,28 @@ pub fn main() {
             println!("Error verifying proof: {:?}", e);
         }
     }
+
+    // Compare verifying the proof `batch_size` times one by one against verifying it as a batch.
+    let entry = Groth16BatchEntry {
+        proof: &proof,
+        sp1_public_inputs: &sp1_public_values,
+        sp1_vkey_hash: &sp1_vkey_hash,
+    };
+    let entries = vec![entry; batch_size];
+
+    println!("cycle-tracker-start: verify-loop");
+    for entry in &entries {
+        Groth16Verifier::verify(
+            entry.proof,
+            entry.sp1_public_inputs,
+            entry.sp1_vkey_hash,
+            groth16_vk,
+        )
+        .expect("Proof is invalid");
+    }
+    println!("cycle-tracker-end: verify-loop");
+
+    println!("cycle-tracker-start: verify-batch");
+    Groth16Verifier::verify_batch(&entries, groth16_vk).expect("Batch is invalid");
+    println!("cycle-tracker-end: verify-batch");
 }
@@ -45,6 +45,9 @@ fn main() {
     stdin.write_vec(fibonacci_public_values);
     stdin.write(&vk);
 
+    // Write the number of copies of the proof to verify in the batch verification benchmark.
+    stdin.write(&8usize);
+
     // Create a `ProverClient`.
     let client = ProverClient::new();
 